@@ -0,0 +1,389 @@
+#![warn(
+    clippy::unwrap_used,
+    clippy::cast_lossless,
+    clippy::unimplemented,
+    clippy::indexing_slicing,
+    clippy::expect_used
+)]
+
+use geo::{Area, BooleanOps, BoundingRect, Contains, Intersects};
+use std::mem;
+
+/// The part of the geometry inside `polygon`, `None` when it's entirely outside.
+///
+/// Polygons and lines are clipped with `geo`'s boolean operations, which panic on some invalid
+/// polygons. Use `clip_to_rect` when clipping to a rectangle.
+pub fn clip(geometry: &geo::Geometry, polygon: &geo::Polygon) -> Option<geo::Geometry> {
+    let bounding_rect = geometry.bounding_rect()?;
+    if !polygon.bounding_rect()?.intersects(&bounding_rect) {
+        return None;
+    }
+    let clipped = match geometry {
+        geo::Geometry::Point(point) => {
+            if !polygon.intersects(point) {
+                return None;
+            }
+            geometry.clone()
+        }
+        geo::Geometry::MultiPoint(multi_point) => geo::Geometry::MultiPoint(
+            multi_point
+                .iter()
+                .filter(|point| polygon.intersects(*point))
+                .copied()
+                .collect(),
+        ),
+        geo::Geometry::Line(line) => polygon
+            .clip(
+                &geo::MultiLineString::new(vec![geo::LineString::new(vec![line.start, line.end])]),
+                false,
+            )
+            .into(),
+        geo::Geometry::LineString(line_string) => polygon
+            .clip(&geo::MultiLineString::new(vec![line_string.clone()]), false)
+            .into(),
+        geo::Geometry::MultiLineString(multi_line_string) => {
+            polygon.clip(multi_line_string, false).into()
+        }
+        geo::Geometry::Polygon(other) => polygon.intersection(other).into(),
+        geo::Geometry::MultiPolygon(multi_polygon) => geo::MultiPolygon::new(vec![polygon.clone()])
+            .intersection(multi_polygon)
+            .into(),
+        geo::Geometry::Rect(rect) => polygon.intersection(&rect.to_polygon()).into(),
+        geo::Geometry::Triangle(triangle) => polygon.intersection(&triangle.to_polygon()).into(),
+        geo::Geometry::GeometryCollection(geometry_collection) => {
+            geo::Geometry::GeometryCollection(
+                geometry_collection
+                    .iter()
+                    .filter_map(|geometry| clip(geometry, polygon))
+                    .collect(),
+            )
+        }
+    };
+    // Features that only touch the polygon have nothing left
+    clipped.bounding_rect()?;
+    Some(clipped)
+}
+
+/// The part of the geometry inside `rect`, `None` when it's entirely outside.
+///
+/// Unlike `clip`, this can't panic. Each polygon ring is clipped on its own with the
+/// Sutherland–Hodgman algorithm, so what's left of a concave polygon can have edges running along
+/// the sides of the rectangle.
+pub fn clip_to_rect(geometry: &geo::Geometry, rect: &geo::Rect) -> Option<geo::Geometry> {
+    let bounding_rect = geometry.bounding_rect()?;
+    if !rect.intersects(&bounding_rect) {
+        return None;
+    }
+    if rect.contains(&bounding_rect) {
+        return Some(geometry.clone());
+    }
+    let clipped = match geometry {
+        geo::Geometry::Point(_) => geometry.clone(),
+        geo::Geometry::MultiPoint(multi_point) => geo::Geometry::MultiPoint(
+            multi_point
+                .iter()
+                .filter(|point| rect.intersects(*point))
+                .copied()
+                .collect(),
+        ),
+        geo::Geometry::Line(line) => {
+            clip_line_string_to_rect(&geo::LineString::new(vec![line.start, line.end]), rect).into()
+        }
+        geo::Geometry::LineString(line_string) => {
+            clip_line_string_to_rect(line_string, rect).into()
+        }
+        geo::Geometry::MultiLineString(multi_line_string) => geo::MultiLineString::new(
+            multi_line_string
+                .iter()
+                .flat_map(|line_string| clip_line_string_to_rect(line_string, rect))
+                .collect(),
+        )
+        .into(),
+        geo::Geometry::Polygon(polygon) => clip_polygon_to_rect(polygon, rect)?.into(),
+        geo::Geometry::MultiPolygon(multi_polygon) => geo::MultiPolygon::new(
+            multi_polygon
+                .iter()
+                .filter_map(|polygon| clip_polygon_to_rect(polygon, rect))
+                .collect(),
+        )
+        .into(),
+        geo::Geometry::Rect(other) => clip_polygon_to_rect(&other.to_polygon(), rect)?.into(),
+        geo::Geometry::Triangle(triangle) => {
+            clip_polygon_to_rect(&triangle.to_polygon(), rect)?.into()
+        }
+        geo::Geometry::GeometryCollection(geometry_collection) => {
+            geo::Geometry::GeometryCollection(
+                geometry_collection
+                    .iter()
+                    .filter_map(|geometry| clip_to_rect(geometry, rect))
+                    .collect(),
+            )
+        }
+    };
+    // Features that only touch the rectangle have nothing left
+    clipped.bounding_rect()?;
+    Some(clipped)
+}
+
+// Consecutive segments that stay in the rectangle are kept together in one line string.
+fn clip_line_string_to_rect(
+    line_string: &geo::LineString,
+    rect: &geo::Rect,
+) -> geo::MultiLineString {
+    let mut line_strings = vec![];
+    let mut coords: Vec<geo::Coord> = vec![];
+    for line in line_string.lines() {
+        let Some((start, end)) = clip_segment_to_rect(line.start, line.end, rect) else {
+            continue;
+        };
+        if coords.last() != Some(&start) {
+            if coords.len() > 1 {
+                line_strings.push(geo::LineString::new(mem::take(&mut coords)));
+            }
+            coords.clear();
+            coords.push(start);
+        }
+        coords.push(end);
+    }
+    if coords.len() > 1 {
+        line_strings.push(geo::LineString::new(coords));
+    }
+    geo::MultiLineString::new(line_strings)
+}
+
+// Liang–Barsky. Endpoints inside the rectangle are kept as is, so the segments of a line string
+// still join up.
+fn clip_segment_to_rect(
+    start: geo::Coord,
+    end: geo::Coord,
+    rect: &geo::Rect,
+) -> Option<(geo::Coord, geo::Coord)> {
+    if ![start.x, start.y, end.x, end.y]
+        .iter()
+        .all(|value| value.is_finite())
+    {
+        return None;
+    }
+    let delta = end - start;
+    let (mut t_start, mut t_end) = (0f64, 1f64);
+    for (p, q) in [
+        (-delta.x, start.x - rect.min().x),
+        (delta.x, rect.max().x - start.x),
+        (-delta.y, start.y - rect.min().y),
+        (delta.y, rect.max().y - start.y),
+    ] {
+        if p == 0. {
+            // Parallel to this side, and outside of it
+            if q < 0. {
+                return None;
+            }
+        } else if p < 0. {
+            t_start = t_start.max(q / p);
+        } else {
+            t_end = t_end.min(q / p);
+        }
+    }
+    if t_start > t_end {
+        return None;
+    }
+    Some((
+        if t_start > 0. {
+            start + delta * t_start
+        } else {
+            start
+        },
+        if t_end < 1. {
+            start + delta * t_end
+        } else {
+            end
+        },
+    ))
+}
+
+fn clip_polygon_to_rect(polygon: &geo::Polygon, rect: &geo::Rect) -> Option<geo::Polygon> {
+    Some(geo::Polygon::new(
+        clip_ring_to_rect(polygon.exterior(), rect)?,
+        polygon
+            .interiors()
+            .iter()
+            .filter_map(|ring| clip_ring_to_rect(ring, rect))
+            .collect(),
+    ))
+}
+
+fn clip_ring_to_rect(ring: &geo::LineString, rect: &geo::Rect) -> Option<geo::LineString> {
+    let (min, max) = (rect.min(), rect.max());
+    // Rings are closed, the last coordinate repeats the first
+    let mut coords = ring
+        .0
+        .split_last()
+        .map_or(&[][..], |(_, coords)| coords)
+        .iter()
+        .filter(|coord| coord.x.is_finite() && coord.y.is_finite())
+        .copied()
+        .collect::<Vec<_>>();
+    for side in [
+        Side::Left(min.x),
+        Side::Right(max.x),
+        Side::Bottom(min.y),
+        Side::Top(max.y),
+    ] {
+        coords = clip_ring_to_side(&coords, side);
+    }
+    let ring = geo::LineString::new(coords);
+    // Rings that only run along the sides of the rectangle have nothing left
+    if geo::Polygon::new(ring.clone(), vec![]).unsigned_area() > 0. {
+        Some(ring)
+    } else {
+        None
+    }
+}
+
+// A side of the rectangle, with the value of the coordinate it lies at.
+#[derive(Clone, Copy)]
+enum Side {
+    Left(f64),
+    Right(f64),
+    Bottom(f64),
+    Top(f64),
+}
+
+impl Side {
+    fn is_inside(self, coord: geo::Coord) -> bool {
+        match self {
+            Side::Left(x) => coord.x >= x,
+            Side::Right(x) => coord.x <= x,
+            Side::Bottom(y) => coord.y >= y,
+            Side::Top(y) => coord.y <= y,
+        }
+    }
+
+    // Where the segment, which has one end on each side, crosses the side.
+    fn crossing(self, start: geo::Coord, end: geo::Coord) -> geo::Coord {
+        match self {
+            Side::Left(x) | Side::Right(x) => geo::Coord {
+                x,
+                y: start.y + (end.y - start.y) * (x - start.x) / (end.x - start.x),
+            },
+            Side::Bottom(y) | Side::Top(y) => geo::Coord {
+                x: start.x + (end.x - start.x) * (y - start.y) / (end.y - start.y),
+                y,
+            },
+        }
+    }
+}
+
+fn clip_ring_to_side(coords: &[geo::Coord], side: Side) -> Vec<geo::Coord> {
+    let mut clipped = vec![];
+    let Some(&last) = coords.last() else {
+        return clipped;
+    };
+    let mut previous = last;
+    for &coord in coords {
+        match (side.is_inside(previous), side.is_inside(coord)) {
+            (true, true) => clipped.push(coord),
+            (true, false) => clipped.push(side.crossing(previous, coord)),
+            (false, true) => {
+                clipped.push(side.crossing(previous, coord));
+                clipped.push(coord);
+            }
+            (false, false) => {}
+        }
+        previous = coord;
+    }
+    clipped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo::{coord, line_string, point, polygon, Rect};
+
+    fn rect() -> Rect {
+        Rect::new(coord! { x: 0., y: 0. }, coord! { x: 10., y: 10. })
+    }
+
+    #[test]
+    fn geometry_inside_is_kept_as_is() {
+        let geometry = geo::Geometry::from(line_string![(x: 1., y: 1.), (x: 9., y: 9.)]);
+        assert_eq!(clip_to_rect(&geometry, &rect()), Some(geometry));
+    }
+
+    #[test]
+    fn geometry_outside_is_dropped() {
+        let geometry = geo::Geometry::from(point!(x: 11., y: 5.));
+        assert_eq!(clip_to_rect(&geometry, &rect()), None);
+    }
+
+    #[test]
+    fn line_string_is_split_where_it_leaves_the_rect() {
+        let geometry = geo::Geometry::from(line_string![
+            (x: 5., y: 5.),
+            (x: 15., y: 5.),
+            (x: 15., y: 8.),
+            (x: 5., y: 8.),
+        ]);
+        assert_eq!(
+            clip_to_rect(&geometry, &rect()),
+            Some(geo::Geometry::from(geo::MultiLineString::new(vec![
+                line_string![(x: 5., y: 5.), (x: 10., y: 5.)],
+                line_string![(x: 10., y: 8.), (x: 5., y: 8.)],
+            ])))
+        );
+    }
+
+    #[test]
+    fn polygon_is_cut_along_the_rect() {
+        let geometry = geo::Geometry::from(polygon![
+            (x: 5., y: 5.),
+            (x: 15., y: 5.),
+            (x: 15., y: 15.),
+            (x: 5., y: 15.),
+        ]);
+        let Some(geo::Geometry::Polygon(clipped)) = clip_to_rect(&geometry, &rect()) else {
+            panic!("Expected a polygon");
+        };
+        assert_eq!(clipped.unsigned_area(), 25.);
+        assert_eq!(
+            clipped.bounding_rect(),
+            Some(Rect::new(
+                coord! { x: 5., y: 5. },
+                coord! { x: 10., y: 10. }
+            ))
+        );
+    }
+
+    #[test]
+    fn holes_outside_the_rect_are_dropped() {
+        let geometry = geo::Geometry::from(polygon!(
+            exterior: [(x: -5., y: -5.), (x: 25., y: -5.), (x: 25., y: 5.), (x: -5., y: 5.)],
+            interiors: [[(x: 20., y: 0.), (x: 22., y: 0.), (x: 22., y: 2.), (x: 20., y: 2.)]],
+        ));
+        let Some(geo::Geometry::Polygon(clipped)) = clip_to_rect(&geometry, &rect()) else {
+            panic!("Expected a polygon");
+        };
+        assert!(clipped.interiors().is_empty());
+        assert_eq!(clipped.unsigned_area(), 50.);
+    }
+
+    #[test]
+    fn polygon_touching_the_rect_is_dropped() {
+        let geometry = geo::Geometry::from(polygon![
+            (x: 10., y: 0.),
+            (x: 20., y: 0.),
+            (x: 20., y: 10.),
+            (x: 10., y: 10.),
+        ]);
+        assert_eq!(clip_to_rect(&geometry, &rect()), None);
+    }
+
+    #[test]
+    fn non_finite_coordinates_are_dropped() {
+        let geometry = geo::Geometry::from(line_string![
+            (x: 5., y: 5.),
+            (x: f64::NAN, y: 5.),
+            (x: 20., y: 5.),
+        ]);
+        assert_eq!(clip_to_rect(&geometry, &rect()), None);
+    }
+}
@@ -90,6 +90,36 @@ pub enum Value {
     Null,
 }
 
+impl Value {
+    pub fn as_number(&self) -> Option<f64> {
+        match *self {
+            Value::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Label used to group features by a string or boolean property.
+    pub fn as_category(&self) -> Option<&str> {
+        match *self {
+            Value::String(ref s) => Some(s),
+            Value::Boolean(true) => Some("true"),
+            Value::Boolean(false) => Some("false"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Value::String(ref s) => write!(f, "{s}"),
+            Value::Number(n) => write!(f, "{n}"),
+            Value::Boolean(b) => write!(f, "{b}"),
+            Value::Null => write!(f, "null"),
+        }
+    }
+}
+
 pub type Properties = collections::HashMap<String, Value>;
 
 impl Feature {
@@ -200,6 +230,12 @@ impl FeatureCollection {
         }
     }
 
+    /// Move the features of `other` to the end of this collection.
+    pub fn append(&mut self, mut other: FeatureCollection) {
+        self.bounding_rect = option_rect_merge(self.bounding_rect, other.bounding_rect);
+        self.features.append(&mut other.features);
+    }
+
     pub fn geometry_iter(&self) -> impl Iterator<Item = &geo::Geometry> {
         self.features.iter().filter_map(|f| f.geometry.as_ref())
     }
@@ -218,6 +254,30 @@ impl FeatureCollection {
     pub fn recalculate_bounding_rect(&mut self) {
         self.bounding_rect = bounding_rect_from_features(&self.features);
     }
+
+    /// Sorted names of every property that holds a number in at least one feature.
+    pub fn numeric_property_names(&self) -> Vec<&str> {
+        self.features
+            .iter()
+            .flat_map(|feature| feature.properties.iter())
+            .filter(|(_, value)| value.as_number().is_some())
+            .map(|(name, _)| name.as_str())
+            .collect::<collections::BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Sorted names of every property that holds a string or boolean in at least one feature.
+    pub fn categorical_property_names(&self) -> Vec<&str> {
+        self.features
+            .iter()
+            .flat_map(|feature| feature.properties.iter())
+            .filter(|(_, value)| value.as_category().is_some())
+            .map(|(name, _)| name.as_str())
+            .collect::<collections::BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
 }
 
 fn bounding_rect_from_features(features: &[Feature]) -> Option<geo::Rect> {
@@ -260,11 +320,12 @@ fn rect_merge<T: geo::CoordFloat>(a: geo::Rect<T>, b: geo::Rect<T>) -> geo::Rect
     )
 }
 
-// The starting value is `1` so we can utilize `NonZeroU16`.
-static NEXT_ID: sync::atomic::AtomicU16 = sync::atomic::AtomicU16::new(1);
+// The starting value is `1` so we can utilize `NonZeroU64`. Streamed files can hold millions of
+// features, so a narrower type would wrap around.
+static NEXT_ID: sync::atomic::AtomicU64 = sync::atomic::AtomicU64::new(1);
 
 #[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd, Hash)]
-pub struct FeatureId(num::NonZeroU16);
+pub struct FeatureId(num::NonZeroU64);
 
 impl Default for FeatureId {
     fn default() -> Self {
@@ -278,7 +339,8 @@ impl FeatureId {
     }
 }
 
-fn new_id() -> num::NonZeroU16 {
-    // Unsafety: The starting ID is 1 and we always increment.
-    unsafe { num::NonZeroU16::new_unchecked(NEXT_ID.fetch_add(1, sync::atomic::Ordering::SeqCst)) }
+fn new_id() -> num::NonZeroU64 {
+    // Unsafety: The starting ID is 1 and we always increment, and a `u64` doesn't realistically
+    // overflow.
+    unsafe { num::NonZeroU64::new_unchecked(NEXT_ID.fetch_add(1, sync::atomic::Ordering::SeqCst)) }
 }
@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum ColumnType {
+    Number,
+    Boolean,
+    String,
+}
+
+pub(crate) struct Column<'a> {
+    pub name: &'a str,
+    pub column_type: ColumnType,
+}
+
+/// Columns for the properties of the features, sorted by name. A column is typed as a number or a
+/// boolean when all its non-null values are, otherwise its values are written as strings.
+pub(crate) fn infer_columns(
+    feature_collection: &geo_features::FeatureCollection,
+) -> Vec<Column<'_>> {
+    let mut column_types = BTreeMap::<&str, Option<ColumnType>>::new();
+    for feature in &feature_collection.features {
+        for (name, value) in &feature.properties {
+            let value_type = match value {
+                geo_features::Value::Number(_) => ColumnType::Number,
+                geo_features::Value::Boolean(_) => ColumnType::Boolean,
+                geo_features::Value::String(_) => ColumnType::String,
+                geo_features::Value::Null => {
+                    column_types.entry(name).or_insert(None);
+                    continue;
+                }
+            };
+            let column_type = column_types.entry(name).or_insert(None);
+            *column_type = match *column_type {
+                Some(column_type) if column_type != value_type => Some(ColumnType::String),
+                _ => Some(value_type),
+            };
+        }
+    }
+    column_types
+        .into_iter()
+        .map(|(name, column_type)| Column {
+            name,
+            // Columns with only null values
+            column_type: column_type.unwrap_or(ColumnType::String),
+        })
+        .collect()
+}
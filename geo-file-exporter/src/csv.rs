@@ -0,0 +1,131 @@
+use std::collections::BTreeSet;
+
+use geozero::ToWkt;
+
+// Names the CSV loader detects geometry columns by
+const WKT_COLUMNS: [&str; 4] = ["WKT", "geometry", "geom", "the_geom"];
+
+/// Writes one row per feature, with its geometry as WKT in the first column followed by a column
+/// for each property, sorted by name.
+pub(crate) fn export(
+    feature_collection: &geo_features::FeatureCollection,
+) -> Result<Vec<u8>, crate::Error> {
+    let columns = feature_collection
+        .features
+        .iter()
+        .flat_map(|feature| feature.properties.keys())
+        .collect::<BTreeSet<_>>();
+
+    let wkt_column = wkt_column(&columns);
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(
+        std::iter::once(wkt_column.as_str()).chain(columns.iter().map(|c| c.as_str())),
+    )?;
+    for feature in &feature_collection.features {
+        let wkt = match feature.geometry {
+            Some(ref geometry) => geometry.to_wkt()?,
+            None => String::new(),
+        };
+        let values = columns
+            .iter()
+            .map(|column| match feature.properties.get(*column) {
+                // Missing and null values are both left empty
+                None | Some(geo_features::Value::Null) => String::new(),
+                Some(value) => value.to_string(),
+            });
+        writer.write_record(std::iter::once(wkt).chain(values))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| crate::Error::Io(e.into_error()))
+}
+
+// The first name the loader detects that no property has. Column names are matched regardless of
+// case, so a `wkt` property would be mistaken for the geometry.
+fn wkt_column(columns: &BTreeSet<&String>) -> String {
+    let is_taken = |name: &str| {
+        columns
+            .iter()
+            .any(|column| column.eq_ignore_ascii_case(name))
+    };
+    WKT_COLUMNS
+        .into_iter()
+        .map(String::from)
+        .chain((2..).map(|n| format!("WKT_{n}")))
+        .find(|name| !is_taken(name))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let feature_collection = geo_features::FeatureCollection::from_features(vec![
+            geo_features::FeatureBuilder::new()
+                .with_geometry(geo::Point::new(1., 2.).into())
+                .with_properties(geo_features::Properties::from([
+                    ("name".into(), geo_features::Value::String("Lisbon".into())),
+                    ("pop".into(), geo_features::Value::Number(545000.)),
+                    (
+                        "wkt".into(),
+                        geo_features::Value::String("a property".into()),
+                    ),
+                ]))
+                .build(),
+            geo_features::FeatureBuilder::new()
+                .with_geometry(geo::LineString::from(vec![(0., 0.), (1., 1.)]).into())
+                .with_properties(geo_features::Properties::from([
+                    ("name".into(), geo_features::Value::String("Porto".into())),
+                    ("pop".into(), geo_features::Value::Null),
+                ]))
+                .build(),
+        ]);
+        let csv = export(&feature_collection)?;
+        assert!(csv.starts_with(b"geometry,name,pop,wkt\n"));
+
+        let loaded = geo_file_loader::load_file(
+            geo_file_loader::FileFormat::Csv,
+            csv.into(),
+            geo_file_loader::LoadOptions::default(),
+        )?;
+        let [lisbon, porto] = loaded.feature_collection.features.as_slice() else {
+            panic!("expected two features");
+        };
+        assert_eq!(lisbon.geometry, Some(geo::Point::new(1., 2.).into()));
+        assert!(
+            matches!(lisbon.properties.get("name"), Some(geo_features::Value::String(s)) if s == "Lisbon")
+        );
+        assert!(
+            matches!(lisbon.properties.get("pop"), Some(geo_features::Value::Number(n)) if *n == 545000.)
+        );
+        assert!(
+            matches!(lisbon.properties.get("wkt"), Some(geo_features::Value::String(s)) if s == "a property")
+        );
+        assert_eq!(
+            porto.geometry,
+            Some(geo::LineString::from(vec![(0., 0.), (1., 1.)]).into())
+        );
+        assert!(matches!(
+            porto.properties.get("pop"),
+            Some(geo_features::Value::Null)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn wkt_column_names() {
+        let taken = ["wkt".to_owned(), "Geometry".to_owned(), "geom".to_owned()];
+        assert_eq!(wkt_column(&taken.iter().collect()), "the_geom");
+        let taken = [
+            "wkt".to_owned(),
+            "geometry".to_owned(),
+            "geom".to_owned(),
+            "THE_GEOM".to_owned(),
+            "wkt_2".to_owned(),
+        ];
+        assert_eq!(wkt_column(&taken.iter().collect()), "WKT_3");
+    }
+}
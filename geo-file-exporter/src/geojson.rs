@@ -0,0 +1,46 @@
+use std::io::Write;
+
+use geozero::ToJson;
+use serde_json::Value as Json;
+
+/// Writes a `FeatureCollection`, with the properties of each feature.
+pub(crate) fn export(
+    feature_collection: &geo_features::FeatureCollection,
+) -> Result<Vec<u8>, crate::Error> {
+    let mut bytes = vec![];
+    write!(bytes, "{{\"type\":\"FeatureCollection\",\"features\":[")?;
+    for (i, feature) in feature_collection.features.iter().enumerate() {
+        if i > 0 {
+            write!(bytes, ",")?;
+        }
+        let geometry = match feature.geometry {
+            Some(ref geometry) => geometry.to_json()?,
+            None => "null".into(),
+        };
+        let properties = feature
+            .properties
+            .iter()
+            .map(|(key, value)| (key.clone(), value_to_json(value)))
+            .collect::<serde_json::Map<_, _>>();
+        write!(
+            bytes,
+            "{{\"type\":\"Feature\",\"geometry\":{},\"properties\":{}}}",
+            geometry,
+            serde_json::to_string(&properties)?,
+        )?;
+    }
+    write!(bytes, "]}}")?;
+    Ok(bytes)
+}
+
+// JSON has no representation for NaN and infinite numbers, they become `null`.
+fn value_to_json(value: &geo_features::Value) -> Json {
+    match value {
+        geo_features::Value::String(s) => Json::String(s.clone()),
+        geo_features::Value::Number(n) => {
+            serde_json::Number::from_f64(*n).map_or(Json::Null, Json::Number)
+        }
+        geo_features::Value::Boolean(b) => Json::Bool(*b),
+        geo_features::Value::Null => Json::Null,
+    }
+}
@@ -0,0 +1,309 @@
+// GeoPackage files are SQLite databases. SQLite can only write databases to the filesystem, so the
+// table is written to a temporary file which is then read back, which also means GeoPackage isn't
+// supported on the web.
+
+#[cfg(not(target_arch = "wasm32"))]
+use geozero::{CoordDimensions, ToWkb};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::columns::{infer_columns, Column, ColumnType};
+#[cfg(not(target_arch = "wasm32"))]
+use std::collections::HashSet;
+
+// Tables and rows every GeoPackage must have, including the two reserved undefined CRSs
+#[cfg(not(target_arch = "wasm32"))]
+const SCHEMA: &str = "
+PRAGMA application_id = 0x47504B47;
+PRAGMA user_version = 10300;
+CREATE TABLE gpkg_spatial_ref_sys (
+    srs_name TEXT NOT NULL,
+    srs_id INTEGER PRIMARY KEY,
+    organization TEXT NOT NULL,
+    organization_coordsys_id INTEGER NOT NULL,
+    definition TEXT NOT NULL,
+    description TEXT
+);
+CREATE TABLE gpkg_contents (
+    table_name TEXT NOT NULL PRIMARY KEY,
+    data_type TEXT NOT NULL,
+    identifier TEXT UNIQUE,
+    description TEXT DEFAULT '',
+    last_change DATETIME NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+    min_x DOUBLE,
+    min_y DOUBLE,
+    max_x DOUBLE,
+    max_y DOUBLE,
+    srs_id INTEGER,
+    CONSTRAINT fk_gc_r_srs_id FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys(srs_id)
+);
+CREATE TABLE gpkg_geometry_columns (
+    table_name TEXT NOT NULL,
+    column_name TEXT NOT NULL,
+    geometry_type_name TEXT NOT NULL,
+    srs_id INTEGER NOT NULL,
+    z TINYINT NOT NULL,
+    m TINYINT NOT NULL,
+    CONSTRAINT pk_geom_cols PRIMARY KEY (table_name, column_name),
+    CONSTRAINT fk_gc_tn FOREIGN KEY (table_name) REFERENCES gpkg_contents(table_name),
+    CONSTRAINT fk_gc_srs FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys (srs_id)
+);
+INSERT INTO gpkg_spatial_ref_sys VALUES
+    ('Undefined cartesian SRS', -1, 'NONE', -1, 'undefined', NULL),
+    ('Undefined geographic SRS', 0, 'NONE', 0, 'undefined', NULL);
+";
+
+#[cfg(not(target_arch = "wasm32"))]
+const PRIMARY_KEY_COLUMN: &str = "fid";
+#[cfg(not(target_arch = "wasm32"))]
+const GEOMETRY_COLUMN: &str = "geom";
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn export(
+    feature_collection: &geo_features::FeatureCollection,
+    layer_name: &str,
+    crs_epsg_code: u16,
+) -> Result<Vec<u8>, crate::Error> {
+    let file = tempfile::NamedTempFile::new()?;
+    let mut connection = rusqlite::Connection::open(file.path())?;
+    connection.execute_batch(SCHEMA)?;
+
+    let srs_id = i32::from(crs_epsg_code);
+    connection.execute(
+        "INSERT INTO gpkg_spatial_ref_sys VALUES (?1, ?2, 'EPSG', ?2, ?3, NULL)",
+        rusqlite::params![
+            format!("EPSG:{crs_epsg_code}"),
+            srs_id,
+            crs_definitions::from_code(crs_epsg_code).map_or("undefined", |def| def.wkt),
+        ],
+    )?;
+
+    let columns = infer_columns(feature_collection);
+    let sql_names = column_names(&columns);
+    let column_definitions = columns
+        .iter()
+        .zip(&sql_names)
+        .map(|(column, sql_name)| {
+            let sql_type = match column.column_type {
+                ColumnType::Number => "REAL",
+                ColumnType::Boolean => "BOOLEAN",
+                ColumnType::String => "TEXT",
+            };
+            format!(", {} {}", quote(sql_name), sql_type)
+        })
+        .collect::<String>();
+    connection.execute_batch(&format!(
+        "CREATE TABLE {} ({} INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL, {} {}{})",
+        quote(layer_name),
+        PRIMARY_KEY_COLUMN,
+        GEOMETRY_COLUMN,
+        geometry_type_name(feature_collection),
+        column_definitions,
+    ))?;
+    let bounding_rect = feature_collection.bounding_rect;
+    connection.execute(
+        "INSERT INTO gpkg_contents (table_name, data_type, identifier, min_x, min_y, max_x, max_y, srs_id) VALUES (?1, 'features', ?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            layer_name,
+            bounding_rect.map(|rect| rect.min().x),
+            bounding_rect.map(|rect| rect.min().y),
+            bounding_rect.map(|rect| rect.max().x),
+            bounding_rect.map(|rect| rect.max().y),
+            srs_id,
+        ],
+    )?;
+    connection.execute(
+        "INSERT INTO gpkg_geometry_columns VALUES (?1, ?2, ?3, ?4, 0, 0)",
+        rusqlite::params![
+            layer_name,
+            GEOMETRY_COLUMN,
+            geometry_type_name(feature_collection),
+            srs_id,
+        ],
+    )?;
+
+    let transaction = connection.transaction()?;
+    {
+        let placeholders = (0..=columns.len())
+            .map(|i| format!("?{}", i + 1))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let column_names = sql_names
+            .iter()
+            .map(|sql_name| format!(", {}", quote(sql_name)))
+            .collect::<String>();
+        let mut statement = transaction.prepare(&format!(
+            "INSERT INTO {} ({}{}) VALUES ({})",
+            quote(layer_name),
+            GEOMETRY_COLUMN,
+            column_names,
+            placeholders,
+        ))?;
+        for feature in &feature_collection.features {
+            let geometry = feature
+                .geometry
+                .as_ref()
+                .map(|geometry| {
+                    geometry.to_gpkg_wkb(CoordDimensions::xy(), Some(srs_id), Vec::new())
+                })
+                .transpose()?;
+            let values = columns
+                .iter()
+                .map(|column| match feature.properties.get(column.name) {
+                    None | Some(geo_features::Value::Null) => rusqlite::types::Value::Null,
+                    Some(geo_features::Value::Number(n)) => rusqlite::types::Value::Real(*n),
+                    Some(geo_features::Value::Boolean(b)) => {
+                        rusqlite::types::Value::Integer(i64::from(*b))
+                    }
+                    Some(geo_features::Value::String(s)) => rusqlite::types::Value::Text(s.clone()),
+                });
+            let row = std::iter::once(match geometry {
+                Some(wkb) => rusqlite::types::Value::Blob(wkb),
+                None => rusqlite::types::Value::Null,
+            })
+            .chain(values)
+            .collect::<Vec<_>>();
+            statement.execute(rusqlite::params_from_iter(row))?;
+        }
+    }
+    transaction.commit()?;
+    connection
+        .close()
+        .map_err(|(_, e)| crate::Error::Sqlite(e))?;
+
+    Ok(std::fs::read(file.path())?)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn export(
+    _feature_collection: &geo_features::FeatureCollection,
+    _layer_name: &str,
+    _crs_epsg_code: u16,
+) -> Result<Vec<u8>, crate::Error> {
+    Err(crate::Error::GeoPackageUnsupported)
+}
+
+// The common type of the geometries, or `GEOMETRY` when they're mixed.
+#[cfg(not(target_arch = "wasm32"))]
+fn geometry_type_name(feature_collection: &geo_features::FeatureCollection) -> &'static str {
+    let mut type_names = feature_collection
+        .features
+        .iter()
+        .filter_map(|feature| feature.geometry.as_ref())
+        .map(|geometry| match geometry {
+            geo::Geometry::Point(_) => "POINT",
+            geo::Geometry::Line(_) | geo::Geometry::LineString(_) => "LINESTRING",
+            geo::Geometry::Polygon(_) | geo::Geometry::Rect(_) | geo::Geometry::Triangle(_) => {
+                "POLYGON"
+            }
+            geo::Geometry::MultiPoint(_) => "MULTIPOINT",
+            geo::Geometry::MultiLineString(_) => "MULTILINESTRING",
+            geo::Geometry::MultiPolygon(_) => "MULTIPOLYGON",
+            geo::Geometry::GeometryCollection(_) => "GEOMETRYCOLLECTION",
+        });
+    let Some(first) = type_names.next() else {
+        return "GEOMETRY";
+    };
+    if type_names.all(|type_name| type_name == first) {
+        first
+    } else {
+        "GEOMETRY"
+    }
+}
+
+// SQLite column names are case-insensitive. Properties whose name is taken by the primary key, the
+// geometry, or an earlier property get a numeric suffix, so the `fid` property of a file loaded
+// from a GeoPackage is kept as `fid_1`.
+#[cfg(not(target_arch = "wasm32"))]
+fn column_names(columns: &[Column]) -> Vec<String> {
+    let mut used = HashSet::from([PRIMARY_KEY_COLUMN.to_owned(), GEOMETRY_COLUMN.to_owned()]);
+    columns
+        .iter()
+        .map(|column| {
+            let mut name = column.name.to_owned();
+            let mut suffix = 1;
+            while !used.insert(name.to_ascii_lowercase()) {
+                name = format!("{}_{suffix}", column.name);
+                suffix += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let feature_collection = geo_features::FeatureCollection::from_features(vec![
+            geo_features::FeatureBuilder::new()
+                .with_geometry(geo::Point::new(1., 2.).into())
+                .with_properties(geo_features::Properties::from([
+                    ("fid".into(), geo_features::Value::Number(7.)),
+                    (
+                        "GEOM".into(),
+                        geo_features::Value::String("a property".into()),
+                    ),
+                    ("name".into(), geo_features::Value::String("Lisbon".into())),
+                ]))
+                .build(),
+            geo_features::FeatureBuilder::new()
+                .with_geometry(geo::Point::new(3., 4.).into())
+                .with_properties(geo_features::Properties::from([(
+                    "name".into(),
+                    geo_features::Value::Null,
+                )]))
+                .build(),
+        ]);
+        let bytes = export(&feature_collection, "cities", 4326)?;
+
+        let loaded = geo_file_loader::load_file(
+            geo_file_loader::FileFormat::GeoPackage,
+            bytes.into(),
+            geo_file_loader::LoadOptions::default(),
+        )?;
+        assert_eq!(loaded.crs_epsg_code, Some(4326));
+        let [lisbon, unnamed] = loaded.feature_collection.features.as_slice() else {
+            panic!("expected two features");
+        };
+        assert_eq!(lisbon.geometry, Some(geo::Point::new(1., 2.).into()));
+        assert!(matches!(
+            lisbon.properties.get("fid"),
+            Some(geo_features::Value::Number(n)) if *n == 1.
+        ));
+        assert!(matches!(
+            lisbon.properties.get("fid_1"),
+            Some(geo_features::Value::Number(n)) if *n == 7.
+        ));
+        assert!(
+            matches!(lisbon.properties.get("GEOM_1"), Some(geo_features::Value::String(s)) if s == "a property")
+        );
+        assert!(
+            matches!(lisbon.properties.get("name"), Some(geo_features::Value::String(s)) if s == "Lisbon")
+        );
+        assert_eq!(unnamed.geometry, Some(geo::Point::new(3., 4.).into()));
+        assert!(matches!(
+            unnamed.properties.get("name"),
+            Some(geo_features::Value::Null)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn colliding_column_names() {
+        let columns = ["FID", "fid_1", "Name", "name"].map(|name| Column {
+            name,
+            column_type: ColumnType::String,
+        });
+        assert_eq!(
+            column_names(&columns),
+            ["FID_1", "fid_1_1", "Name", "name_1"]
+        );
+    }
+}
@@ -0,0 +1,93 @@
+#![warn(
+    clippy::unwrap_used,
+    clippy::cast_lossless,
+    clippy::unimplemented,
+    clippy::indexing_slicing,
+    clippy::expect_used
+)]
+
+mod columns;
+mod csv;
+mod geojson;
+mod geopackage;
+mod shapefile;
+mod wkt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FileFormat {
+    GeoJson,
+    Wkt,
+    Csv,
+    Shapefile,
+    GeoPackage,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("{0}")]
+    Geozero(#[from] geozero::error::GeozeroError),
+    #[error("{0}")]
+    Csv(#[from] ::csv::Error),
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+    #[error("{0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[cfg(not(target_arch = "wasm32"))]
+    #[error("{0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("No geometry to export")]
+    NoGeometry,
+    #[error("Too many features to export to a Shapefile")]
+    ShapefileTooLarge,
+    #[error("GeoPackage files are not supported on the web")]
+    GeoPackageUnsupported,
+}
+
+impl FileFormat {
+    pub const ALL: [FileFormat; 5] = [
+        FileFormat::GeoJson,
+        FileFormat::Wkt,
+        FileFormat::Csv,
+        FileFormat::Shapefile,
+        FileFormat::GeoPackage,
+    ];
+
+    pub const fn display_name(self) -> &'static str {
+        match self {
+            Self::GeoJson => "GeoJSON",
+            Self::Wkt => "WKT",
+            Self::Csv => "CSV (WKT geometry)",
+            Self::Shapefile => "Shapefile (zipped)",
+            Self::GeoPackage => "GeoPackage",
+        }
+    }
+
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::GeoJson => "geojson",
+            Self::Wkt => "wkt",
+            Self::Csv => "csv",
+            Self::Shapefile => "zip",
+            Self::GeoPackage => "gpkg",
+        }
+    }
+}
+
+/// Encodes the features of a layer. The name of the layer is used for the files inside a zipped
+/// Shapefile and for the GeoPackage table, and the CRS is recorded by the formats which support it.
+pub fn export_file(
+    file_format: FileFormat,
+    feature_collection: &geo_features::FeatureCollection,
+    layer_name: &str,
+    crs_epsg_code: u16,
+) -> Result<Vec<u8>, Error> {
+    match file_format {
+        FileFormat::GeoJson => geojson::export(feature_collection),
+        FileFormat::Wkt => wkt::export(feature_collection),
+        FileFormat::Csv => csv::export(feature_collection),
+        FileFormat::Shapefile => shapefile::export(feature_collection, layer_name, crs_epsg_code),
+        FileFormat::GeoPackage => geopackage::export(feature_collection, layer_name, crs_epsg_code),
+    }
+}
@@ -0,0 +1,566 @@
+// A Shapefile only holds one type of shape, so features are split into a set of files for each
+// type: the `.shp` shapes, the `.shx` index of their offsets, the `.dbf` attribute table, the `.prj`
+// CRS definition, and the `.cpg` encoding of the attributes. All of them get zipped together.
+
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, Write};
+
+use geo::orient::{Direction, Orient};
+
+use crate::columns::{infer_columns, Column, ColumnType};
+
+const FILE_CODE: i32 = 9994;
+const VERSION: i32 = 1000;
+const HEADER_LENGTH: usize = 100;
+// Character and numeric fields of dBase files are at most this long
+const MAX_FIELD_LENGTH: usize = 254;
+const MAX_FIELD_NAME_LENGTH: usize = 10;
+const MAX_DECIMALS: usize = 15;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum ShapeType {
+    Point = 1,
+    PolyLine = 3,
+    Polygon = 5,
+    MultiPoint = 8,
+}
+
+impl ShapeType {
+    fn file_name_suffix(self) -> &'static str {
+        match self {
+            ShapeType::Point => "points",
+            ShapeType::PolyLine => "lines",
+            ShapeType::Polygon => "polygons",
+            ShapeType::MultiPoint => "multipoints",
+        }
+    }
+}
+
+enum Shape {
+    Point(geo::Coord),
+    MultiPoint(Vec<geo::Coord>),
+    PolyLine(Vec<Vec<geo::Coord>>),
+    // Exterior rings are clockwise, holes are counter-clockwise
+    Polygon(Vec<Vec<geo::Coord>>),
+}
+
+impl Shape {
+    fn shape_type(&self) -> ShapeType {
+        match self {
+            Shape::Point(_) => ShapeType::Point,
+            Shape::MultiPoint(_) => ShapeType::MultiPoint,
+            Shape::PolyLine(_) => ShapeType::PolyLine,
+            Shape::Polygon(_) => ShapeType::Polygon,
+        }
+    }
+
+    fn coords(&self) -> Box<dyn Iterator<Item = &geo::Coord> + '_> {
+        match self {
+            Shape::Point(coord) => Box::new(std::iter::once(coord)),
+            Shape::MultiPoint(coords) => Box::new(coords.iter()),
+            Shape::PolyLine(parts) | Shape::Polygon(parts) => Box::new(parts.iter().flatten()),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.coords().next().is_none()
+    }
+}
+
+pub(crate) fn export(
+    feature_collection: &geo_features::FeatureCollection,
+    layer_name: &str,
+    crs_epsg_code: u16,
+) -> Result<Vec<u8>, crate::Error> {
+    // Shapes of each type, along with the index of their feature
+    let mut shapes_by_type = BTreeMap::<ShapeType, Vec<(Shape, usize)>>::new();
+    for (index, feature) in feature_collection.features.iter().enumerate() {
+        let mut shapes = vec![];
+        if let Some(ref geometry) = feature.geometry {
+            push_shapes(geometry, &mut shapes);
+        }
+        for shape in shapes.into_iter().filter(|shape| !shape.is_empty()) {
+            shapes_by_type
+                .entry(shape.shape_type())
+                .or_default()
+                .push((shape, index));
+        }
+    }
+    if shapes_by_type.is_empty() {
+        return Err(crate::Error::NoGeometry);
+    }
+
+    let columns = infer_columns(feature_collection);
+    let prj = crs_definitions::from_code(crs_epsg_code).map(|def| def.wkt);
+    let base_name = file_name(layer_name);
+    let is_split = shapes_by_type.len() > 1;
+
+    let mut zip = zip::ZipWriter::new(io::Cursor::new(vec![]));
+    for (shape_type, shapes) in &shapes_by_type {
+        let name = if is_split {
+            format!("{}_{}", base_name, shape_type.file_name_suffix())
+        } else {
+            base_name.clone()
+        };
+        let (shp, shx) = write_shp_and_shx(*shape_type, shapes)?;
+        let features = shapes
+            .iter()
+            .filter_map(|(_, index)| feature_collection.features.get(*index));
+        let dbf = write_dbf(&columns, features)?;
+
+        let options = zip::write::FileOptions::default();
+        zip.start_file(format!("{name}.shp"), options)?;
+        zip.write_all(&shp)?;
+        zip.start_file(format!("{name}.shx"), options)?;
+        zip.write_all(&shx)?;
+        zip.start_file(format!("{name}.dbf"), options)?;
+        zip.write_all(&dbf)?;
+        zip.start_file(format!("{name}.cpg"), options)?;
+        zip.write_all(b"UTF-8")?;
+        if let Some(prj) = prj {
+            zip.start_file(format!("{name}.prj"), options)?;
+            zip.write_all(prj.as_bytes())?;
+        }
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+// Geometry collections are flattened, so each of their geometries ends up in the file of its type.
+fn push_shapes(geometry: &geo::Geometry, shapes: &mut Vec<Shape>) {
+    match geometry {
+        geo::Geometry::Point(point) => shapes.push(Shape::Point(point.0)),
+        geo::Geometry::MultiPoint(multi_point) => shapes.push(Shape::MultiPoint(
+            multi_point.iter().map(|point| point.0).collect(),
+        )),
+        geo::Geometry::Line(line) => shapes.push(Shape::PolyLine(vec![vec![line.start, line.end]])),
+        geo::Geometry::LineString(line_string) => {
+            shapes.push(Shape::PolyLine(vec![line_string.0.clone()]))
+        }
+        geo::Geometry::MultiLineString(multi_line_string) => shapes.push(Shape::PolyLine(
+            multi_line_string
+                .iter()
+                .map(|line_string| line_string.0.clone())
+                .collect(),
+        )),
+        geo::Geometry::Polygon(polygon) => shapes.push(Shape::Polygon(rings(polygon))),
+        geo::Geometry::MultiPolygon(multi_polygon) => shapes.push(Shape::Polygon(
+            multi_polygon.iter().flat_map(rings).collect(),
+        )),
+        geo::Geometry::Rect(rect) => shapes.push(Shape::Polygon(rings(&rect.to_polygon()))),
+        geo::Geometry::Triangle(triangle) => {
+            shapes.push(Shape::Polygon(rings(&triangle.to_polygon())))
+        }
+        geo::Geometry::GeometryCollection(geometry_collection) => {
+            for geometry in geometry_collection {
+                push_shapes(geometry, shapes);
+            }
+        }
+    }
+}
+
+fn rings(polygon: &geo::Polygon) -> Vec<Vec<geo::Coord>> {
+    let polygon = polygon.orient(Direction::Reversed);
+    let (exterior, interiors) = polygon.into_inner();
+    std::iter::once(exterior)
+        .chain(interiors)
+        .map(|ring| ring.0)
+        .collect()
+}
+
+fn write_shp_and_shx(
+    shape_type: ShapeType,
+    shapes: &[(Shape, usize)],
+) -> Result<(Vec<u8>, Vec<u8>), crate::Error> {
+    let mut records = vec![];
+    let mut index = vec![];
+    let mut bbox: Option<geo::Rect> = None;
+    for (record_number, (shape, _)) in shapes.iter().enumerate() {
+        let content = shape_content(shape)?;
+        let offset = HEADER_LENGTH + records.len();
+        write_i32_be(&mut records, to_i32(record_number + 1)?);
+        write_i32_be(&mut records, to_i32(content.len() / 2)?);
+        records.extend_from_slice(&content);
+        write_i32_be(&mut index, to_i32(offset / 2)?);
+        write_i32_be(&mut index, to_i32(content.len() / 2)?);
+        bbox = merge_rects(bbox, shape_bbox(shape));
+    }
+
+    let mut shp = header(shape_type, HEADER_LENGTH + records.len(), bbox)?;
+    shp.extend_from_slice(&records);
+    let mut shx = header(shape_type, HEADER_LENGTH + index.len(), bbox)?;
+    shx.extend_from_slice(&index);
+    Ok((shp, shx))
+}
+
+// Lengths in the headers are counted in 16-bit words.
+fn header(
+    shape_type: ShapeType,
+    file_length: usize,
+    bbox: Option<geo::Rect>,
+) -> Result<Vec<u8>, crate::Error> {
+    let mut header = Vec::with_capacity(HEADER_LENGTH);
+    write_i32_be(&mut header, FILE_CODE);
+    header.extend_from_slice(&[0; 20]);
+    write_i32_be(&mut header, to_i32(file_length / 2)?);
+    write_i32_le(&mut header, VERSION);
+    write_i32_le(&mut header, shape_type as i32);
+    write_bbox(&mut header, bbox);
+    // Z and M ranges, unused
+    header.extend_from_slice(&[0; 32]);
+    Ok(header)
+}
+
+fn shape_content(shape: &Shape) -> Result<Vec<u8>, crate::Error> {
+    let mut content = vec![];
+    write_i32_le(&mut content, shape.shape_type() as i32);
+    match shape {
+        Shape::Point(coord) => write_coord(&mut content, coord),
+        Shape::MultiPoint(coords) => {
+            write_bbox(&mut content, shape_bbox(shape));
+            write_i32_le(&mut content, to_i32(coords.len())?);
+            for coord in coords {
+                write_coord(&mut content, coord);
+            }
+        }
+        Shape::PolyLine(parts) | Shape::Polygon(parts) => {
+            write_bbox(&mut content, shape_bbox(shape));
+            write_i32_le(&mut content, to_i32(parts.len())?);
+            write_i32_le(&mut content, to_i32(parts.iter().map(Vec::len).sum())?);
+            let mut part_start = 0;
+            for part in parts {
+                write_i32_le(&mut content, to_i32(part_start)?);
+                part_start += part.len();
+            }
+            for coord in parts.iter().flatten() {
+                write_coord(&mut content, coord);
+            }
+        }
+    }
+    Ok(content)
+}
+
+fn shape_bbox(shape: &Shape) -> Option<geo::Rect> {
+    shape
+        .coords()
+        .map(|coord| geo::Rect::new(*coord, *coord))
+        .reduce(|a, b| merge_rects(Some(a), Some(b)).unwrap_or(a))
+}
+
+fn merge_rects(a: Option<geo::Rect>, b: Option<geo::Rect>) -> Option<geo::Rect> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(geo::Rect::new(
+            geo::coord! { x: a.min().x.min(b.min().x), y: a.min().y.min(b.min().y) },
+            geo::coord! { x: a.max().x.max(b.max().x), y: a.max().y.max(b.max().y) },
+        )),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+fn write_bbox(bytes: &mut Vec<u8>, bbox: Option<geo::Rect>) {
+    let (min, max) = bbox.map_or((geo::Coord::zero(), geo::Coord::zero()), |bbox| {
+        (bbox.min(), bbox.max())
+    });
+    write_coord(bytes, &min);
+    write_coord(bytes, &max);
+}
+
+fn write_coord(bytes: &mut Vec<u8>, coord: &geo::Coord) {
+    bytes.extend_from_slice(&coord.x.to_le_bytes());
+    bytes.extend_from_slice(&coord.y.to_le_bytes());
+}
+
+fn write_i32_be(bytes: &mut Vec<u8>, n: i32) {
+    bytes.extend_from_slice(&n.to_be_bytes());
+}
+
+fn write_i32_le(bytes: &mut Vec<u8>, n: i32) {
+    bytes.extend_from_slice(&n.to_le_bytes());
+}
+
+fn to_i32(n: usize) -> Result<i32, crate::Error> {
+    i32::try_from(n).map_err(|_| crate::Error::ShapefileTooLarge)
+}
+
+struct Field {
+    name: Vec<u8>,
+    field_type: u8,
+    length: usize,
+    decimals: usize,
+    // Values of each record, blank values are null
+    values: Vec<Option<String>>,
+}
+
+// dBase III table, with each field as wide as its widest value.
+fn write_dbf<'a>(
+    columns: &[Column],
+    features: impl Iterator<Item = &'a geo_features::Feature> + Clone,
+) -> Result<Vec<u8>, crate::Error> {
+    let field_names = field_names(columns);
+    let mut fields = columns
+        .iter()
+        .zip(field_names)
+        .map(|(column, name)| build_field(column, name, features.clone()))
+        .collect::<Vec<_>>();
+    let record_count = features.clone().count();
+    // Some readers can't open tables without fields
+    if fields.is_empty() {
+        fields.push(Field {
+            name: b"FID".to_vec(),
+            field_type: b'N',
+            length: record_count.to_string().len(),
+            decimals: 0,
+            values: (0..record_count).map(|i| Some(i.to_string())).collect(),
+        });
+    }
+
+    let header_length = 32 + 32 * fields.len() + 1;
+    let record_length = 1 + fields.iter().map(|field| field.length).sum::<usize>();
+    let mut dbf = vec![];
+    dbf.push(0x03);
+    // Date of the last update, left empty as there's no clock on the web
+    dbf.extend_from_slice(&[0, 0, 0]);
+    dbf.extend_from_slice(
+        &u32::try_from(record_count)
+            .map_err(|_| crate::Error::ShapefileTooLarge)?
+            .to_le_bytes(),
+    );
+    dbf.extend_from_slice(
+        &u16::try_from(header_length)
+            .map_err(|_| crate::Error::ShapefileTooLarge)?
+            .to_le_bytes(),
+    );
+    dbf.extend_from_slice(
+        &u16::try_from(record_length)
+            .map_err(|_| crate::Error::ShapefileTooLarge)?
+            .to_le_bytes(),
+    );
+    dbf.extend_from_slice(&[0; 20]);
+    for field in &fields {
+        let mut name = field.name.clone();
+        name.resize(11, 0);
+        dbf.extend_from_slice(&name);
+        dbf.push(field.field_type);
+        dbf.extend_from_slice(&[0; 4]);
+        // Both are at most `MAX_FIELD_LENGTH`
+        dbf.push(u8::try_from(field.length).unwrap_or(u8::MAX));
+        dbf.push(u8::try_from(field.decimals).unwrap_or(u8::MAX));
+        dbf.extend_from_slice(&[0; 14]);
+    }
+    dbf.push(0x0D);
+    for record in 0..record_count {
+        // Not deleted
+        dbf.push(b' ');
+        for field in &fields {
+            let value = field.values.get(record).and_then(Option::as_deref);
+            let value = value.unwrap_or(match field.field_type {
+                b'L' => "?",
+                _ => "",
+            });
+            // Numbers are right-aligned, text is left-aligned. Lengths are in bytes, not characters.
+            let padding = vec![b' '; field.length.saturating_sub(value.len())];
+            if field.field_type == b'N' {
+                dbf.extend_from_slice(&padding);
+                dbf.extend_from_slice(value.as_bytes());
+            } else {
+                dbf.extend_from_slice(value.as_bytes());
+                dbf.extend_from_slice(&padding);
+            }
+        }
+    }
+    dbf.push(0x1A);
+    Ok(dbf)
+}
+
+fn build_field<'a>(
+    column: &Column,
+    name: Vec<u8>,
+    features: impl Iterator<Item = &'a geo_features::Feature>,
+) -> Field {
+    let values = features.map(|feature| feature.properties.get(column.name));
+    match column.column_type {
+        ColumnType::Number => {
+            let numbers = values
+                .map(|value| {
+                    value
+                        .and_then(geo_features::Value::as_number)
+                        .filter(|n| n.is_finite())
+                })
+                .collect::<Vec<_>>();
+            // As many decimals as the most precise number, which `Display` prints exactly
+            let decimals = numbers
+                .iter()
+                .flatten()
+                .filter_map(|n| n.to_string().split_once('.').map(|(_, d)| d.len()))
+                .max()
+                .unwrap_or(0)
+                .min(MAX_DECIMALS);
+            let values = numbers
+                .into_iter()
+                .map(|n| {
+                    n.map(|n| format!("{n:.decimals$}"))
+                        .filter(|n| n.len() <= MAX_FIELD_LENGTH)
+                })
+                .collect::<Vec<_>>();
+            Field {
+                name,
+                field_type: b'N',
+                length: field_length(&values),
+                decimals,
+                values,
+            }
+        }
+        ColumnType::Boolean => Field {
+            name,
+            field_type: b'L',
+            length: 1,
+            decimals: 0,
+            values: values
+                .map(|value| match value {
+                    Some(geo_features::Value::Boolean(true)) => Some("T".into()),
+                    Some(geo_features::Value::Boolean(false)) => Some("F".into()),
+                    _ => None,
+                })
+                .collect(),
+        },
+        ColumnType::String => {
+            let values = values
+                .map(|value| match value {
+                    None | Some(geo_features::Value::Null) => None,
+                    Some(value) => Some(truncate(&value.to_string(), MAX_FIELD_LENGTH).into()),
+                })
+                .collect::<Vec<_>>();
+            Field {
+                name,
+                field_type: b'C',
+                length: field_length(&values),
+                decimals: 0,
+                values,
+            }
+        }
+    }
+}
+
+fn field_length(values: &[Option<String>]) -> usize {
+    values
+        .iter()
+        .flatten()
+        .map(String::len)
+        .max()
+        .unwrap_or(0)
+        .max(1)
+}
+
+// Field names are at most 10 bytes, truncated names which collide get a numeric suffix.
+fn field_names(columns: &[Column]) -> Vec<Vec<u8>> {
+    let mut used = HashSet::new();
+    columns
+        .iter()
+        .map(|column| {
+            let mut name = truncate(column.name, MAX_FIELD_NAME_LENGTH).to_string();
+            let mut suffix = 1;
+            while !used.insert(name.to_ascii_lowercase()) {
+                let suffix_str = format!("_{suffix}");
+                name = format!(
+                    "{}{}",
+                    truncate(
+                        column.name,
+                        MAX_FIELD_NAME_LENGTH.saturating_sub(suffix_str.len())
+                    ),
+                    suffix_str
+                );
+                suffix += 1;
+            }
+            name.into_bytes()
+        })
+        .collect()
+}
+
+// Truncates to at most `max_length` bytes, without splitting a character.
+fn truncate(s: &str, max_length: usize) -> &str {
+    let mut end = s.len().min(max_length);
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    s.get(..end).unwrap_or_default()
+}
+
+// Characters which aren't allowed in file names on some platforms are replaced.
+fn file_name(layer_name: &str) -> String {
+    let name = layer_name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect::<String>();
+    match name.trim() {
+        "" => "layer".into(),
+        name => name.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let feature_collection = geo_features::FeatureCollection::from_features(vec![
+            geo_features::FeatureBuilder::new()
+                .with_geometry(geo::Point::new(1., 2.).into())
+                .with_properties(geo_features::Properties::from([
+                    (
+                        "name".into(),
+                        geo_features::Value::String("São Paulo".into()),
+                    ),
+                    ("population_2020".into(), geo_features::Value::Number(12.25)),
+                    ("capital".into(), geo_features::Value::Boolean(false)),
+                ]))
+                .build(),
+            geo_features::FeatureBuilder::new()
+                .with_geometry(geo::Point::new(3., 4.).into())
+                .with_properties(geo_features::Properties::from([
+                    (
+                        "name".into(),
+                        geo_features::Value::String("Brasília".into()),
+                    ),
+                    ("population_2020".into(), geo_features::Value::Null),
+                    ("capital".into(), geo_features::Value::Boolean(true)),
+                ]))
+                .build(),
+        ]);
+        let zip = export(&feature_collection, "cities", 4326)?;
+
+        let loaded = geo_file_loader::load_file(
+            geo_file_loader::FileFormat::Shapefile,
+            zip.into(),
+            geo_file_loader::LoadOptions::default(),
+        )?;
+        let [sao_paulo, brasilia] = loaded.feature_collection.features.as_slice() else {
+            panic!("expected two features");
+        };
+        assert_eq!(sao_paulo.geometry, Some(geo::Point::new(1., 2.).into()));
+        assert!(
+            matches!(sao_paulo.properties.get("name"), Some(geo_features::Value::String(s)) if s == "São Paulo")
+        );
+        assert!(matches!(
+            sao_paulo.properties.get("population"),
+            Some(geo_features::Value::Number(n)) if *n == 12.25
+        ));
+        assert!(matches!(
+            sao_paulo.properties.get("capital"),
+            Some(geo_features::Value::Boolean(false))
+        ));
+        assert_eq!(brasilia.geometry, Some(geo::Point::new(3., 4.).into()));
+        assert!(matches!(
+            brasilia.properties.get("population"),
+            Some(geo_features::Value::Null)
+        ));
+        assert!(matches!(
+            brasilia.properties.get("capital"),
+            Some(geo_features::Value::Boolean(true))
+        ));
+        Ok(())
+    }
+}
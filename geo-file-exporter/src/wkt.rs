@@ -0,0 +1,19 @@
+use geozero::ToWkt;
+
+/// Writes the geometries as a single `GEOMETRYCOLLECTION`, or the geometry itself when there's
+/// only one, so the file can be loaded back. Properties are dropped.
+pub(crate) fn export(
+    feature_collection: &geo_features::FeatureCollection,
+) -> Result<Vec<u8>, crate::Error> {
+    let mut geometries = feature_collection
+        .features
+        .iter()
+        .filter_map(|feature| feature.geometry.clone())
+        .collect::<Vec<_>>();
+    let geometry = if geometries.len() == 1 {
+        geometries.remove(0)
+    } else {
+        geo::Geometry::GeometryCollection(geo::GeometryCollection(geometries))
+    };
+    Ok(geometry.to_wkt()?.into_bytes())
+}
@@ -0,0 +1,173 @@
+// Matching of WKT CRS definitions (e.g. from a shapefile's .prj file) to EPSG codes.
+
+// ESRI names for common CRSs. ESRI-flavored WKT (what most .prj files contain) omits the
+// `AUTHORITY` clause, so the CRS name is all there is to go on.
+const ESRI_NAMES: [(&str, u16); 12] = [
+    ("GCS_WGS_1984", 4326),
+    ("WGS 84", 4326),
+    ("GCS_North_American_1983", 4269),
+    ("NAD83", 4269),
+    ("GCS_North_American_1927", 4267),
+    ("NAD27", 4267),
+    ("GCS_ETRS_1989", 4258),
+    ("ETRS89", 4258),
+    ("WGS_1984_Web_Mercator_Auxiliary_Sphere", 3857),
+    ("WGS 84 / Pseudo-Mercator", 3857),
+    ("ETRS_1989_LAEA", 3035),
+    ("British_National_Grid", 27700),
+];
+
+/// EPSG code of a WKT CRS definition, `None` if it can't be determined.
+pub(crate) fn epsg_code_from_wkt(wkt: &str) -> Option<u16> {
+    epsg_code_from_authority(wkt).or_else(|| epsg_code_from_name(crs_name(wkt)?))
+}
+
+/// EPSG code of a PROJJSON CRS definition (e.g. from GeoParquet metadata), `None` if it can't be
+/// determined.
+pub(crate) fn epsg_code_from_projjson(projjson: &serde_json::Value) -> Option<u16> {
+    projjson
+        .get("id")
+        .or_else(|| projjson.get("ids")?.as_array()?.first())
+        .and_then(epsg_code_from_projjson_id)
+        .or_else(|| epsg_code_from_name(projjson.get("name")?.as_str()?))
+}
+
+// `{"authority": "EPSG", "code": 4326}`, the code can also be a string
+fn epsg_code_from_projjson_id(id: &serde_json::Value) -> Option<u16> {
+    let code = id.get("code")?;
+    let code = match code.as_u64() {
+        Some(code) => code.to_string(),
+        None => code.as_str()?.to_string(),
+    };
+    match (id.get("authority")?.as_str()?, code.as_str()) {
+        ("EPSG", code) => code.parse().ok(),
+        ("OGC", "CRS84") => Some(4326),
+        _ => None,
+    }
+}
+
+// Only the `AUTHORITY` of the root CRS counts, nested ones describe its datum, units, etc.
+fn epsg_code_from_authority(wkt: &str) -> Option<u16> {
+    const AUTHORITY: &str = "AUTHORITY[\"EPSG\",";
+    let (index, _) = wkt.rmatch_indices(AUTHORITY).next()?;
+    let (before, after) = wkt.split_at(index);
+    let depth = before
+        .matches('[')
+        .count()
+        .saturating_sub(before.matches(']').count());
+    if depth != 1 {
+        return None;
+    }
+    after
+        .get(AUTHORITY.len()..)?
+        .trim_start()
+        .trim_start_matches('"')
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+// `PROJCS["NAD_1983_UTM_Zone_10N",…]` → `NAD_1983_UTM_Zone_10N`
+fn crs_name(wkt: &str) -> Option<&str> {
+    let (_, rest) = wkt.split_once("[\"")?;
+    let (name, _) = rest.split_once('"')?;
+    Some(name)
+}
+
+fn epsg_code_from_name(name: &str) -> Option<u16> {
+    if let Some((_, code)) = ESRI_NAMES
+        .iter()
+        .find(|(esri_name, _)| esri_name.eq_ignore_ascii_case(name))
+    {
+        return Some(*code);
+    }
+    utm_epsg_code(name)
+}
+
+// `WGS_1984_UTM_Zone_33N`, `NAD_1983_UTM_Zone_10N`, `ETRS_1989_UTM_Zone_32N`, and their
+// `WGS 84 / UTM zone 33N` style equivalents.
+fn utm_epsg_code(name: &str) -> Option<u16> {
+    let normalized = name.to_ascii_uppercase().replace([' ', '/'], "_");
+    let (datum, zone) = normalized.split_once("_UTM_ZONE_")?;
+    let datum = datum.trim_end_matches('_');
+    let hemisphere = zone.chars().last()?;
+    let zone = zone.trim_end_matches(['N', 'S']).parse::<u16>().ok()?;
+    if !(1..=60).contains(&zone) {
+        return None;
+    }
+    match (datum, hemisphere) {
+        ("WGS_1984" | "WGS_84", 'N') => Some(32600 + zone),
+        ("WGS_1984" | "WGS_84", 'S') => Some(32700 + zone),
+        ("NAD_1983" | "NAD83", 'N') if (1..=23).contains(&zone) => Some(26900 + zone),
+        ("NAD_1927" | "NAD27", 'N') if (1..=22).contains(&zone) => Some(26700 + zone),
+        ("ETRS_1989" | "ETRS89", 'N') if (28..=38).contains(&zone) => Some(25800 + zone),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root_authority() {
+        let wkt = r#"PROJCS["NAD83 / UTM zone 10N",GEOGCS["NAD83",DATUM["North_American_Datum_1983",SPHEROID["GRS 1980",6378137,298.257222101,AUTHORITY["EPSG","7019"]],AUTHORITY["EPSG","6269"]],AUTHORITY["EPSG","4269"]],PROJECTION["Transverse_Mercator"],UNIT["metre",1,AUTHORITY["EPSG","9001"]],AUTHORITY["EPSG","26910"]]"#;
+        assert_eq!(epsg_code_from_wkt(wkt), Some(26910));
+    }
+
+    #[test]
+    fn nested_authorities_are_ignored() {
+        // The only `AUTHORITY` belongs to the datum, so the CRS is matched by name
+        let wkt = r#"GEOGCS["GCS_WGS_1984",DATUM["D_WGS_1984",SPHEROID["WGS_1984",6378137.0,298.257223563],AUTHORITY["EPSG","6326"]],PRIMEM["Greenwich",0.0],UNIT["Degree",0.0174532925199433]]"#;
+        assert_eq!(epsg_code_from_wkt(wkt), Some(4326));
+        let wkt = r#"PROJCS["Unknown",GEOGCS["GCS_WGS_1984",DATUM["D_WGS_1984",SPHEROID["WGS_1984",6378137.0,298.257223563]],AUTHORITY["EPSG","4326"]]]"#;
+        assert_eq!(epsg_code_from_wkt(wkt), None);
+    }
+
+    #[test]
+    fn esri_names() {
+        let wkt = r#"PROJCS["WGS_1984_Web_Mercator_Auxiliary_Sphere",GEOGCS["GCS_WGS_1984",DATUM["D_WGS_1984",SPHEROID["WGS_1984",6378137.0,298.257223563]]],PROJECTION["Mercator_Auxiliary_Sphere"]]"#;
+        assert_eq!(epsg_code_from_wkt(wkt), Some(3857));
+        let wkt = r#"PROJCS["british_national_grid",GEOGCS["GCS_OSGB_1936"]]"#;
+        assert_eq!(epsg_code_from_wkt(wkt), Some(27700));
+    }
+
+    #[test]
+    fn utm_zones() {
+        for (name, epsg_code) in [
+            ("WGS_1984_UTM_Zone_33N", Some(32633)),
+            ("WGS 84 / UTM zone 33S", Some(32733)),
+            ("NAD_1983_UTM_Zone_10N", Some(26910)),
+            ("NAD27 / UTM zone 22N", Some(26722)),
+            ("ETRS_1989_UTM_Zone_32N", Some(25832)),
+            // Zones the datum has no EPSG code for
+            ("NAD_1983_UTM_Zone_30N", None),
+            ("WGS_1984_UTM_Zone_61N", None),
+            ("WGS_1984_UTM_Zone_0N", None),
+        ] {
+            assert_eq!(
+                epsg_code_from_wkt(&format!(r#"PROJCS["{name}",GEOGCS["GCS"]]"#)),
+                epsg_code,
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn projjson() -> Result<(), serde_json::Error> {
+        let projjson = serde_json::from_str(
+            r#"{"name": "WGS 84", "id": {"authority": "EPSG", "code": 4326}}"#,
+        )?;
+        assert_eq!(epsg_code_from_projjson(&projjson), Some(4326));
+        let projjson = serde_json::from_str(r#"{"ids": [{"authority": "EPSG", "code": "3857"}]}"#)?;
+        assert_eq!(epsg_code_from_projjson(&projjson), Some(3857));
+        let projjson = serde_json::from_str(r#"{"id": {"authority": "OGC", "code": "CRS84"}}"#)?;
+        assert_eq!(epsg_code_from_projjson(&projjson), Some(4326));
+        let projjson = serde_json::from_str(r#"{"name": "NAD83"}"#)?;
+        assert_eq!(epsg_code_from_projjson(&projjson), Some(4269));
+        let projjson = serde_json::from_str(r#"{"name": "Unknown"}"#)?;
+        assert_eq!(epsg_code_from_projjson(&projjson), None);
+        Ok(())
+    }
+}
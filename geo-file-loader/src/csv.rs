@@ -0,0 +1,228 @@
+use geozero::ToGeo;
+
+const X_COLUMN_NAMES: [&str; 5] = ["x", "lon", "lng", "long", "longitude"];
+const Y_COLUMN_NAMES: [&str; 3] = ["y", "lat", "latitude"];
+const WKT_COLUMN_NAMES: [&str; 4] = ["wkt", "geometry", "geom", "the_geom"];
+const DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+const UTF_8_BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// Columns of a CSV file that hold each row's geometry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CsvGeometryColumns {
+    Xy { x: String, y: String },
+    Wkt(String),
+}
+
+impl CsvGeometryColumns {
+    /// Guess the geometry columns from common column names like `longitude`/`latitude` or `wkt`.
+    pub fn detect(headers: &[String]) -> Option<Self> {
+        let find = |names: &[&str]| {
+            headers
+                .iter()
+                .find(|header| names.iter().any(|name| header.eq_ignore_ascii_case(name)))
+                .cloned()
+        };
+        match (find(&X_COLUMN_NAMES), find(&Y_COLUMN_NAMES)) {
+            (Some(x), Some(y)) => Some(CsvGeometryColumns::Xy { x, y }),
+            _ => find(&WKT_COLUMN_NAMES).map(CsvGeometryColumns::Wkt),
+        }
+    }
+
+    fn contains(&self, header: &str) -> bool {
+        match self {
+            CsvGeometryColumns::Xy { x, y } => x == header || y == header,
+            CsvGeometryColumns::Wkt(wkt) => wkt == header,
+        }
+    }
+}
+
+/// Column names of a CSV file, so the user can pick the geometry columns before loading.
+pub fn csv_headers(bytes: &[u8]) -> Result<Vec<String>, crate::Error> {
+    let bytes = strip_bom(bytes);
+    Ok(reader_builder(bytes)
+        .from_reader(bytes)
+        .headers()?
+        .iter()
+        .map(String::from)
+        .collect())
+}
+
+pub struct CsvSource {
+    pub bytes: bytes::Bytes,
+    /// When `None`, the geometry columns are detected from the column names.
+    pub geometry_columns: Option<CsvGeometryColumns>,
+}
+
+impl crate::FileLoader for CsvSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        CsvSource {
+            bytes,
+            geometry_columns: None,
+        }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let bytes = strip_bom(&self.bytes);
+        let mut reader = reader_builder(bytes).from_reader(bytes);
+        let headers = reader
+            .headers()?
+            .iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let geometry_columns = match self.geometry_columns {
+            Some(geometry_columns) => geometry_columns,
+            None => CsvGeometryColumns::detect(&headers).ok_or(crate::Error::NoCsvGeometry)?,
+        };
+        let column_index = |name: &str| {
+            headers
+                .iter()
+                .position(|header| header == name)
+                .ok_or_else(|| crate::Error::MissingCsvColumn(name.into()))
+        };
+        let geometry_indices = match geometry_columns {
+            CsvGeometryColumns::Xy { ref x, ref y } => (column_index(x)?, Some(column_index(y)?)),
+            CsvGeometryColumns::Wkt(ref wkt) => (column_index(wkt)?, None),
+        };
+
+        let records = reader.records().collect::<Result<Vec<_>, _>>()?;
+
+        // A column holds numbers if every non-empty value in it parses as one. Codes like FIPS
+        // `01001` or ZIP `02134` would lose their leading zeros, so they're kept as strings.
+        let is_numeric_column = (0..headers.len())
+            .map(|i| {
+                records
+                    .iter()
+                    .filter_map(|record| record.get(i).map(str::trim))
+                    .filter(|value| !value.is_empty())
+                    .all(|value| value.parse::<f64>().is_ok() && !has_leading_zero(value))
+            })
+            .collect::<Vec<_>>();
+
+        let features = records
+            .iter()
+            .map(|record| {
+                let geometry = match geometry_indices {
+                    (x, Some(y)) => parse_point(record.get(x), record.get(y)),
+                    (wkt, None) => record
+                        .get(wkt)
+                        .and_then(|wkt| geozero::wkt::Wkt(wkt).to_geo().ok()),
+                };
+                let properties = headers
+                    .iter()
+                    .zip(record.iter())
+                    .zip(&is_numeric_column)
+                    .filter(|((header, _), _)| !geometry_columns.contains(header))
+                    .map(|((header, value), is_numeric)| {
+                        (header.clone(), parse_value(value.trim(), *is_numeric))
+                    })
+                    .collect();
+                let mut builder = geo_features::FeatureBuilder::new().with_properties(properties);
+                if let Some(geometry) = geometry {
+                    builder = builder.with_geometry(geometry);
+                }
+                builder.build()
+            })
+            .collect();
+
+        Ok(geo_features::FeatureCollection::from_features(features).into())
+    }
+}
+
+fn parse_point(x: Option<&str>, y: Option<&str>) -> Option<geo::Geometry> {
+    let x = x?.trim().parse::<f64>().ok()?;
+    let y = y?.trim().parse::<f64>().ok()?;
+    Some(geo::Point::new(x, y).into())
+}
+
+// `0` and `0.5` are numbers, `01001` is a code.
+fn has_leading_zero(value: &str) -> bool {
+    let digits = value.trim_start_matches(['+', '-']).as_bytes();
+    matches!(digits, [b'0', second, ..] if second.is_ascii_digit())
+}
+
+fn parse_value(value: &str, is_numeric: bool) -> geo_features::Value {
+    if value.is_empty() {
+        return geo_features::Value::Null;
+    }
+    if is_numeric {
+        if let Ok(n) = value.parse() {
+            return geo_features::Value::Number(n);
+        }
+    }
+    if value.eq_ignore_ascii_case("true") {
+        geo_features::Value::Boolean(true)
+    } else if value.eq_ignore_ascii_case("false") {
+        geo_features::Value::Boolean(false)
+    } else {
+        geo_features::Value::String(value.into())
+    }
+}
+
+// Spreadsheet programs often start UTF-8 files with a byte order mark, which would otherwise end up
+// in the first column name.
+fn strip_bom(bytes: &[u8]) -> &[u8] {
+    bytes.strip_prefix(UTF_8_BOM).unwrap_or(bytes)
+}
+
+fn reader_builder(bytes: &[u8]) -> csv::ReaderBuilder {
+    let mut builder = csv::ReaderBuilder::new();
+    builder.delimiter(detect_delimiter(bytes)).flexible(true);
+    builder
+}
+
+// The delimiter that shows up the most in the header row.
+fn detect_delimiter(bytes: &[u8]) -> u8 {
+    let header = bytes.split(|b| *b == b'\n').next().unwrap_or_default();
+    // Reversed so ties go to the earlier, more common, delimiter
+    DELIMITERS
+        .into_iter()
+        .rev()
+        .max_by_key(|delimiter| header.iter().filter(|b| *b == delimiter).count())
+        .unwrap_or(b',')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileLoader;
+
+    fn load(text: &'static str) -> Result<geo_features::FeatureCollection, crate::Error> {
+        Ok(
+            CsvSource::from_bytes(bytes::Bytes::from_static(text.as_bytes()))
+                .load()?
+                .feature_collection,
+        )
+    }
+
+    #[test]
+    fn zero_padded_codes_are_strings() -> Result<(), crate::Error> {
+        let feature_collection =
+            load("fips,zip,area,x,y\n01001,02134,0.5,1,2\n06037,90012,0,3,4\n")?;
+        let [autauga, _] = feature_collection.features.as_slice() else {
+            panic!("expected two features");
+        };
+        assert!(
+            matches!(autauga.properties.get("fips"), Some(geo_features::Value::String(s)) if s == "01001")
+        );
+        assert!(
+            matches!(autauga.properties.get("zip"), Some(geo_features::Value::String(s)) if s == "02134")
+        );
+        assert!(matches!(
+            autauga.properties.get("area"),
+            Some(geo_features::Value::Number(n)) if *n == 0.5
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn byte_order_mark() -> Result<(), crate::Error> {
+        let text = "\u{feff}lon,lat,name\n1,2,Lisbon\n";
+        assert_eq!(csv_headers(text.as_bytes())?, ["lon", "lat", "name"]);
+        let feature_collection = load(text)?;
+        let [lisbon] = feature_collection.features.as_slice() else {
+            panic!("expected one feature");
+        };
+        assert_eq!(lisbon.geometry, Some(geo::Point::new(1., 2.).into()));
+        Ok(())
+    }
+}
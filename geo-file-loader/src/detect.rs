@@ -0,0 +1,406 @@
+use std::io;
+
+use crate::FileFormat;
+
+const SQLITE_MAGIC: &[u8] = b"SQLite format 3\0";
+const FLATGEOBUF_MAGIC: &[u8] = b"fgb\x03";
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+const PARQUET_MAGIC: &[u8] = b"PAR1";
+// Type of the first blob header of PBF files, after its length
+const OSM_PBF_MAGIC: &[u8] = b"\x0a\x09OSMHeader";
+// File code at the start of every .shp file, big-endian 9994
+const SHP_MAGIC: &[u8] = &[0x00, 0x00, 0x27, 0x0A];
+// How far into JSON text the `type` of the outer object is looked for
+const JSON_TYPE_SEARCH_LEN: usize = 4096;
+const WKT_KEYWORDS: [&str; 7] = [
+    "POINT",
+    "LINESTRING",
+    "POLYGON",
+    "MULTIPOINT",
+    "MULTILINESTRING",
+    "MULTIPOLYGON",
+    "GEOMETRYCOLLECTION",
+];
+// Written either right after the geometry type, or as a word of its own
+const WKT_DIMENSIONS: [&str; 3] = ["Z", "M", "ZM"];
+
+/// Guess the format of a file from its contents, its name, and the content type it was served
+/// with. Binary formats with a magic number are recognized from their contents first, then the
+/// file extension and content type are tried, and finally the contents are sniffed for textual
+/// formats.
+pub fn detect_file_format(
+    file_name: Option<&str>,
+    content_type: Option<&str>,
+    bytes: &[u8],
+) -> Option<FileFormat> {
+    format_from_magic(bytes)
+        .or_else(|| file_name.and_then(format_from_file_name))
+        .or_else(|| content_type.and_then(format_from_content_type))
+        .or_else(|| format_from_text(bytes))
+}
+
+fn format_from_magic(bytes: &[u8]) -> Option<FileFormat> {
+    if bytes.starts_with(SQLITE_MAGIC) {
+        Some(FileFormat::GeoPackage)
+    } else if bytes.starts_with(FLATGEOBUF_MAGIC) {
+        Some(FileFormat::FlatGeobuf)
+    } else if bytes.starts_with(PARQUET_MAGIC) {
+        Some(FileFormat::GeoParquet)
+    } else if bytes
+        .get(4..)
+        .is_some_and(|bytes| bytes.starts_with(OSM_PBF_MAGIC))
+    {
+        Some(FileFormat::Osm)
+    } else if bytes.starts_with(SHP_MAGIC) {
+        Some(FileFormat::Shapefile)
+    } else if bytes.starts_with(ZIP_MAGIC) {
+        format_from_zip(bytes)
+    } else {
+        None
+    }
+}
+
+// Zipped shapefiles and KMZ files are both zip archives, tell them apart by what they hold.
+fn format_from_zip(bytes: &[u8]) -> Option<FileFormat> {
+    let archive = zip::ZipArchive::new(io::Cursor::new(bytes)).ok()?;
+    let has_extension = |extension: &str| {
+        archive
+            .file_names()
+            .any(|name| extension_of(name).is_some_and(|e| e.eq_ignore_ascii_case(extension)))
+    };
+    if has_extension("shp") {
+        Some(FileFormat::Shapefile)
+    } else if has_extension("kml") {
+        Some(FileFormat::Kml)
+    } else {
+        None
+    }
+}
+
+fn format_from_file_name(file_name: &str) -> Option<FileFormat> {
+    match extension_of(file_name)?.to_ascii_lowercase().as_str() {
+        "geojson" => Some(FileFormat::GeoJson),
+        // Plain GeoJSON and TopoJSON both use it, so it's told apart by the contents instead
+        "json" => None,
+        "topojson" => Some(FileFormat::TopoJson),
+        "geojsons" | "geojsonl" | "geojsonseq" | "ndjson" | "jsonl" => Some(FileFormat::GeoJsonSeq),
+        "shp" | "zip" => Some(FileFormat::Shapefile),
+        "gpx" => Some(FileFormat::Gpx),
+        "kml" | "kmz" => Some(FileFormat::Kml),
+        "osm" | "pbf" => Some(FileFormat::Osm),
+        "csv" | "tsv" => Some(FileFormat::Csv),
+        "wkt" => Some(FileFormat::Wkt),
+        "wkb" | "ewkb" => Some(FileFormat::Wkb),
+        "fgb" => Some(FileFormat::FlatGeobuf),
+        "gpkg" => Some(FileFormat::GeoPackage),
+        "parquet" | "geoparquet" => Some(FileFormat::GeoParquet),
+        _ => None,
+    }
+}
+
+fn format_from_content_type(content_type: &str) -> Option<FileFormat> {
+    let mime_type = content_type.split(';').next()?.trim().to_ascii_lowercase();
+    match mime_type.as_str() {
+        "application/geo+json" | "application/vnd.geo+json" => Some(FileFormat::GeoJson),
+        "application/geo+json-seq" | "application/x-ndjson" => Some(FileFormat::GeoJsonSeq),
+        "application/gpx+xml" => Some(FileFormat::Gpx),
+        "application/vnd.google-earth.kml+xml" | "application/vnd.google-earth.kmz" => {
+            Some(FileFormat::Kml)
+        }
+        "application/vnd.openstreetmap.data+xml" => Some(FileFormat::Osm),
+        "text/csv" | "text/tab-separated-values" => Some(FileFormat::Csv),
+        "application/flatgeobuf" => Some(FileFormat::FlatGeobuf),
+        "application/geopackage+sqlite3" => Some(FileFormat::GeoPackage),
+        "application/vnd.apache.parquet" => Some(FileFormat::GeoParquet),
+        "application/x-shapefile" => Some(FileFormat::Shapefile),
+        _ => None,
+    }
+}
+
+fn format_from_text(bytes: &[u8]) -> Option<FileFormat> {
+    let text = std::str::from_utf8(bytes).ok()?;
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if text.starts_with('\u{1e}') {
+        return Some(FileFormat::GeoJsonSeq);
+    }
+    if text.starts_with('{') {
+        return if top_level_json_type(text) == Some("Topology") {
+            Some(FileFormat::TopoJson)
+        } else if is_newline_delimited_json(text) {
+            Some(FileFormat::GeoJsonSeq)
+        } else {
+            Some(FileFormat::GeoJson)
+        };
+    }
+    if text.starts_with('<') {
+        return if text.contains("<gpx") {
+            Some(FileFormat::Gpx)
+        } else if text.contains("<kml") {
+            Some(FileFormat::Kml)
+        } else if text.contains("<osm") {
+            Some(FileFormat::Osm)
+        } else {
+            None
+        };
+    }
+    if starts_with_wkt(text) {
+        return Some(FileFormat::Wkt);
+    }
+    let first_word = text
+        .split(|c: char| c.is_whitespace() || c == '(')
+        .next()?
+        .to_ascii_uppercase();
+    // Hex-encoded WKB starts with its byte order, `00` or `01`
+    let hex = first_word.strip_prefix("\\X").unwrap_or(&first_word);
+    if (hex.starts_with("00") || hex.starts_with("01"))
+        && hex.bytes().all(|b| b.is_ascii_hexdigit())
+    {
+        return Some(FileFormat::Wkb);
+    }
+    // A header row with several columns, followed by at least one row
+    let mut lines = text.lines();
+    match (lines.next(), lines.next()) {
+        (Some(header), Some(_)) if header.contains([',', ';', '\t']) => Some(FileFormat::Csv),
+        _ => None,
+    }
+}
+
+// Whether the text starts with a WKT (or EWKT) geometry: its type, possibly with dimensions, then
+// either its coordinates or `EMPTY`. The type has to match exactly, so that a CSV header like
+// `POINTS_ID,x,y` isn't taken for WKT.
+fn starts_with_wkt(text: &str) -> bool {
+    let text = match text.get(..5) {
+        Some(srid) if srid.eq_ignore_ascii_case("SRID=") => {
+            text.split_once(';').map_or("", |(_, text)| text)
+        }
+        _ => text,
+    };
+    let (geometry_type, rest) = wkt_word(text);
+    let geometry_type = geometry_type.to_ascii_uppercase();
+    let Some(dimensions) = WKT_KEYWORDS
+        .iter()
+        .find_map(|keyword| geometry_type.strip_prefix(keyword))
+    else {
+        return false;
+    };
+    let (mut word, mut rest) = wkt_word(rest);
+    if dimensions.is_empty() {
+        if WKT_DIMENSIONS
+            .iter()
+            .any(|dimensions| word.eq_ignore_ascii_case(dimensions))
+        {
+            (word, rest) = wkt_word(rest);
+        }
+    } else if !WKT_DIMENSIONS.contains(&dimensions) {
+        return false;
+    }
+    (word.is_empty() && rest.starts_with('(')) || word.eq_ignore_ascii_case("EMPTY")
+}
+
+// The word at the start of the text, which ends at whitespace or an opening parenthesis, and the
+// text following it
+fn wkt_word(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    text.split_at(
+        text.find(|c: char| c.is_whitespace() || c == '(')
+            .unwrap_or(text.len()),
+    )
+}
+
+// Whether the first line is a whole JSON value, with more of them on the following lines
+fn is_newline_delimited_json(text: &str) -> bool {
+    let Some((first_line, rest)) = text.split_once('\n') else {
+        return false;
+    };
+    !rest.trim().is_empty() && serde_json::from_str::<serde_json::Value>(first_line).is_ok()
+}
+
+// Value of the `type` member of the outer JSON object, only looked for in the start of the text.
+fn top_level_json_type(text: &str) -> Option<&str> {
+    let bytes = text.as_bytes();
+    let bytes = bytes.get(..JSON_TYPE_SEARCH_LEN).unwrap_or(bytes);
+    let mut depth = 0usize;
+    let mut i = 0;
+    while let Some(b) = bytes.get(i) {
+        match b {
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            b'"' => {
+                let (string, end) = json_string(bytes, i)?;
+                i = end;
+                if depth == 1 && string == b"type" {
+                    let colon = skip_whitespace(bytes, i);
+                    if bytes.get(colon) == Some(&b':') {
+                        let value = skip_whitespace(bytes, colon + 1);
+                        if bytes.get(value) != Some(&b'"') {
+                            return None;
+                        }
+                        let (value, _) = json_string(bytes, value)?;
+                        return std::str::from_utf8(value).ok();
+                    }
+                }
+                continue;
+            }
+            _ => (),
+        }
+        i += 1;
+    }
+    None
+}
+
+fn skip_whitespace(bytes: &[u8], start: usize) -> usize {
+    start
+        + bytes.get(start..).map_or(0, |rest| {
+            rest.iter().take_while(|b| b.is_ascii_whitespace()).count()
+        })
+}
+
+// Contents of the JSON string starting with the quote at `start`, and the index following it
+fn json_string(bytes: &[u8], start: usize) -> Option<(&[u8], usize)> {
+    let mut i = start + 1;
+    loop {
+        match bytes.get(i)? {
+            b'\\' => i += 2,
+            b'"' => return Some((bytes.get(start + 1..i)?, i + 1)),
+            _ => i += 1,
+        }
+    }
+}
+
+// Extension of a file name or URL path, ignoring any query string
+fn extension_of(file_name: &str) -> Option<&str> {
+    let path = file_name.split(['?', '#']).next()?;
+    let (_, extension) = path.rsplit_once('.')?;
+    if extension.contains('/') {
+        return None;
+    }
+    Some(extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(text: &str) -> Option<FileFormat> {
+        detect_file_format(None, None, text.as_bytes())
+    }
+
+    #[test]
+    fn wkt() {
+        assert_eq!(detect("POINT(1 2)"), Some(FileFormat::Wkt));
+        assert_eq!(detect("  point (1 2)"), Some(FileFormat::Wkt));
+        assert_eq!(detect("POINT Z (1 2 3)"), Some(FileFormat::Wkt));
+        assert_eq!(detect("POINTZ(1 2 3)"), Some(FileFormat::Wkt));
+        assert_eq!(
+            detect("LINESTRING ZM (1 2 3 4, 5 6 7 8)"),
+            Some(FileFormat::Wkt)
+        );
+        assert_eq!(detect("POLYGON EMPTY"), Some(FileFormat::Wkt));
+        assert_eq!(detect("MULTIPOINT M EMPTY"), Some(FileFormat::Wkt));
+        assert_eq!(
+            detect("GEOMETRYCOLLECTION(POINT(1 2))"),
+            Some(FileFormat::Wkt)
+        );
+        assert_eq!(detect("SRID=4326;POINT(1 2)"), Some(FileFormat::Wkt));
+    }
+
+    #[test]
+    fn csv_headers_starting_with_wkt_keywords() {
+        assert_eq!(detect("POINTS_ID,x,y\n1,2,3\n"), Some(FileFormat::Csv));
+        assert_eq!(
+            detect("polygon_id,wkt\n1,\"POLYGON((0 0,1 0,1 1,0 0))\"\n"),
+            Some(FileFormat::Csv)
+        );
+        assert_eq!(detect("POINTX(1 2)"), None);
+        assert_eq!(detect("POINT 1 2"), None);
+    }
+
+    #[test]
+    fn wkb_hex() {
+        assert_eq!(
+            detect("0101000000000000000000F03F0000000000000040"),
+            Some(FileFormat::Wkb)
+        );
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(
+            detect(r#"{"type":"FeatureCollection","features":[]}"#),
+            Some(FileFormat::GeoJson)
+        );
+        assert_eq!(
+            detect(r#"{"features":[{"type":"Topology"}],"type":"FeatureCollection"}"#),
+            Some(FileFormat::GeoJson)
+        );
+        assert_eq!(
+            detect(r#"{"type":"Topology","objects":{},"arcs":[]}"#),
+            Some(FileFormat::TopoJson)
+        );
+        assert_eq!(
+            detect("{\"type\":\"Point\",\"coordinates\":[1,2]}\n{\"type\":\"Point\",\"coordinates\":[3,4]}\n"),
+            Some(FileFormat::GeoJsonSeq)
+        );
+        assert_eq!(
+            detect("\u{1e}{\"type\":\"Point\",\"coordinates\":[1,2]}\n"),
+            Some(FileFormat::GeoJsonSeq)
+        );
+    }
+
+    #[test]
+    fn xml() {
+        assert_eq!(
+            detect(r#"<?xml version="1.0"?><gpx version="1.1"></gpx>"#),
+            Some(FileFormat::Gpx)
+        );
+        assert_eq!(
+            detect(r#"<?xml version="1.0"?><kml xmlns="http://www.opengis.net/kml/2.2"></kml>"#),
+            Some(FileFormat::Kml)
+        );
+        assert_eq!(
+            detect(r#"<?xml version="1.0"?><osm version="0.6"></osm>"#),
+            Some(FileFormat::Osm)
+        );
+    }
+
+    #[test]
+    fn magic_numbers() {
+        assert_eq!(
+            detect_file_format(None, None, b"SQLite format 3\0rest"),
+            Some(FileFormat::GeoPackage)
+        );
+        assert_eq!(
+            detect_file_format(None, None, b"fgb\x03\x00"),
+            Some(FileFormat::FlatGeobuf)
+        );
+        assert_eq!(
+            detect_file_format(None, None, b"PAR1rest"),
+            Some(FileFormat::GeoParquet)
+        );
+    }
+
+    #[test]
+    fn file_names_and_content_types() {
+        assert_eq!(
+            detect_file_format(Some("roads.shp"), None, &[]),
+            Some(FileFormat::Shapefile)
+        );
+        assert_eq!(
+            detect_file_format(Some("https://example.com/a.geojson?x=1"), None, &[]),
+            Some(FileFormat::GeoJson)
+        );
+        assert_eq!(
+            detect_file_format(None, Some("text/csv; charset=utf-8"), &[]),
+            Some(FileFormat::Csv)
+        );
+        // Told apart by the contents
+        assert_eq!(
+            detect_file_format(
+                Some("data.json"),
+                None,
+                br#"{"type":"Topology","objects":{}}"#
+            ),
+            Some(FileFormat::TopoJson)
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use geozero::{ColumnValue, FeatureProcessor, GeomProcessor, PropertyProcessor};
+
+/// Geozero processor that builds a `geo_features::FeatureCollection`, keeping each feature's
+/// properties alongside its geometry.
+#[derive(Default)]
+pub(crate) struct FeatureCollectionWriter {
+    features: Vec<geo_features::Feature>,
+    properties: geo_features::Properties,
+    geo_writer: geozero::geo_types::GeoWriter,
+}
+
+impl FeatureCollectionWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn finish(mut self) -> geo_features::FeatureCollection {
+        // Sources that only emit a geometry never call `feature_end`
+        if let Some(geometry) = self.geo_writer.take_geometry() {
+            self.push_feature(Some(geometry));
+        }
+        geo_features::FeatureCollection::from_features(self.features)
+    }
+
+    fn push_feature(&mut self, geometry: Option<geo::Geometry>) {
+        let mut builder = geo_features::FeatureBuilder::new()
+            .with_properties(std::mem::take(&mut self.properties));
+        if let Some(geometry) = geometry {
+            builder = builder.with_geometry(geometry);
+        }
+        self.features.push(builder.build());
+    }
+}
+
+impl FeatureProcessor for FeatureCollectionWriter {
+    fn feature_begin(&mut self, _idx: u64) -> geozero::error::Result<()> {
+        self.properties.clear();
+        Ok(())
+    }
+
+    fn feature_end(&mut self, _idx: u64) -> geozero::error::Result<()> {
+        let geometry = self.geo_writer.take_geometry();
+        self.push_feature(geometry);
+        Ok(())
+    }
+}
+
+impl PropertyProcessor for FeatureCollectionWriter {
+    fn property(
+        &mut self,
+        _idx: usize,
+        name: &str,
+        value: &ColumnValue,
+    ) -> geozero::error::Result<bool> {
+        self.properties
+            .insert(name.to_owned(), column_value_to_value(value));
+        Ok(false)
+    }
+}
+
+pub(crate) fn column_value_to_value(value: &ColumnValue) -> geo_features::Value {
+    match *value {
+        ColumnValue::Byte(n) => geo_features::Value::Number(f64::from(n)),
+        ColumnValue::UByte(n) => geo_features::Value::Number(f64::from(n)),
+        ColumnValue::Bool(b) => geo_features::Value::Boolean(b),
+        ColumnValue::Short(n) => geo_features::Value::Number(f64::from(n)),
+        ColumnValue::UShort(n) => geo_features::Value::Number(f64::from(n)),
+        ColumnValue::Int(n) => geo_features::Value::Number(f64::from(n)),
+        ColumnValue::UInt(n) => geo_features::Value::Number(f64::from(n)),
+        ColumnValue::Long(n) => geo_features::Value::Number(n as f64),
+        ColumnValue::ULong(n) => geo_features::Value::Number(n as f64),
+        ColumnValue::Float(n) => geo_features::Value::Number(f64::from(n)),
+        ColumnValue::Double(n) => geo_features::Value::Number(n),
+        // Nested objects and arrays arrive as JSON strings
+        ColumnValue::String(s) | ColumnValue::Json(s) | ColumnValue::DateTime(s) => {
+            geo_features::Value::String(s.to_owned())
+        }
+        ColumnValue::Binary(bytes) => {
+            geo_features::Value::String(bytes.iter().map(|b| format!("{b:02x}")).collect())
+        }
+    }
+}
+
+// Geometries are built by the wrapped `GeoWriter`.
+impl GeomProcessor for FeatureCollectionWriter {
+    fn xy(&mut self, x: f64, y: f64, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.xy(x, y, idx)
+    }
+
+    fn point_begin(&mut self, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.point_begin(idx)
+    }
+
+    fn point_end(&mut self, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.point_end(idx)
+    }
+
+    fn multipoint_begin(&mut self, size: usize, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.multipoint_begin(size, idx)
+    }
+
+    fn multipoint_end(&mut self, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.multipoint_end(idx)
+    }
+
+    fn linestring_begin(
+        &mut self,
+        tagged: bool,
+        size: usize,
+        idx: usize,
+    ) -> geozero::error::Result<()> {
+        self.geo_writer.linestring_begin(tagged, size, idx)
+    }
+
+    fn linestring_end(&mut self, tagged: bool, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.linestring_end(tagged, idx)
+    }
+
+    fn multilinestring_begin(&mut self, size: usize, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.multilinestring_begin(size, idx)
+    }
+
+    fn multilinestring_end(&mut self, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.multilinestring_end(idx)
+    }
+
+    fn polygon_begin(
+        &mut self,
+        tagged: bool,
+        size: usize,
+        idx: usize,
+    ) -> geozero::error::Result<()> {
+        self.geo_writer.polygon_begin(tagged, size, idx)
+    }
+
+    fn polygon_end(&mut self, tagged: bool, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.polygon_end(tagged, idx)
+    }
+
+    fn multipolygon_begin(&mut self, size: usize, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.multipolygon_begin(size, idx)
+    }
+
+    fn multipolygon_end(&mut self, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.multipolygon_end(idx)
+    }
+
+    fn geometrycollection_begin(&mut self, size: usize, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.geometrycollection_begin(size, idx)
+    }
+
+    fn geometrycollection_end(&mut self, idx: usize) -> geozero::error::Result<()> {
+        self.geo_writer.geometrycollection_end(idx)
+    }
+}
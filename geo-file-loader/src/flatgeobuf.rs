@@ -0,0 +1,80 @@
+use std::{fs, io, path};
+
+use geo::Intersects;
+
+pub struct FlatGeobufSource {
+    pub bytes: bytes::Bytes,
+    /// Only read the features intersecting this rectangle, in the CRS of the file. The file's
+    /// spatial index is used when it has one, so only the matching features get decoded.
+    pub bbox: Option<geo::Rect>,
+}
+
+impl crate::FileLoader for FlatGeobufSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        FlatGeobufSource { bytes, bbox: None }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        load(io::Cursor::new(&self.bytes), self.bbox)
+    }
+}
+
+/// Loads a FlatGeobuf file straight from disk, without reading it into memory first. Along with a
+/// `bbox`, only the parts of the file holding the matching features get read.
+pub(crate) fn load_from_path(
+    path: &path::Path,
+    bbox: Option<geo::Rect>,
+) -> Result<crate::LoadedFile, crate::Error> {
+    load(io::BufReader::new(fs::File::open(path)?), bbox)
+}
+
+fn load<R: io::Read + io::Seek>(
+    reader: R,
+    bbox: Option<geo::Rect>,
+) -> Result<crate::LoadedFile, crate::Error> {
+    let reader = flatgeobuf::FgbReader::open(reader)?;
+    let crs_epsg_code = header_epsg_code(&reader.header());
+    let has_index = reader.header().index_node_size() > 0;
+    let mut writer = crate::feature_collection_writer::FeatureCollectionWriter::new();
+
+    match bbox {
+        Some(bbox) if has_index => reader
+            .select_bbox(bbox.min().x, bbox.min().y, bbox.max().x, bbox.max().y)?
+            .process_features(&mut writer)?,
+        _ => reader.select_all()?.process_features(&mut writer)?,
+    }
+    let mut feature_collection = writer.finish();
+
+    // Without an index, every feature had to be read, so filter them afterwards
+    if let (Some(bbox), false) = (bbox, has_index) {
+        feature_collection.features.retain(|feature| {
+            feature
+                .bounding_rect
+                .is_some_and(|feature_bbox| feature_bbox.intersects(&bbox))
+        });
+        feature_collection.recalculate_bounding_rect();
+    }
+
+    Ok(crate::LoadedFile {
+        feature_collection,
+        crs_epsg_code,
+    })
+}
+
+/// EPSG code of the CRS declared in a FlatGeobuf file's header, without reading its features.
+pub fn flatgeobuf_crs_epsg_code(reader: impl io::Read) -> Result<Option<u16>, crate::Error> {
+    let reader = flatgeobuf::FgbReader::open(reader)?;
+    let header = reader.header();
+    Ok(header_epsg_code(&header))
+}
+
+fn header_epsg_code(header: &flatgeobuf::Header) -> Option<u16> {
+    let crs = header.crs()?;
+    if crs
+        .org()
+        .is_some_and(|org| !org.eq_ignore_ascii_case("EPSG"))
+    {
+        return None;
+    }
+    u16::try_from(crs.code()).ok().filter(|code| *code != 0)
+}
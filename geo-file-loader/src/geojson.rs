@@ -1,6 +1,7 @@
-use std::io;
+// The GeoJSON is parsed with the `geojson` crate rather than geozero, which leaves out the
+// properties whose value is null.
 
-use geozero::GeozeroDatasource;
+use serde_json::Value as Json;
 
 pub struct GeoJsonSource {
     pub bytes: bytes::Bytes,
@@ -11,12 +12,105 @@ impl crate::FileLoader for GeoJsonSource {
         GeoJsonSource { bytes }
     }
 
-    fn load(self) -> Result<geo_features::FeatureCollection, crate::Error> {
-        let bytes_cursor = io::Cursor::new(&self.bytes);
-        let mut geojson_reader = geozero::geojson::GeoJsonReader(bytes_cursor);
-        let mut geo_writer = geozero::geo_types::GeoWriter::new();
-        geojson_reader.process(&mut geo_writer)?;
-        let geometry = geo_writer.take_geometry().ok_or(crate::Error::NoGeometry)?;
-        Ok(geo_features::FeatureCollection::from_geometry(geometry))
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let geojson = serde_json::from_slice::<::geojson::GeoJson>(&self.bytes)?;
+        let features = features(geojson)?;
+        if features.is_empty() {
+            return Err(crate::Error::NoGeometry);
+        }
+        Ok(geo_features::FeatureCollection::from_features(features).into())
+    }
+}
+
+/// Features of a feature collection, a single feature, or a bare geometry.
+pub(crate) fn features(
+    geojson: ::geojson::GeoJson,
+) -> Result<Vec<geo_features::Feature>, crate::Error> {
+    match geojson {
+        ::geojson::GeoJson::FeatureCollection(feature_collection) => feature_collection
+            .features
+            .into_iter()
+            .map(feature)
+            .collect(),
+        ::geojson::GeoJson::Feature(f) => Ok(vec![feature(f)?]),
+        ::geojson::GeoJson::Geometry(geometry) => Ok(vec![geo_features::FeatureBuilder::new()
+            .with_geometry(geometry.try_into()?)
+            .build()]),
+    }
+}
+
+fn feature(feature: ::geojson::Feature) -> Result<geo_features::Feature, crate::Error> {
+    let properties = feature
+        .properties
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, value)| (name, json_to_value(&value)))
+        .collect();
+    let mut builder = geo_features::FeatureBuilder::new().with_properties(properties);
+    if let Some(geometry) = feature.geometry {
+        builder = builder.with_geometry(geometry.try_into()?);
+    }
+    Ok(builder.build())
+}
+
+// Nested arrays and objects are kept as JSON strings.
+pub(crate) fn json_to_value(json: &Json) -> geo_features::Value {
+    match json {
+        Json::Null => geo_features::Value::Null,
+        Json::Bool(b) => geo_features::Value::Boolean(*b),
+        Json::Number(n) => n
+            .as_f64()
+            .map_or(geo_features::Value::Null, geo_features::Value::Number),
+        Json::String(s) => geo_features::Value::String(s.clone()),
+        Json::Array(_) | Json::Object(_) => geo_features::Value::String(json.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileLoader;
+
+    #[test]
+    fn null_properties_stay_with_their_feature() -> Result<(), crate::Error> {
+        let loaded = GeoJsonSource::from_bytes(bytes::Bytes::from_static(
+            br#"{"type": "FeatureCollection", "features": [
+                {"type": "Feature", "geometry": null, "properties": {"name": "no geometry"}},
+                {"type": "Feature", "geometry": {"type": "Point", "coordinates": [1, 2]}, "properties": {"name": null, "count": 3}}
+            ]}"#,
+        ))
+        .load()?;
+        let [first, second] = loaded.feature_collection.features.as_slice() else {
+            panic!("expected two features");
+        };
+        assert!(first.geometry.is_none());
+        assert!(
+            matches!(first.properties.get("name"), Some(geo_features::Value::String(s)) if s == "no geometry")
+        );
+        assert!(matches!(second.geometry, Some(geo::Geometry::Point(_))));
+        assert!(matches!(
+            second.properties.get("name"),
+            Some(geo_features::Value::Null)
+        ));
+        assert!(
+            matches!(second.properties.get("count"), Some(geo_features::Value::Number(n)) if *n == 3.)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bare_geometry() -> Result<(), crate::Error> {
+        let loaded = GeoJsonSource::from_bytes(bytes::Bytes::from_static(
+            br#"{"type": "LineString", "coordinates": [[0, 0], [1, 1]]}"#,
+        ))
+        .load()?;
+        let [feature] = loaded.feature_collection.features.as_slice() else {
+            panic!("expected a single feature");
+        };
+        assert!(matches!(
+            feature.geometry,
+            Some(geo::Geometry::LineString(_))
+        ));
+        Ok(())
     }
 }
@@ -0,0 +1,152 @@
+// GeoJSON text sequences (RFC 8142) start each record with an ASCII record separator, and the JSON
+// in a record may span several lines. Newline delimited GeoJSON just puts one record per line.
+// Either way, records are read one at a time, which lets big files get loaded a chunk at a time.
+
+const RECORD_SEPARATOR: u8 = 0x1e;
+
+pub struct GeoJsonSeqSource {
+    pub bytes: bytes::Bytes,
+}
+
+impl crate::FileLoader for GeoJsonSeqSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        GeoJsonSeqSource { bytes }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let feature_collection = GeoJsonSeqReader::new(self.bytes).read_chunk(usize::MAX)?;
+        if feature_collection.features.is_empty() {
+            return Err(crate::Error::NoGeometry);
+        }
+        Ok(feature_collection.into())
+    }
+}
+
+/// Reads the features of a GeoJSONSeq file a chunk at a time, so the first ones can be shown
+/// while the rest of the file is still loading.
+pub struct GeoJsonSeqReader {
+    bytes: bytes::Bytes,
+    position: usize,
+    // Byte records are split on, the record separator when the file starts with one
+    delimiter: u8,
+}
+
+impl GeoJsonSeqReader {
+    pub fn new(bytes: bytes::Bytes) -> Self {
+        let delimiter = match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(&RECORD_SEPARATOR) => RECORD_SEPARATOR,
+            _ => b'\n',
+        };
+        GeoJsonSeqReader {
+            bytes,
+            position: 0,
+            delimiter,
+        }
+    }
+
+    /// Read the next `max_features` features, or fewer if the end of the file is reached.
+    pub fn read_chunk(
+        &mut self,
+        max_features: usize,
+    ) -> Result<geo_features::FeatureCollection, crate::Error> {
+        let mut features = vec![];
+        while features.len() < max_features {
+            let Some(record) = self.next_record() else {
+                break;
+            };
+            let record = std::str::from_utf8(record)?
+                .trim_matches(|c: char| c == char::from(RECORD_SEPARATOR) || c.is_whitespace());
+            if record.is_empty() {
+                continue;
+            }
+            // A record is either a feature or a bare geometry
+            let geojson = serde_json::from_str::<::geojson::GeoJson>(record)?;
+            features.append(&mut crate::geojson::features(geojson)?);
+        }
+        Ok(geo_features::FeatureCollection::from_features(features))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    /// Percentage of the file read so far.
+    pub fn progress(&self) -> u8 {
+        if self.bytes.is_empty() {
+            return 100;
+        }
+        u8::try_from(100 * self.position / self.bytes.len()).unwrap_or(100)
+    }
+
+    fn next_record(&mut self) -> Option<&[u8]> {
+        let rest = self
+            .bytes
+            .get(self.position..)
+            .filter(|rest| !rest.is_empty())?;
+        // A record separator starts a record, while a newline ends one
+        let len = match self.delimiter {
+            RECORD_SEPARATOR => rest
+                .iter()
+                .skip(1)
+                .position(|b| *b == RECORD_SEPARATOR)
+                .map_or(rest.len(), |i| i + 1),
+            delimiter => rest
+                .iter()
+                .position(|b| *b == delimiter)
+                .map_or(rest.len(), |i| i + 1),
+        };
+        self.position += len;
+        rest.get(..len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(text: &'static str) -> Result<geo_features::FeatureCollection, crate::Error> {
+        GeoJsonSeqReader::new(bytes::Bytes::from_static(text.as_bytes())).read_chunk(usize::MAX)
+    }
+
+    #[test]
+    fn newline_delimited() -> Result<(), crate::Error> {
+        let feature_collection = read(concat!(
+            r#"{"type":"Feature","geometry":{"type":"Point","coordinates":[1,2]},"properties":{"a":1}}"#,
+            "\n",
+            r#"{"type":"Point","coordinates":[3,4]}"#,
+            "\n",
+        ))?;
+        assert_eq!(feature_collection.features.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn record_separated_records_span_lines() -> Result<(), crate::Error> {
+        let feature_collection = read(concat!(
+            "\u{1e}{\n",
+            "  \"type\": \"Feature\",\n",
+            "  \"geometry\": {\"type\": \"Point\", \"coordinates\": [1, 2]},\n",
+            "  \"properties\": {\"a\": 1}\n",
+            "}\n",
+            "\u{1e}{\n",
+            "  \"type\": \"Point\",\n",
+            "  \"coordinates\": [3, 4]\n",
+            "}\n",
+        ))?;
+        assert_eq!(feature_collection.features.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn chunks_stop_at_record_boundaries() -> Result<(), crate::Error> {
+        let mut reader = GeoJsonSeqReader::new(bytes::Bytes::from_static(
+            "\u{1e}{\"type\":\"Point\",\n\"coordinates\":[1,2]}\n\u{1e}{\"type\":\"Point\",\n\"coordinates\":[3,4]}\n"
+                .as_bytes(),
+        ));
+        assert_eq!(reader.read_chunk(1)?.features.len(), 1);
+        assert!(!reader.is_finished());
+        assert_eq!(reader.read_chunk(1)?.features.len(), 1);
+        assert!(reader.is_finished());
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+// GeoPackage files are SQLite databases. SQLite can only open databases from the filesystem, so
+// the bytes get written to a temporary file first, which also means GeoPackage isn't supported on
+// the web.
+
+#[cfg(not(target_arch = "wasm32"))]
+use geozero::ToGeo;
+#[cfg(not(target_arch = "wasm32"))]
+use std::io::Write;
+
+pub struct GeoPackageSource {
+    pub bytes: bytes::Bytes,
+    /// When `None`, the first feature table is loaded.
+    pub table: Option<String>,
+}
+
+impl crate::FileLoader for GeoPackageSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        GeoPackageSource { bytes, table: None }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let (_file, connection) = open(&self.bytes)?;
+        let table = match self.table {
+            Some(table) => table,
+            None => feature_tables(&connection)?
+                .into_iter()
+                .next()
+                .ok_or(crate::Error::NoGeometry)?,
+        };
+
+        let (geometry_column, srs_id) = connection
+            .query_row(
+                "SELECT column_name, srs_id FROM gpkg_geometry_columns WHERE table_name = ?1",
+                [&table],
+                |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)),
+            )
+            .map_err(|_| crate::Error::MissingGeoPackageTable(table.clone()))?;
+
+        let mut statement = connection.prepare(&format!("SELECT * FROM {}", quote(&table)))?;
+        let column_names = statement
+            .column_names()
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let mut rows = statement.query([])?;
+        let mut features = vec![];
+        while let Some(row) = rows.next()? {
+            let mut builder = geo_features::FeatureBuilder::new();
+            let mut properties = geo_features::Properties::new();
+            for (i, column_name) in column_names.iter().enumerate() {
+                let value = row.get_ref(i)?;
+                if column_name.eq_ignore_ascii_case(&geometry_column) {
+                    if let rusqlite::types::ValueRef::Blob(blob) = value {
+                        builder = builder.with_geometry(geozero::wkb::GpkgWkb(blob).to_geo()?);
+                    }
+                } else {
+                    properties.insert(column_name.clone(), sqlite_value_to_value(value));
+                }
+            }
+            features.push(builder.with_properties(properties).build());
+        }
+
+        Ok(crate::LoadedFile {
+            feature_collection: geo_features::FeatureCollection::from_features(features),
+            crs_epsg_code: srs_epsg_code(&connection, srs_id)?,
+        })
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        Err(crate::Error::GeoPackageUnsupported)
+    }
+}
+
+/// Names of the feature tables in a GeoPackage file, so the user can pick which ones to load.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn geopackage_feature_tables(bytes: &[u8]) -> Result<Vec<String>, crate::Error> {
+    let (_file, connection) = open(bytes)?;
+    feature_tables(&connection)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn geopackage_feature_tables(_bytes: &[u8]) -> Result<Vec<String>, crate::Error> {
+    Err(crate::Error::GeoPackageUnsupported)
+}
+
+// The temporary file is deleted when dropped, so it's returned alongside the connection.
+#[cfg(not(target_arch = "wasm32"))]
+fn open(bytes: &[u8]) -> Result<(tempfile::NamedTempFile, rusqlite::Connection), crate::Error> {
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(bytes)?;
+    file.flush()?;
+    let connection = rusqlite::Connection::open_with_flags(
+        file.path(),
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )?;
+    Ok((file, connection))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn feature_tables(connection: &rusqlite::Connection) -> Result<Vec<String>, crate::Error> {
+    let mut statement = connection.prepare(
+        "SELECT table_name FROM gpkg_contents WHERE data_type = 'features' ORDER BY table_name",
+    )?;
+    let tables = statement
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(tables)
+}
+
+// `gpkg_spatial_ref_sys` usually names the EPSG code directly, otherwise fall back to matching the
+// WKT definition. The reserved ids -1 and 0 are undefined cartesian and geographic CRSs.
+#[cfg(not(target_arch = "wasm32"))]
+fn srs_epsg_code(
+    connection: &rusqlite::Connection,
+    srs_id: i64,
+) -> Result<Option<u16>, crate::Error> {
+    if srs_id <= 0 {
+        return Ok(None);
+    }
+    let (organization, organization_coordsys_id, definition) = connection.query_row(
+        "SELECT organization, organization_coordsys_id, definition FROM gpkg_spatial_ref_sys WHERE srs_id = ?1",
+        [srs_id],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        },
+    )?;
+    if organization.eq_ignore_ascii_case("EPSG") {
+        if let Ok(code) = u16::try_from(organization_coordsys_id) {
+            return Ok(Some(code));
+        }
+    }
+    Ok(crate::crs::epsg_code_from_wkt(&definition))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn sqlite_value_to_value(value: rusqlite::types::ValueRef) -> geo_features::Value {
+    match value {
+        rusqlite::types::ValueRef::Null | rusqlite::types::ValueRef::Blob(_) => {
+            geo_features::Value::Null
+        }
+        rusqlite::types::ValueRef::Integer(n) => geo_features::Value::Number(n as f64),
+        rusqlite::types::ValueRef::Real(n) => geo_features::Value::Number(n),
+        rusqlite::types::ValueRef::Text(text) => {
+            geo_features::Value::String(String::from_utf8_lossy(text).into_owned())
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
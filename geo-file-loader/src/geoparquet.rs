@@ -0,0 +1,174 @@
+// GeoParquet files are Parquet files with WKB geometry columns, described by the JSON `geo`
+// entry of the file metadata. Only the columns the user picked are read from the file.
+
+use geozero::ToGeo;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use serde_json::Value as Json;
+
+pub struct GeoParquetSource {
+    pub bytes: bytes::Bytes,
+    /// Attribute columns to load as properties. When `None`, every column is loaded.
+    pub columns: Option<Vec<String>>,
+}
+
+impl crate::FileLoader for GeoParquetSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        GeoParquetSource {
+            bytes,
+            columns: None,
+        }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let reader = SerializedFileReader::new(self.bytes)?;
+        let metadata = GeoMetadata::from_reader(&reader)?;
+
+        // Other geometry columns aren't loaded, only the primary one
+        let is_selected = |name: &str| {
+            if name == metadata.primary_column {
+                return true;
+            }
+            if metadata
+                .geometry_columns
+                .iter()
+                .any(|column| column == name)
+            {
+                return false;
+            }
+            match self.columns {
+                Some(ref columns) => columns.iter().any(|column| column == name),
+                None => true,
+            }
+        };
+        let root_schema = reader
+            .metadata()
+            .file_metadata()
+            .schema_descr()
+            .root_schema();
+        let fields = root_schema
+            .get_fields()
+            .iter()
+            .filter(|field| is_selected(field.name()))
+            .cloned()
+            .collect();
+        let projection = parquet::schema::types::Type::group_type_builder(root_schema.name())
+            .with_fields(fields)
+            .build()?;
+
+        let mut features = vec![];
+        for row in reader.get_row_iter(Some(projection))? {
+            let row = row?;
+            let mut builder = geo_features::FeatureBuilder::new();
+            let mut properties = geo_features::Properties::new();
+            for (name, field) in row.get_column_iter() {
+                if *name != metadata.primary_column {
+                    properties.insert(name.clone(), field_to_value(field));
+                } else if let parquet::record::Field::Bytes(wkb) = field {
+                    builder = builder.with_geometry(geozero::wkb::Wkb(wkb.data()).to_geo()?);
+                }
+            }
+            features.push(builder.with_properties(properties).build());
+        }
+
+        Ok(crate::LoadedFile {
+            feature_collection: geo_features::FeatureCollection::from_features(features),
+            crs_epsg_code: metadata.crs_epsg_code,
+        })
+    }
+}
+
+/// Names of the attribute columns of a GeoParquet file, so the user can pick which ones to load.
+pub fn geoparquet_columns(bytes: &[u8]) -> Result<Vec<String>, crate::Error> {
+    let reader = SerializedFileReader::new(bytes::Bytes::copy_from_slice(bytes))?;
+    let metadata = GeoMetadata::from_reader(&reader)?;
+    Ok(reader
+        .metadata()
+        .file_metadata()
+        .schema_descr()
+        .root_schema()
+        .get_fields()
+        .iter()
+        .map(|field| field.name())
+        .filter(|name| {
+            !metadata
+                .geometry_columns
+                .iter()
+                .any(|column| column == name)
+        })
+        .map(String::from)
+        .collect())
+}
+
+struct GeoMetadata {
+    primary_column: String,
+    geometry_columns: Vec<String>,
+    crs_epsg_code: Option<u16>,
+}
+
+impl GeoMetadata {
+    fn from_reader(reader: &impl FileReader) -> Result<Self, crate::Error> {
+        let geo = reader
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .and_then(|key_values| key_values.iter().find(|key_value| key_value.key == "geo"))
+            .and_then(|key_value| key_value.value.as_deref())
+            .ok_or(crate::Error::InvalidGeoParquet)?;
+        let geo = serde_json::from_str::<Json>(geo)?;
+
+        let primary_column = geo
+            .get("primary_column")
+            .and_then(Json::as_str)
+            .ok_or(crate::Error::InvalidGeoParquet)?
+            .to_string();
+        let columns = geo
+            .get("columns")
+            .and_then(Json::as_object)
+            .ok_or(crate::Error::InvalidGeoParquet)?;
+        let primary_column_metadata = columns
+            .get(&primary_column)
+            .ok_or(crate::Error::InvalidGeoParquet)?;
+        // Newer versions of the spec also allow native GeoArrow encodings
+        if primary_column_metadata
+            .get("encoding")
+            .and_then(Json::as_str)
+            != Some("WKB")
+        {
+            return Err(crate::Error::UnsupportedGeoParquetEncoding);
+        }
+        // A missing CRS means OGC:CRS84, an explicit `null` means it's unknown
+        let crs_epsg_code = match primary_column_metadata.get("crs") {
+            None => Some(4326),
+            Some(Json::Null) => None,
+            Some(projjson) => crate::crs::epsg_code_from_projjson(projjson),
+        };
+
+        Ok(GeoMetadata {
+            geometry_columns: columns.keys().cloned().collect(),
+            primary_column,
+            crs_epsg_code,
+        })
+    }
+}
+
+// Nested and binary values are kept as their textual representation.
+fn field_to_value(field: &parquet::record::Field) -> geo_features::Value {
+    use parquet::record::Field;
+
+    match field {
+        Field::Null => geo_features::Value::Null,
+        Field::Bool(b) => geo_features::Value::Boolean(*b),
+        Field::Byte(n) => geo_features::Value::Number(f64::from(*n)),
+        Field::Short(n) => geo_features::Value::Number(f64::from(*n)),
+        Field::Int(n) => geo_features::Value::Number(f64::from(*n)),
+        Field::Long(n) => geo_features::Value::Number(*n as f64),
+        Field::UByte(n) => geo_features::Value::Number(f64::from(*n)),
+        Field::UShort(n) => geo_features::Value::Number(f64::from(*n)),
+        Field::UInt(n) => geo_features::Value::Number(f64::from(*n)),
+        Field::ULong(n) => geo_features::Value::Number(*n as f64),
+        Field::Float(n) => geo_features::Value::Number(f64::from(*n)),
+        Field::Double(n) => geo_features::Value::Number(*n),
+        Field::Str(s) => geo_features::Value::String(s.clone()),
+        _ => geo_features::Value::String(field.to_string()),
+    }
+}
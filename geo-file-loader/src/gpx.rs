@@ -1,9 +1,7 @@
 use std::io;
 
-use geozero::GeozeroDatasource;
-
-// TOOD: create generic file loader for geozero
-
+/// Waypoints, routes, and tracks each become their own feature, with a `feature_type` property
+/// to tell them apart.
 pub struct GpxSource {
     pub bytes: bytes::Bytes,
 }
@@ -13,12 +11,132 @@ impl crate::FileLoader for GpxSource {
         GpxSource { bytes }
     }
 
-    fn load(self) -> Result<geo_features::FeatureCollection, crate::Error> {
-        let bytes_cursor = io::Cursor::new(&self.bytes);
-        let mut gpx_reader = geozero::gpx::GpxReader(bytes_cursor);
-        let mut geo_writer = geozero::geo_types::GeoWriter::new();
-        gpx_reader.process(&mut geo_writer)?;
-        let geometry = geo_writer.take_geometry().ok_or(crate::Error::NoGeometry)?;
-        Ok(geo_features::FeatureCollection::from_geometry(geometry))
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let gpx = gpx::read(io::Cursor::new(&self.bytes))?;
+
+        let waypoints = gpx.waypoints.iter().map(|waypoint| {
+            let mut properties = common_properties("waypoint", waypoint.name.as_deref());
+            insert_string(&mut properties, "description", &waypoint.description);
+            insert_string(&mut properties, "comment", &waypoint.comment);
+            insert_string(&mut properties, "symbol", &waypoint.symbol);
+            insert_string(&mut properties, "type", &waypoint.type_);
+            if let Some(elevation) = waypoint.elevation {
+                properties.insert("elevation".into(), geo_features::Value::Number(elevation));
+            }
+            if let Some(time) = format_time(waypoint) {
+                properties.insert("time".into(), geo_features::Value::String(time));
+            }
+            geo_features::FeatureBuilder::new()
+                .with_geometry(waypoint.point().into())
+                .with_properties(properties)
+                .build()
+        });
+
+        let routes = gpx.routes.iter().map(|route| {
+            let mut properties = common_properties("route", route.name.as_deref());
+            insert_string(&mut properties, "description", &route.description);
+            insert_string(&mut properties, "comment", &route.comment);
+            insert_string(&mut properties, "type", &route.type_);
+            insert_number(&mut properties, route.number);
+            insert_point_properties(&mut properties, &route.points.iter().collect::<Vec<_>>());
+            geo_features::FeatureBuilder::new()
+                .with_geometry(route.linestring().into())
+                .with_properties(properties)
+                .build()
+        });
+
+        let tracks = gpx.tracks.iter().map(|track| {
+            let mut properties = common_properties("track", track.name.as_deref());
+            insert_string(&mut properties, "description", &track.description);
+            insert_string(&mut properties, "comment", &track.comment);
+            insert_string(&mut properties, "type", &track.type_);
+            insert_number(&mut properties, track.number);
+            let points = track
+                .segments
+                .iter()
+                .flat_map(|segment| &segment.points)
+                .collect::<Vec<_>>();
+            insert_point_properties(&mut properties, &points);
+            geo_features::FeatureBuilder::new()
+                .with_geometry(track.multilinestring().into())
+                .with_properties(properties)
+                .build()
+        });
+
+        let features = waypoints.chain(routes).chain(tracks).collect::<Vec<_>>();
+        if features.is_empty() {
+            return Err(crate::Error::NoGeometry);
+        }
+        Ok(crate::LoadedFile {
+            feature_collection: geo_features::FeatureCollection::from_features(features),
+            // GPX coordinates are always WGS 84
+            crs_epsg_code: Some(4326),
+        })
+    }
+}
+
+fn common_properties(feature_type: &str, name: Option<&str>) -> geo_features::Properties {
+    let mut properties = geo_features::Properties::new();
+    properties.insert(
+        "feature_type".into(),
+        geo_features::Value::String(feature_type.into()),
+    );
+    if let Some(name) = name {
+        properties.insert("name".into(), geo_features::Value::String(name.into()));
+    }
+    properties
+}
+
+fn insert_string(properties: &mut geo_features::Properties, key: &str, value: &Option<String>) {
+    if let Some(value) = value {
+        properties.insert(key.into(), geo_features::Value::String(value.clone()));
+    }
+}
+
+fn insert_number(properties: &mut geo_features::Properties, number: Option<u32>) {
+    if let Some(number) = number {
+        properties.insert(
+            "number".into(),
+            geo_features::Value::Number(f64::from(number)),
+        );
     }
 }
+
+// Per-point elevations and timestamps are stored as JSON arrays, the same way nested GeoJSON
+// properties are, along with the time span of the route or track.
+fn insert_point_properties(properties: &mut geo_features::Properties, points: &[&gpx::Waypoint]) {
+    let times = points
+        .iter()
+        .map(|point| format_time(point))
+        .collect::<Vec<_>>();
+    if times.iter().any(Option::is_some) {
+        let json = times.iter().cloned().collect::<serde_json::Value>();
+        properties.insert(
+            "times".into(),
+            geo_features::Value::String(json.to_string()),
+        );
+        let mut times = times.into_iter().flatten();
+        if let Some(start_time) = times.next() {
+            let end_time = times.last().unwrap_or_else(|| start_time.clone());
+            properties.insert("start_time".into(), geo_features::Value::String(start_time));
+            properties.insert("end_time".into(), geo_features::Value::String(end_time));
+        }
+    }
+
+    if points.iter().any(|point| point.elevation.is_some()) {
+        // Non-finite elevations become null, since JSON has no representation for them
+        let json = points
+            .iter()
+            .map(|point| point.elevation.map_or(serde_json::Value::Null, Into::into))
+            .collect::<serde_json::Value>();
+        properties.insert(
+            "elevations".into(),
+            geo_features::Value::String(json.to_string()),
+        );
+    }
+}
+
+// RFC 3339 timestamp
+fn format_time(waypoint: &gpx::Waypoint) -> Option<String> {
+    waypoint.time.as_ref().and_then(|time| time.format().ok())
+}
@@ -0,0 +1,296 @@
+use std::io::{self, Read};
+
+use quick_xml::events::Event;
+
+/// Each `Placemark` becomes a feature with its `name`, `description`, and `ExtendedData` fields as
+/// properties. The names of the folders containing it are kept in a `folder` property, joined
+/// with `/`.
+pub struct KmlSource {
+    pub bytes: bytes::Bytes,
+}
+
+impl crate::FileLoader for KmlSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        KmlSource { bytes }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let features = if self.bytes.starts_with(b"PK\x03\x04") {
+            read_features(&kml_from_kmz(&self.bytes)?)?
+        } else {
+            read_features(&self.bytes)?
+        };
+        if features.is_empty() {
+            return Err(crate::Error::NoGeometry);
+        }
+        Ok(crate::LoadedFile {
+            feature_collection: geo_features::FeatureCollection::from_features(features),
+            // KML coordinates are always WGS 84
+            crs_epsg_code: Some(4326),
+        })
+    }
+}
+
+// A KMZ file is a zip holding the main `doc.kml`, plus any images it references. Older KMZ files
+// name the main file differently, so fall back to the first `.kml` file.
+fn kml_from_kmz(bytes: &[u8]) -> Result<Vec<u8>, crate::Error> {
+    let mut archive = zip::ZipArchive::new(io::Cursor::new(bytes))?;
+    let name = archive
+        .file_names()
+        .filter(|name| name.to_ascii_lowercase().ends_with(".kml"))
+        .min_by_key(|name| {
+            (
+                *name != "doc.kml",
+                name.matches('/').count(),
+                name.to_string(),
+            )
+        })
+        .map(String::from)
+        .ok_or(crate::Error::MissingKml)?;
+    let mut kml = vec![];
+    archive.by_name(&name)?.read_to_end(&mut kml)?;
+    Ok(kml)
+}
+
+#[derive(Default)]
+struct Placemark {
+    properties: geo_features::Properties,
+    // Geometries of the placemark, and of each `MultiGeometry` being read
+    geometries: Vec<Vec<geo::Geometry>>,
+    outer_ring: Option<geo::LineString>,
+    inner_rings: Vec<geo::LineString>,
+}
+
+fn read_features(kml: &[u8]) -> Result<Vec<geo_features::Feature>, crate::Error> {
+    let mut reader = quick_xml::Reader::from_reader(kml);
+    reader.trim_text(true).expand_empty_elements(true);
+
+    let mut buf = vec![];
+    let mut features = vec![];
+    // Local names of the open elements
+    let mut path: Vec<String> = vec![];
+    // Names of the open folders, `None` until the folder's `name` element is read
+    let mut folders: Vec<Option<String>> = vec![];
+    let mut placemark: Option<Placemark> = None;
+    let mut data_name: Option<String> = None;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(element) => {
+                let name = String::from_utf8_lossy(element.local_name().as_ref()).into_owned();
+                text.clear();
+                match name.as_str() {
+                    "Folder" => folders.push(None),
+                    "Placemark" => {
+                        placemark = Some(Placemark {
+                            geometries: vec![vec![]],
+                            ..Default::default()
+                        })
+                    }
+                    "MultiGeometry" => {
+                        if let Some(ref mut placemark) = placemark {
+                            placemark.geometries.push(vec![]);
+                        }
+                    }
+                    "Data" | "SimpleData" => {
+                        data_name = element
+                            .try_get_attribute("name")?
+                            .map(|attribute| attribute.unescape_value())
+                            .transpose()?
+                            .map(|name| name.into_owned());
+                    }
+                    _ => (),
+                }
+                path.push(name);
+            }
+            Event::Text(t) => text.push_str(&t.unescape()?),
+            Event::CData(data) => text.push_str(&String::from_utf8_lossy(&data.into_inner())),
+            Event::End(_) => {
+                let Some(name) = path.pop() else {
+                    continue;
+                };
+                let parent = path.last().map(String::as_str);
+                let text = std::mem::take(&mut text);
+                match (name.as_str(), parent) {
+                    ("name", Some("Folder")) => {
+                        if let Some(folder @ None) = folders.last_mut() {
+                            *folder = Some(text);
+                        }
+                    }
+                    ("Folder", _) => {
+                        folders.pop();
+                    }
+                    ("Placemark", _) => {
+                        if let Some(placemark) = placemark.take() {
+                            features.push(build_feature(placemark, &folders));
+                        }
+                    }
+                    _ => {
+                        if let Some(ref mut placemark) = placemark {
+                            end_placemark_element(placemark, &name, &path, text, &mut data_name);
+                        }
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    Ok(features)
+}
+
+fn end_placemark_element(
+    placemark: &mut Placemark,
+    name: &str,
+    path: &[String],
+    text: String,
+    data_name: &mut Option<String>,
+) {
+    let parent = path.last().map(String::as_str);
+    match (name, parent) {
+        ("name" | "description", Some("Placemark")) => {
+            placemark
+                .properties
+                .insert(name.into(), geo_features::Value::String(text));
+        }
+        ("value", Some("Data")) | ("SimpleData", _) => {
+            if let Some(data_name) = data_name.take() {
+                placemark.properties.insert(data_name, parse_value(text));
+            }
+        }
+        ("coordinates", Some("Point")) => {
+            if let Some(coord) = parse_coordinates(&text).first() {
+                push_geometry(placemark, geo::Point(*coord).into());
+            }
+        }
+        ("coordinates", Some("LineString")) => {
+            push_geometry(
+                placemark,
+                geo::LineString::new(parse_coordinates(&text)).into(),
+            );
+        }
+        ("coordinates", Some("LinearRing")) => {
+            let ring = geo::LineString::new(parse_coordinates(&text));
+            match path.iter().rev().nth(1).map(String::as_str) {
+                Some("outerBoundaryIs") => placemark.outer_ring = Some(ring),
+                Some("innerBoundaryIs") => placemark.inner_rings.push(ring),
+                _ => push_geometry(placemark, ring.into()),
+            }
+        }
+        ("Polygon", _) => {
+            let inner_rings = std::mem::take(&mut placemark.inner_rings);
+            if let Some(outer_ring) = placemark.outer_ring.take() {
+                push_geometry(placemark, geo::Polygon::new(outer_ring, inner_rings).into());
+            }
+        }
+        ("MultiGeometry", _) if placemark.geometries.len() > 1 => {
+            if let Some(geometry) = placemark.geometries.pop().and_then(combine_geometries) {
+                push_geometry(placemark, geometry);
+            }
+        }
+        _ => (),
+    }
+}
+
+fn build_feature(placemark: Placemark, folders: &[Option<String>]) -> geo_features::Feature {
+    let mut properties = placemark.properties;
+    let folder = folders
+        .iter()
+        .map(|folder| folder.as_deref().unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("/");
+    if !folder.is_empty() {
+        properties.insert("folder".into(), geo_features::Value::String(folder));
+    }
+    let mut builder = geo_features::FeatureBuilder::new().with_properties(properties);
+    if let Some(geometry) = placemark
+        .geometries
+        .into_iter()
+        .next()
+        .and_then(combine_geometries)
+    {
+        builder = builder.with_geometry(geometry);
+    }
+    builder.build()
+}
+
+fn push_geometry(placemark: &mut Placemark, geometry: geo::Geometry) {
+    if let Some(geometries) = placemark.geometries.last_mut() {
+        geometries.push(geometry);
+    }
+}
+
+// A `MultiGeometry` of only points, lines, or polygons becomes the matching multi geometry.
+fn combine_geometries(mut geometries: Vec<geo::Geometry>) -> Option<geo::Geometry> {
+    if geometries.len() <= 1 {
+        return geometries.pop();
+    }
+    if geometries
+        .iter()
+        .all(|geometry| matches!(geometry, geo::Geometry::Point(_)))
+    {
+        return Some(
+            geo::MultiPoint::new(
+                geometries
+                    .into_iter()
+                    .filter_map(|g| g.try_into().ok())
+                    .collect(),
+            )
+            .into(),
+        );
+    }
+    if geometries
+        .iter()
+        .all(|geometry| matches!(geometry, geo::Geometry::LineString(_)))
+    {
+        return Some(
+            geo::MultiLineString::new(
+                geometries
+                    .into_iter()
+                    .filter_map(|g| g.try_into().ok())
+                    .collect(),
+            )
+            .into(),
+        );
+    }
+    if geometries
+        .iter()
+        .all(|geometry| matches!(geometry, geo::Geometry::Polygon(_)))
+    {
+        return Some(
+            geo::MultiPolygon::new(
+                geometries
+                    .into_iter()
+                    .filter_map(|g| g.try_into().ok())
+                    .collect(),
+            )
+            .into(),
+        );
+    }
+    Some(geo::Geometry::GeometryCollection(geo::GeometryCollection(
+        geometries,
+    )))
+}
+
+// `lon,lat[,alt]` tuples separated by whitespace. The altitude is dropped.
+fn parse_coordinates(text: &str) -> Vec<geo::Coord> {
+    text.split_whitespace()
+        .filter_map(|tuple| {
+            let mut values = tuple.split(',').map(|value| value.trim().parse::<f64>());
+            match (values.next(), values.next()) {
+                (Some(Ok(x)), Some(Ok(y))) => Some(geo::coord! { x: x, y: y }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn parse_value(text: String) -> geo_features::Value {
+    match text.trim().parse::<f64>() {
+        Ok(n) if n.is_finite() => geo_features::Value::Number(n),
+        _ => geo_features::Value::String(text),
+    }
+}
@@ -6,22 +6,53 @@
     clippy::expect_used
 )]
 
+mod crs;
+mod csv;
+mod detect;
+mod feature_collection_writer;
+mod flatgeobuf;
 mod geojson;
+mod geojson_seq;
+mod geopackage;
+mod geoparquet;
 mod gpx;
+mod kml;
+mod osm;
 mod shapefile;
+mod topojson;
+mod wkb;
 mod wkt;
 
+pub use crate::csv::{csv_headers, CsvGeometryColumns, CsvSource};
+pub use crate::detect::detect_file_format;
+pub use crate::flatgeobuf::{flatgeobuf_crs_epsg_code, FlatGeobufSource};
 pub use crate::geojson::GeoJsonSource;
+pub use crate::geojson_seq::{GeoJsonSeqReader, GeoJsonSeqSource};
+pub use crate::geopackage::{geopackage_feature_tables, GeoPackageSource};
+pub use crate::geoparquet::{geoparquet_columns, GeoParquetSource};
 pub use crate::gpx::GpxSource;
-pub use crate::shapefile::ShapefileSource;
+pub use crate::kml::KmlSource;
+pub use crate::osm::{OsmSource, OsmTagFilter};
+pub use crate::shapefile::{bundle_shapefile_files, ShapefileSource};
+pub use crate::topojson::{topojson_objects, TopoJsonSource};
+pub use crate::wkb::{wkb_srid, WkbSource};
 pub use crate::wkt::WktSource;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum FileFormat {
     GeoJson,
+    GeoJsonSeq,
     Shapefile,
     Wkt,
     Gpx,
+    Csv,
+    FlatGeobuf,
+    GeoPackage,
+    GeoParquet,
+    Kml,
+    Osm,
+    TopoJson,
+    Wkb,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -30,26 +61,135 @@ pub enum Error {
     Geozero(#[from] geozero::error::GeozeroError),
     #[error("{0}")]
     Shapefile(#[from] geozero_shp::Error),
+    #[error("{0}")]
+    Gpx(#[from] ::gpx::errors::GpxError),
+    #[error("{0}")]
+    Csv(#[from] ::csv::Error),
+    #[error("{0}")]
+    FlatGeobuf(#[from] ::flatgeobuf::Error),
+    #[cfg(not(target_arch = "wasm32"))]
+    #[error("{0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("{0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+    #[error("{0}")]
+    OsmPbf(#[from] osmpbf::Error),
+    #[error("{0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("{0}")]
+    Xml(#[from] quick_xml::Error),
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+    // Boxed, as it holds the JSON it failed to convert
+    #[error("{0}")]
+    GeoJson(Box<::geojson::Error>),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Utf8(#[from] std::str::Utf8Error),
     #[error("No geometry found in GeoJSON file")]
     NoGeometry,
+    #[error("No .shp file found")]
+    MissingShp,
+    #[error("Invalid .dbf file")]
+    InvalidDbf,
+    #[error("Could not find geometry columns in CSV file")]
+    NoCsvGeometry,
+    #[error("Column '{0}' not found in CSV file")]
+    MissingCsvColumn(String),
+    #[error("Table '{0}' is not a feature table of the GeoPackage file")]
+    MissingGeoPackageTable(String),
+    #[error("GeoPackage files are not supported on the web")]
+    GeoPackageUnsupported,
+    #[error("No .kml file found in KMZ file")]
+    MissingKml,
+    #[error("Invalid TopoJSON file")]
+    InvalidTopoJson,
+    #[error("Object '{0}' not found in TopoJSON file")]
+    MissingTopoJsonObject(String),
+    #[error("Invalid hex-encoded WKB")]
+    InvalidHex,
+    #[error("Missing or invalid 'geo' metadata in GeoParquet file")]
+    InvalidGeoParquet,
+    #[error("Only WKB-encoded GeoParquet geometries are supported")]
+    UnsupportedGeoParquetEncoding,
+    #[error("Invalid '{0}' attribute in OSM file")]
+    InvalidOsmAttribute(String),
+}
+
+impl From<::geojson::Error> for Error {
+    fn from(error: ::geojson::Error) -> Self {
+        Error::GeoJson(Box::new(error))
+    }
+}
+
+/// Format-specific settings chosen by the user before loading a file.
+#[derive(Clone, Debug, Default)]
+pub struct LoadOptions {
+    /// When `None`, CSV geometry columns are detected from the column names.
+    pub csv_geometry_columns: Option<CsvGeometryColumns>,
+    /// Only load the features intersecting this rectangle, in the CRS of the file. Used for
+    /// FlatGeobuf files.
+    pub bbox: Option<geo::Rect>,
+    /// Layer to load from formats holding several: the feature table of a GeoPackage file, or the
+    /// object of a TopoJSON topology. When `None`, the first one is loaded.
+    pub layer: Option<String>,
+    /// Attribute columns to load from a GeoParquet file. When `None`, every column is loaded.
+    pub columns: Option<Vec<String>>,
+    /// Only load the OpenStreetMap elements with matching tags. When `None`, every tagged element
+    /// is loaded.
+    pub osm_tag_filter: Option<OsmTagFilter>,
+}
+
+pub struct LoadedFile {
+    pub feature_collection: geo_features::FeatureCollection,
+    /// EPSG code of the CRS declared by the file itself, when it could be determined.
+    pub crs_epsg_code: Option<u16>,
+}
+
+impl From<geo_features::FeatureCollection> for LoadedFile {
+    fn from(feature_collection: geo_features::FeatureCollection) -> Self {
+        LoadedFile {
+            feature_collection,
+            crs_epsg_code: None,
+        }
+    }
 }
 
 impl FileFormat {
     pub const fn is_plaintext(self) -> bool {
         match self {
             Self::GeoJson => true,
+            Self::GeoJsonSeq => true,
             Self::Gpx => true,
             Self::Shapefile => false,
             Self::Wkt => true,
+            Self::Csv => true,
+            Self::FlatGeobuf => false,
+            Self::GeoPackage => false,
+            Self::GeoParquet => false,
+            Self::Kml => true,
+            Self::Osm => true,
+            Self::TopoJson => true,
+            Self::Wkb => true,
         }
     }
 
     pub const fn display_name(self) -> &'static str {
         match self {
             Self::GeoJson => "GeoJSON",
+            Self::GeoJsonSeq => "GeoJSONSeq",
             Self::Gpx => "GPX",
             Self::Shapefile => "Shapefile",
             Self::Wkt => "WKT",
+            Self::Csv => "CSV",
+            Self::FlatGeobuf => "FlatGeobuf",
+            Self::GeoPackage => "GeoPackage",
+            Self::GeoParquet => "GeoParquet",
+            Self::Kml => "KML/KMZ",
+            Self::Osm => "OSM XML/PBF",
+            Self::TopoJson => "TopoJSON",
+            Self::Wkb => "WKB",
         }
     }
 }
@@ -57,16 +197,63 @@ impl FileFormat {
 pub fn load_file(
     file_format: FileFormat,
     bytes: bytes::Bytes,
-) -> Result<geo_features::FeatureCollection, Error> {
+    options: LoadOptions,
+) -> Result<LoadedFile, Error> {
     match file_format {
         FileFormat::GeoJson => Ok(GeoJsonSource::from_bytes(bytes).load()?),
+        FileFormat::GeoJsonSeq => Ok(GeoJsonSeqSource::from_bytes(bytes).load()?),
         FileFormat::Gpx => Ok(GpxSource::from_bytes(bytes).load()?),
+        FileFormat::Kml => Ok(KmlSource::from_bytes(bytes).load()?),
         FileFormat::Shapefile => Ok(ShapefileSource::from_bytes(bytes).load()?),
         FileFormat::Wkt => Ok(WktSource::from_bytes(bytes).load()?),
+        FileFormat::Wkb => Ok(WkbSource::from_bytes(bytes).load()?),
+        FileFormat::Csv => Ok(CsvSource {
+            bytes,
+            geometry_columns: options.csv_geometry_columns,
+        }
+        .load()?),
+        FileFormat::FlatGeobuf => Ok(FlatGeobufSource {
+            bytes,
+            bbox: options.bbox,
+        }
+        .load()?),
+        FileFormat::GeoPackage => Ok(GeoPackageSource {
+            bytes,
+            table: options.layer,
+        }
+        .load()?),
+        FileFormat::GeoParquet => Ok(GeoParquetSource {
+            bytes,
+            columns: options.columns,
+        }
+        .load()?),
+        FileFormat::Osm => Ok(OsmSource {
+            bytes,
+            tag_filter: options.osm_tag_filter,
+        }
+        .load()?),
+        FileFormat::TopoJson => Ok(TopoJsonSource {
+            bytes,
+            object: options.layer,
+        }
+        .load()?),
+    }
+}
+
+/// Same as `load_file`, for a file on disk. FlatGeobuf files get read through their spatial index
+/// instead of being read whole, which matters for files larger than memory.
+pub fn load_file_from_path(
+    file_format: FileFormat,
+    path: &std::path::Path,
+    options: LoadOptions,
+) -> Result<LoadedFile, Error> {
+    match file_format {
+        FileFormat::FlatGeobuf => crate::flatgeobuf::load_from_path(path, options.bbox),
+        _ => load_file(file_format, std::fs::read(path)?.into(), options),
     }
 }
 
 trait FileLoader {
     fn from_bytes(bytes: bytes::Bytes) -> Self;
-    fn load(self) -> Result<geo_features::FeatureCollection, Error>;
+    fn load(self) -> Result<LoadedFile, Error>;
 }
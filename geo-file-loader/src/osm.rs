@@ -0,0 +1,468 @@
+// OpenStreetMap data is a set of tagged nodes, ways referencing their nodes, and relations
+// referencing their member ways. Tagged nodes become points, ways become lines or polygons
+// depending on their tags, and multipolygon relations become multipolygons assembled from the
+// rings formed by their member ways.
+
+use std::collections::HashMap;
+use std::io;
+
+use geo::Intersects;
+use quick_xml::events::{BytesStart, Event};
+
+// Keys whose closed ways are areas, unless tagged `area=no`
+const AREA_KEYS: [&str; 14] = [
+    "amenity",
+    "area:highway",
+    "building",
+    "building:part",
+    "craft",
+    "historic",
+    "landuse",
+    "leisure",
+    "man_made",
+    "military",
+    "natural",
+    "office",
+    "place",
+    "shop",
+];
+// Values of area keys which are lines even when the way is closed
+const LINEAR_TAGS: [(&str, &str); 5] = [
+    ("natural", "coastline"),
+    ("natural", "cliff"),
+    ("natural", "ridge"),
+    ("natural", "tree_row"),
+    ("man_made", "embankment"),
+];
+
+/// Either an `.osm` XML file or an `.osm.pbf` file. The tags of each element are kept as
+/// properties, along with its `osm_type` and `osm_id`.
+pub struct OsmSource {
+    pub bytes: bytes::Bytes,
+    /// Only load the elements with matching tags. When `None`, every tagged element is loaded.
+    pub tag_filter: Option<OsmTagFilter>,
+}
+
+impl crate::FileLoader for OsmSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        OsmSource {
+            bytes,
+            tag_filter: None,
+        }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let data = if is_pbf(&self.bytes) {
+            read_pbf(&self.bytes)?
+        } else {
+            read_xml(&self.bytes)?
+        };
+        let features = data.features(self.tag_filter.as_ref());
+        if features.is_empty() {
+            return Err(crate::Error::NoGeometry);
+        }
+        Ok(crate::LoadedFile {
+            feature_collection: geo_features::FeatureCollection::from_features(features),
+            // OSM coordinates are always WGS 84
+            crs_epsg_code: Some(4326),
+        })
+    }
+}
+
+/// Tags an element must have to be loaded, as comma-separated `key`, `key=*`, or `key=value`
+/// conditions, e.g. `building=*, highway=primary`. Elements matching any of them are loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OsmTagFilter {
+    // Keys and values, any value is accepted when `None`
+    conditions: Vec<(String, Option<String>)>,
+}
+
+impl OsmTagFilter {
+    /// `None` when there are no conditions.
+    pub fn parse(filter: &str) -> Option<Self> {
+        let conditions = filter
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|condition| !condition.is_empty())
+            .map(|condition| match condition.split_once('=') {
+                Some((key, "*")) => (key.trim().to_string(), None),
+                Some((key, value)) => (key.trim().to_string(), Some(value.trim().to_string())),
+                None => (condition.to_string(), None),
+            })
+            .collect::<Vec<_>>();
+        if conditions.is_empty() {
+            return None;
+        }
+        Some(OsmTagFilter { conditions })
+    }
+
+    fn matches(&self, tags: &[(String, String)]) -> bool {
+        self.conditions.iter().any(|(key, value)| {
+            tags.iter().any(|(k, v)| {
+                k == key
+                    && match value {
+                        Some(value) => value == v,
+                        None => true,
+                    }
+            })
+        })
+    }
+}
+
+// PBF files start with the length of the first blob header, followed by its `OSMHeader` type
+fn is_pbf(bytes: &[u8]) -> bool {
+    bytes.get(4..15) == Some(b"\x0a\x09OSMHeader")
+}
+
+type Tags = Vec<(String, String)>;
+
+struct Way {
+    id: i64,
+    node_ids: Vec<i64>,
+    tags: Tags,
+}
+
+struct Relation {
+    id: i64,
+    // IDs and roles of the member ways, other members are ignored
+    ways: Vec<(i64, String)>,
+    tags: Tags,
+}
+
+#[derive(Default)]
+struct OsmData {
+    coords: HashMap<i64, geo::Coord>,
+    tagged_nodes: Vec<(i64, Tags)>,
+    ways: Vec<Way>,
+    relations: Vec<Relation>,
+}
+
+impl OsmData {
+    fn add_node(&mut self, id: i64, coord: geo::Coord, tags: Tags) {
+        self.coords.insert(id, coord);
+        if !tags.is_empty() {
+            self.tagged_nodes.push((id, tags));
+        }
+    }
+
+    fn features(&self, tag_filter: Option<&OsmTagFilter>) -> Vec<geo_features::Feature> {
+        let is_selected = |tags: &Tags| match tag_filter {
+            Some(tag_filter) => tag_filter.matches(tags),
+            None => !tags.is_empty(),
+        };
+        let mut features = vec![];
+
+        for (id, tags) in &self.tagged_nodes {
+            if let (true, Some(coord)) = (is_selected(tags), self.coords.get(id)) {
+                features.push(build_feature("node", *id, tags, geo::Point(*coord).into()));
+            }
+        }
+
+        let ways_by_id = self
+            .ways
+            .iter()
+            .map(|way| (way.id, way))
+            .collect::<HashMap<_, _>>();
+        for relation in &self.relations {
+            if !is_multipolygon(&relation.tags) || !is_selected(&relation.tags) {
+                continue;
+            }
+            let ways = |role: &'static str| {
+                relation
+                    .ways
+                    .iter()
+                    .filter(move |(_, r)| match role {
+                        // An empty role is treated as outer, as most renderers do
+                        "outer" => r.is_empty() || r == "outer",
+                        _ => r == role,
+                    })
+                    .filter_map(|(id, _)| ways_by_id.get(id))
+                    .map(|way| way.node_ids.as_slice())
+            };
+            let outers = self.rings(ways("outer"));
+            let inners = self.rings(ways("inner"));
+            if let Some(multi_polygon) = build_multi_polygon(outers, inners) {
+                features.push(build_feature(
+                    "relation",
+                    relation.id,
+                    &relation.tags,
+                    multi_polygon.into(),
+                ));
+            }
+        }
+
+        for way in &self.ways {
+            if !is_selected(&way.tags) {
+                continue;
+            }
+            let Some(line_string) = self.line_string(&way.node_ids) else {
+                continue;
+            };
+            let geometry = if line_string.is_closed() && is_area(&way.tags) {
+                geo::Polygon::new(line_string, vec![]).into()
+            } else {
+                line_string.into()
+            };
+            features.push(build_feature("way", way.id, &way.tags, geometry));
+        }
+
+        features
+    }
+
+    // Nodes missing from the data, e.g. outside the bounds of an extract, are skipped.
+    fn line_string(&self, node_ids: &[i64]) -> Option<geo::LineString> {
+        let coords = node_ids
+            .iter()
+            .filter_map(|id| self.coords.get(id).copied())
+            .collect::<Vec<_>>();
+        (coords.len() >= 2).then(|| geo::LineString::new(coords))
+    }
+
+    // Rings are formed by joining ways end to end, in either direction. Rings which can't be
+    // closed, e.g. because a member way is missing from the extract, are dropped.
+    fn rings<'a>(&self, ways: impl Iterator<Item = &'a [i64]>) -> Vec<geo::LineString> {
+        let mut remaining = ways
+            .filter(|node_ids| node_ids.len() >= 2)
+            .collect::<Vec<_>>();
+        let mut rings = vec![];
+        while let Some(first) = remaining.pop() {
+            let mut ring = first.to_vec();
+            while ring.first() != ring.last() {
+                let Some(end) = ring.last().copied() else {
+                    break;
+                };
+                let Some(index) = remaining.iter().position(|node_ids| {
+                    node_ids.first() == Some(&end) || node_ids.last() == Some(&end)
+                }) else {
+                    break;
+                };
+                let next = remaining.swap_remove(index);
+                if next.first() == Some(&end) {
+                    ring.extend(next.iter().skip(1));
+                } else {
+                    ring.extend(next.iter().rev().skip(1));
+                }
+            }
+            if ring.len() >= 4 && ring.first() == ring.last() {
+                if let Some(line_string) = self.line_string(&ring) {
+                    rings.push(line_string);
+                }
+            }
+        }
+        rings
+    }
+}
+
+// Each inner ring is a hole of the outer ring containing it.
+fn build_multi_polygon(
+    outers: Vec<geo::LineString>,
+    inners: Vec<geo::LineString>,
+) -> Option<geo::MultiPolygon> {
+    if outers.is_empty() {
+        return None;
+    }
+    let mut polygons = outers
+        .into_iter()
+        .map(|outer| geo::Polygon::new(outer, vec![]))
+        .collect::<Vec<_>>();
+    for inner in inners {
+        let Some(coord) = inner.0.first().copied() else {
+            continue;
+        };
+        if let Some(polygon) = polygons
+            .iter_mut()
+            .find(|polygon| polygon.intersects(&coord))
+        {
+            polygon.interiors_push(inner);
+        }
+    }
+    Some(geo::MultiPolygon::new(polygons))
+}
+
+fn is_multipolygon(tags: &Tags) -> bool {
+    tags.iter()
+        .any(|(k, v)| k == "type" && (v == "multipolygon" || v == "boundary"))
+}
+
+fn is_area(tags: &Tags) -> bool {
+    let has_tag = |key: &str, value: &str| tags.iter().any(|(k, v)| k == key && v == value);
+    if has_tag("area", "no") {
+        return false;
+    }
+    if has_tag("area", "yes") {
+        return true;
+    }
+    tags.iter().any(|(k, v)| {
+        AREA_KEYS.contains(&k.as_str())
+            && !LINEAR_TAGS.contains(&(k.as_str(), v.as_str()))
+            && v != "no"
+    })
+}
+
+fn build_feature(
+    osm_type: &str,
+    id: i64,
+    tags: &Tags,
+    geometry: geo::Geometry,
+) -> geo_features::Feature {
+    let mut properties = tags
+        .iter()
+        .map(|(k, v)| (k.clone(), geo_features::Value::String(v.clone())))
+        .collect::<geo_features::Properties>();
+    properties.insert(
+        "osm_type".into(),
+        geo_features::Value::String(osm_type.into()),
+    );
+    properties.insert("osm_id".into(), geo_features::Value::String(id.to_string()));
+    geo_features::FeatureBuilder::new()
+        .with_geometry(geometry)
+        .with_properties(properties)
+        .build()
+}
+
+fn read_pbf(bytes: &[u8]) -> Result<OsmData, crate::Error> {
+    let tags = |tags: &mut dyn Iterator<Item = (&str, &str)>| {
+        tags.map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<Tags>()
+    };
+    let mut data = OsmData::default();
+    osmpbf::ElementReader::new(io::Cursor::new(bytes)).for_each(|element| match element {
+        osmpbf::Element::Node(node) => data.add_node(
+            node.id(),
+            geo::coord! { x: node.lon(), y: node.lat() },
+            tags(&mut node.tags()),
+        ),
+        osmpbf::Element::DenseNode(node) => data.add_node(
+            node.id(),
+            geo::coord! { x: node.lon(), y: node.lat() },
+            tags(&mut node.tags()),
+        ),
+        osmpbf::Element::Way(way) => data.ways.push(Way {
+            id: way.id(),
+            node_ids: way.refs().collect(),
+            tags: tags(&mut way.tags()),
+        }),
+        osmpbf::Element::Relation(relation) => data.relations.push(Relation {
+            id: relation.id(),
+            ways: relation
+                .members()
+                .filter(|member| member.member_type == osmpbf::RelMemberType::Way)
+                .map(|member| {
+                    let role = member.role().unwrap_or_default().to_string();
+                    (member.member_id, role)
+                })
+                .collect(),
+            tags: tags(&mut relation.tags()),
+        }),
+    })?;
+    Ok(data)
+}
+
+// The element being read, until its end tag
+enum XmlElement {
+    Node(i64, geo::Coord, Tags),
+    Way(Way),
+    Relation(Relation),
+}
+
+fn read_xml(xml: &[u8]) -> Result<OsmData, crate::Error> {
+    let mut reader = quick_xml::Reader::from_reader(xml);
+    reader.trim_text(true).expand_empty_elements(true);
+
+    let mut buf = vec![];
+    let mut data = OsmData::default();
+    let mut element: Option<XmlElement> = None;
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(start) => match start.local_name().as_ref() {
+                b"node" => {
+                    let coord = geo::coord! {
+                        x: parse_attribute(&start, "lon")?,
+                        y: parse_attribute(&start, "lat")?,
+                    };
+                    element = Some(XmlElement::Node(
+                        parse_attribute(&start, "id")?,
+                        coord,
+                        vec![],
+                    ));
+                }
+                b"way" => {
+                    element = Some(XmlElement::Way(Way {
+                        id: parse_attribute(&start, "id")?,
+                        node_ids: vec![],
+                        tags: vec![],
+                    }));
+                }
+                b"relation" => {
+                    element = Some(XmlElement::Relation(Relation {
+                        id: parse_attribute(&start, "id")?,
+                        ways: vec![],
+                        tags: vec![],
+                    }));
+                }
+                b"tag" => {
+                    let tag = (attribute(&start, "k")?, attribute(&start, "v")?);
+                    match element {
+                        Some(XmlElement::Node(_, _, ref mut tags)) => tags.push(tag),
+                        Some(XmlElement::Way(ref mut way)) => way.tags.push(tag),
+                        Some(XmlElement::Relation(ref mut relation)) => relation.tags.push(tag),
+                        None => (),
+                    }
+                }
+                b"nd" => {
+                    if let Some(XmlElement::Way(ref mut way)) = element {
+                        way.node_ids.push(parse_attribute(&start, "ref")?);
+                    }
+                }
+                b"member" => {
+                    if let Some(XmlElement::Relation(ref mut relation)) = element {
+                        if attribute(&start, "type")? == "way" {
+                            relation.ways.push((
+                                parse_attribute(&start, "ref")?,
+                                attribute(&start, "role")?,
+                            ));
+                        }
+                    }
+                }
+                _ => (),
+            },
+            Event::End(end) => {
+                let is_element_end =
+                    matches!(end.local_name().as_ref(), b"node" | b"way" | b"relation");
+                if is_element_end {
+                    match element.take() {
+                        Some(XmlElement::Node(id, coord, tags)) => data.add_node(id, coord, tags),
+                        Some(XmlElement::Way(way)) => data.ways.push(way),
+                        Some(XmlElement::Relation(relation)) => data.relations.push(relation),
+                        None => (),
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    Ok(data)
+}
+
+// Missing attributes are read as empty strings
+fn attribute(start: &BytesStart, name: &str) -> Result<String, crate::Error> {
+    Ok(start
+        .try_get_attribute(name)?
+        .map(|attribute| attribute.unescape_value())
+        .transpose()?
+        .map(|value| value.into_owned())
+        .unwrap_or_default())
+}
+
+fn parse_attribute<T: std::str::FromStr>(
+    start: &BytesStart,
+    name: &str,
+) -> Result<T, crate::Error> {
+    attribute(start, name)?
+        .parse()
+        .map_err(|_| crate::Error::InvalidOsmAttribute(name.into()))
+}
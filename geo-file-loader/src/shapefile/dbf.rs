@@ -0,0 +1,315 @@
+// Minimal dBASE reader for shapefile attributes. The text encoding comes from the .cpg sidecar
+// file; without one, text is decoded as UTF-8, falling back to Windows-1252.
+
+const HEADER_LEN: usize = 32;
+const FIELD_DESCRIPTOR_LEN: usize = 32;
+const FIELD_DESCRIPTOR_TERMINATOR: u8 = 0x0D;
+const DELETED_RECORD: u8 = b'*';
+
+struct Field {
+    name: String,
+    kind: u8,
+    len: usize,
+}
+
+/// Properties of every record, in the same order as the shapes in the .shp file. Deleted records
+/// are `None`.
+pub(super) fn read(
+    bytes: &[u8],
+    cpg: Option<&str>,
+) -> Result<Vec<Option<geo_features::Properties>>, crate::Error> {
+    let encoding = cpg.and_then(encoding_from_cpg);
+    let header = bytes.get(..HEADER_LEN).ok_or(crate::Error::InvalidDbf)?;
+    let num_records = read_u32(header, 4)? as usize;
+    let header_len = usize::from(read_u16(header, 8)?);
+    let record_len = usize::from(read_u16(header, 10)?);
+
+    let fields = bytes
+        .get(HEADER_LEN..header_len)
+        .ok_or(crate::Error::InvalidDbf)?
+        .chunks_exact(FIELD_DESCRIPTOR_LEN)
+        .take_while(|descriptor| descriptor.first() != Some(&FIELD_DESCRIPTOR_TERMINATOR))
+        .map(|descriptor| {
+            let name = descriptor.get(..11).ok_or(crate::Error::InvalidDbf)?;
+            let name_len = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+            Ok(Field {
+                name: decode_text(name.get(..name_len).unwrap_or_default(), encoding),
+                kind: *descriptor.get(11).ok_or(crate::Error::InvalidDbf)?,
+                len: usize::from(*descriptor.get(16).ok_or(crate::Error::InvalidDbf)?),
+            })
+        })
+        .collect::<Result<Vec<_>, crate::Error>>()?;
+
+    (0..num_records)
+        .map(|i| {
+            let start = header_len + i * record_len;
+            let record = bytes
+                .get(start..start + record_len)
+                .ok_or(crate::Error::InvalidDbf)?;
+            // The first byte of a record is the deletion flag.
+            if record.first() == Some(&DELETED_RECORD) {
+                return Ok(None);
+            }
+            let mut offset = 1;
+            let mut properties = geo_features::Properties::new();
+            for field in &fields {
+                let raw = record
+                    .get(offset..offset + field.len)
+                    .ok_or(crate::Error::InvalidDbf)?;
+                offset += field.len;
+                properties.insert(field.name.clone(), parse_value(field.kind, raw, encoding));
+            }
+            Ok(Some(properties))
+        })
+        .collect()
+}
+
+fn parse_value(
+    kind: u8,
+    raw: &[u8],
+    encoding: Option<&'static encoding_rs::Encoding>,
+) -> geo_features::Value {
+    match kind {
+        // Numeric and floating point, stored as text
+        b'N' | b'F' => match std::str::from_utf8(raw).map(str::trim) {
+            Ok(s) => s
+                .parse::<f64>()
+                .map_or(geo_features::Value::Null, geo_features::Value::Number),
+            Err(_) => geo_features::Value::Null,
+        },
+        // Logical
+        b'L' => match raw.first() {
+            Some(b'T' | b't' | b'Y' | b'y') => geo_features::Value::Boolean(true),
+            Some(b'F' | b'f' | b'N' | b'n') => geo_features::Value::Boolean(false),
+            _ => geo_features::Value::Null,
+        },
+        // Date, stored as YYYYMMDD
+        b'D' => match raw {
+            [y1, y2, y3, y4, m1, m2, d1, d2] if raw.iter().all(u8::is_ascii_digit) => {
+                geo_features::Value::String(
+                    String::from_utf8_lossy(&[*y1, *y2, *y3, *y4, b'-', *m1, *m2, b'-', *d1, *d2])
+                        .into_owned(),
+                )
+            }
+            _ => geo_features::Value::Null,
+        },
+        // Integer and double, stored as little-endian binary
+        b'I' => match <[u8; 4]>::try_from(raw) {
+            Ok(n) => geo_features::Value::Number(f64::from(i32::from_le_bytes(n))),
+            Err(_) => geo_features::Value::Null,
+        },
+        b'O' => match <[u8; 8]>::try_from(raw) {
+            Ok(n) => geo_features::Value::Number(f64::from_le_bytes(n)),
+            Err(_) => geo_features::Value::Null,
+        },
+        // Memo fields point into a separate .dbt file, which shapefiles don't have
+        b'M' => geo_features::Value::Null,
+        _ => {
+            let text = decode_text(raw, encoding);
+            let text = text.trim_end_matches([' ', '\0']);
+            if text.is_empty() {
+                geo_features::Value::Null
+            } else {
+                geo_features::Value::String(text.to_owned())
+            }
+        }
+    }
+}
+
+fn decode_text(raw: &[u8], encoding: Option<&'static encoding_rs::Encoding>) -> String {
+    match encoding {
+        Some(encoding) => encoding.decode_without_bom_handling(raw).0.into_owned(),
+        None => match std::str::from_utf8(raw) {
+            Ok(s) => s.to_owned(),
+            Err(_) => encoding_rs::WINDOWS_1252
+                .decode_without_bom_handling(raw)
+                .0
+                .into_owned(),
+        },
+    }
+}
+
+// .cpg files hold either an encoding label ("UTF-8", "ISO-8859-1") or a Windows code page
+// number ("1252", "ANSI 1252").
+fn encoding_from_cpg(cpg: &str) -> Option<&'static encoding_rs::Encoding> {
+    let label = cpg.trim();
+    let label = label.strip_prefix("ANSI ").unwrap_or(label);
+    match label {
+        "65001" => Some(encoding_rs::UTF_8),
+        "88591" => Some(encoding_rs::WINDOWS_1252),
+        _ if label.bytes().all(|b| b.is_ascii_digit()) => {
+            encoding_rs::Encoding::for_label(format!("windows-{label}").as_bytes())
+                .or_else(|| encoding_rs::Encoding::for_label(format!("cp{label}").as_bytes()))
+        }
+        _ => encoding_rs::Encoding::for_label(label.as_bytes()),
+    }
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, crate::Error> {
+    bytes
+        .get(offset..offset + 2)
+        .and_then(|b| b.try_into().ok())
+        .map(u16::from_le_bytes)
+        .ok_or(crate::Error::InvalidDbf)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, crate::Error> {
+    bytes
+        .get(offset..offset + 4)
+        .and_then(|b| b.try_into().ok())
+        .map(u32::from_le_bytes)
+        .ok_or(crate::Error::InvalidDbf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A .dbf file with the given fields (name, kind, length) and records (deletion flag, values)
+    fn dbf(fields: &[(&str, u8, u8)], records: &[(u8, &[&[u8]])]) -> Vec<u8> {
+        let header_len = HEADER_LEN + fields.len() * FIELD_DESCRIPTOR_LEN + 1;
+        let record_len = 1 + fields
+            .iter()
+            .map(|(_, _, len)| usize::from(*len))
+            .sum::<usize>();
+        let mut bytes = vec![0x03, 124, 1, 1];
+        bytes.extend((records.len() as u32).to_le_bytes());
+        bytes.extend((header_len as u16).to_le_bytes());
+        bytes.extend((record_len as u16).to_le_bytes());
+        bytes.resize(HEADER_LEN, 0);
+        for (name, kind, len) in fields {
+            let mut descriptor = [0; 11];
+            for (byte, name_byte) in descriptor.iter_mut().zip(name.bytes()) {
+                *byte = name_byte;
+            }
+            bytes.extend(descriptor.get(..11).unwrap_or_default());
+            bytes.push(*kind);
+            bytes.extend([0; 4]);
+            bytes.push(*len);
+            bytes.extend([0; FIELD_DESCRIPTOR_LEN - 17]);
+        }
+        bytes.push(FIELD_DESCRIPTOR_TERMINATOR);
+        for (flag, values) in records {
+            bytes.push(*flag);
+            for ((_, _, len), value) in fields.iter().zip(*values) {
+                let mut value = value.to_vec();
+                value.resize(usize::from(*len), b' ');
+                bytes.extend(value);
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn field_kinds() -> Result<(), crate::Error> {
+        let bytes = dbf(
+            &[
+                ("NAME", b'C', 10),
+                ("POP", b'N', 8),
+                ("OPEN", b'L', 1),
+                ("FOUNDED", b'D', 8),
+                ("ID", b'I', 4),
+            ],
+            &[(
+                b' ',
+                &[
+                    b"Lisbon",
+                    b"  545000",
+                    b"T",
+                    b"11470101",
+                    &7i32.to_le_bytes(),
+                ],
+            )],
+        );
+        let records = read(&bytes, None)?;
+        let [Some(properties)] = records.as_slice() else {
+            panic!("expected a single record");
+        };
+        assert!(
+            matches!(properties.get("NAME"), Some(geo_features::Value::String(s)) if s == "Lisbon")
+        );
+        assert!(
+            matches!(properties.get("POP"), Some(geo_features::Value::Number(n)) if *n == 545000.)
+        );
+        assert!(matches!(
+            properties.get("OPEN"),
+            Some(geo_features::Value::Boolean(true))
+        ));
+        assert!(
+            matches!(properties.get("FOUNDED"), Some(geo_features::Value::String(s)) if s == "1147-01-01")
+        );
+        assert!(matches!(properties.get("ID"), Some(geo_features::Value::Number(n)) if *n == 7.));
+        Ok(())
+    }
+
+    #[test]
+    fn blank_values_are_null() -> Result<(), crate::Error> {
+        let bytes = dbf(
+            &[("NAME", b'C', 4), ("POP", b'N', 4), ("OPEN", b'L', 1)],
+            &[(b' ', &[b"", b"", b"?"])],
+        );
+        let records = read(&bytes, None)?;
+        let [Some(properties)] = records.as_slice() else {
+            panic!("expected a single record");
+        };
+        assert_eq!(properties.len(), 3);
+        assert!(properties
+            .values()
+            .all(|value| matches!(value, geo_features::Value::Null)));
+        Ok(())
+    }
+
+    #[test]
+    fn deleted_records_keep_their_position() -> Result<(), crate::Error> {
+        let bytes = dbf(
+            &[("NAME", b'C', 4)],
+            &[(b' ', &[b"a"]), (DELETED_RECORD, &[b"b"]), (b' ', &[b"c"])],
+        );
+        let records = read(&bytes, None)?;
+        let [Some(first), None, Some(third)] = records.as_slice() else {
+            panic!("expected the second of three records to be deleted");
+        };
+        assert!(matches!(first.get("NAME"), Some(geo_features::Value::String(s)) if s == "a"));
+        assert!(matches!(third.get("NAME"), Some(geo_features::Value::String(s)) if s == "c"));
+        Ok(())
+    }
+
+    #[test]
+    fn text_encodings() -> Result<(), crate::Error> {
+        let bytes = dbf(&[("NAME", b'C', 8)], &[(b' ', &[b"S\xe3o"])]);
+        for cpg in [None, Some("1252"), Some("ANSI 1252"), Some("ISO-8859-1")] {
+            let records = read(&bytes, cpg)?;
+            let [Some(properties)] = records.as_slice() else {
+                panic!("expected a single record");
+            };
+            assert!(
+                matches!(properties.get("NAME"), Some(geo_features::Value::String(s)) if s == "São"),
+                "{cpg:?}"
+            );
+        }
+
+        let bytes = dbf(&[("NAME", b'C', 8)], &[(b' ', &["São".as_bytes()])]);
+        for cpg in [None, Some("UTF-8"), Some("65001")] {
+            let records = read(&bytes, cpg)?;
+            let [Some(properties)] = records.as_slice() else {
+                panic!("expected a single record");
+            };
+            assert!(
+                matches!(properties.get("NAME"), Some(geo_features::Value::String(s)) if s == "São"),
+                "{cpg:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = dbf(&[("NAME", b'C', 8)], &[(b' ', &[b"a"]), (b' ', &[b"b"])]);
+        let truncated = bytes.get(..bytes.len() - 4).unwrap_or_default();
+        assert!(matches!(
+            read(truncated, None),
+            Err(crate::Error::InvalidDbf)
+        ));
+        assert!(matches!(read(&[0x03], None), Err(crate::Error::InvalidDbf)));
+    }
+}
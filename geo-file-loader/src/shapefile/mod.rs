@@ -0,0 +1,133 @@
+use std::io::{self, Read, Write};
+
+mod dbf;
+
+/// A shapefile is loaded from either a bare `.shp` file or a `.zip` bundle containing the `.shp`
+/// file along with its `.dbf`, `.prj`, and `.cpg` sidecar files.
+pub struct ShapefileSource {
+    pub bytes: bytes::Bytes,
+}
+
+impl crate::FileLoader for ShapefileSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        ShapefileSource { bytes }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let files = if is_zip(&self.bytes) {
+            ShapefileFiles::from_zip(&self.bytes)?
+        } else {
+            ShapefileFiles {
+                shp: self.bytes.to_vec(),
+                ..Default::default()
+            }
+        };
+
+        let mut feature_collection = read_features(&files.shp)?;
+        if let Some(ref dbf) = files.dbf {
+            // The nth record of the .dbf file holds the attributes of the nth shape
+            let records = dbf::read(dbf, files.cpg.as_deref())?;
+            let mut records = records.into_iter();
+            feature_collection
+                .features
+                .retain_mut(|feature| match records.next() {
+                    Some(Some(properties)) => {
+                        feature.properties = properties;
+                        true
+                    }
+                    Some(None) => false,
+                    None => true,
+                });
+        }
+
+        Ok(crate::LoadedFile {
+            feature_collection,
+            crs_epsg_code: files
+                .prj
+                .as_deref()
+                .and_then(crate::crs::epsg_code_from_wkt),
+        })
+    }
+}
+
+/// Bundle the files of a shapefile the user selected individually (`.shp`, `.dbf`, `.prj`, …)
+/// into a `.zip` that `ShapefileSource` can load.
+pub fn bundle_shapefile_files(
+    files: impl IntoIterator<Item = (String, Vec<u8>)>,
+) -> Result<Vec<u8>, crate::Error> {
+    let mut zip_writer = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for (file_name, bytes) in files {
+        zip_writer.start_file(file_name, options)?;
+        zip_writer.write_all(&bytes)?;
+    }
+    Ok(zip_writer.finish()?.into_inner())
+}
+
+#[derive(Default)]
+struct ShapefileFiles {
+    shp: Vec<u8>,
+    dbf: Option<Vec<u8>>,
+    prj: Option<String>,
+    cpg: Option<String>,
+}
+
+impl ShapefileFiles {
+    fn from_zip(bytes: &[u8]) -> Result<Self, crate::Error> {
+        let mut archive = zip::ZipArchive::new(io::Cursor::new(bytes))?;
+        let file_names = archive
+            .file_names()
+            .filter(|name| !name.starts_with("__MACOSX/"))
+            .map(String::from)
+            .collect::<Vec<_>>();
+
+        // Sidecar files share the name of the .shp file, only differing by extension.
+        let shp_name = file_names
+            .iter()
+            .filter(|name| has_extension(name, "shp"))
+            .min()
+            .ok_or(crate::Error::MissingShp)?;
+        let (stem, _) = shp_name.rsplit_once('.').ok_or(crate::Error::MissingShp)?;
+        let mut read_sidecar = |extension: &str| -> Result<Option<Vec<u8>>, crate::Error> {
+            let Some(name) = file_names.iter().find(|name| {
+                name.len() == stem.len() + 1 + extension.len()
+                    && name.starts_with(stem)
+                    && has_extension(name, extension)
+            }) else {
+                return Ok(None);
+            };
+            let mut bytes = vec![];
+            archive.by_name(name)?.read_to_end(&mut bytes)?;
+            Ok(Some(bytes))
+        };
+
+        Ok(ShapefileFiles {
+            shp: read_sidecar("shp")?.ok_or(crate::Error::MissingShp)?,
+            dbf: read_sidecar("dbf")?,
+            prj: read_sidecar("prj")?.map(|bytes| String::from_utf8_lossy(&bytes).into_owned()),
+            cpg: read_sidecar("cpg")?.map(|bytes| String::from_utf8_lossy(&bytes).into_owned()),
+        })
+    }
+}
+
+// geozero-shp decodes .dbf attributes without honoring the .cpg encoding, so it only reads the
+// shapes, each one becoming a feature even when it's empty.
+fn read_features(shp: &[u8]) -> Result<geo_features::FeatureCollection, crate::Error> {
+    let shapefile_reader = geozero_shp::Reader::new(io::Cursor::new(shp))?;
+    let mut writer = crate::feature_collection_writer::FeatureCollectionWriter::new();
+    for result in shapefile_reader.iter_features(&mut writer)? {
+        result?;
+    }
+    Ok(writer.finish())
+}
+
+fn is_zip(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"PK\x03\x04")
+}
+
+fn has_extension(file_name: &str, extension: &str) -> bool {
+    file_name
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| ext.eq_ignore_ascii_case(extension))
+}
@@ -0,0 +1,249 @@
+// TopoJSON stores each line once, as an arc shared by every geometry it borders. Geometries only
+// reference their arcs by index, with negative (one's complement) indices for reversed arcs.
+
+use serde_json::Value as Json;
+
+pub struct TopoJsonSource {
+    pub bytes: bytes::Bytes,
+    /// Name of the object in the topology to load. When `None`, the first one is loaded.
+    pub object: Option<String>,
+}
+
+impl crate::FileLoader for TopoJsonSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        TopoJsonSource {
+            bytes,
+            object: None,
+        }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let topology = serde_json::from_slice::<Json>(&self.bytes)?;
+        let objects = objects(&topology)?;
+        let object = match self.object {
+            Some(ref name) => objects
+                .get(name)
+                .ok_or_else(|| crate::Error::MissingTopoJsonObject(name.clone()))?,
+            None => objects.values().next().ok_or(crate::Error::NoGeometry)?,
+        };
+
+        let transform = Transform::from_topology(&topology);
+        let arcs = topology
+            .get("arcs")
+            .and_then(Json::as_array)
+            .map(|arcs| {
+                arcs.iter()
+                    .map(|arc| decode_arc(arc, transform.as_ref()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let decoder = Decoder {
+            arcs: &arcs,
+            transform: transform.as_ref(),
+        };
+
+        // A `GeometryCollection` object holds one feature per geometry, anything else is a
+        // single feature.
+        let features = match object.get("type").and_then(Json::as_str) {
+            Some("GeometryCollection") => object
+                .get("geometries")
+                .and_then(Json::as_array)
+                .map(|geometries| {
+                    geometries
+                        .iter()
+                        .map(|geometry| decoder.feature(geometry))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()?
+                .unwrap_or_default(),
+            _ => vec![decoder.feature(object)?],
+        };
+
+        Ok(geo_features::FeatureCollection::from_features(features).into())
+    }
+}
+
+/// Names of the objects in a TopoJSON topology, so the user can pick which ones to load.
+pub fn topojson_objects(bytes: &[u8]) -> Result<Vec<String>, crate::Error> {
+    let topology = serde_json::from_slice::<Json>(bytes)?;
+    Ok(objects(&topology)?.keys().cloned().collect())
+}
+
+fn objects(topology: &Json) -> Result<&serde_json::Map<String, Json>, crate::Error> {
+    if topology.get("type").and_then(Json::as_str) != Some("Topology") {
+        return Err(crate::Error::InvalidTopoJson);
+    }
+    topology
+        .get("objects")
+        .and_then(Json::as_object)
+        .ok_or(crate::Error::InvalidTopoJson)
+}
+
+// Quantized topologies store integer positions, which the transform maps back to coordinates.
+struct Transform {
+    scale: [f64; 2],
+    translate: [f64; 2],
+}
+
+impl Transform {
+    fn from_topology(topology: &Json) -> Option<Self> {
+        let transform = topology.get("transform")?;
+        Some(Transform {
+            scale: pair(transform.get("scale")?)?,
+            translate: pair(transform.get("translate")?)?,
+        })
+    }
+
+    fn apply(&self, [x, y]: [f64; 2]) -> geo::Coord {
+        geo::coord! {
+            x: x * self.scale[0] + self.translate[0],
+            y: y * self.scale[1] + self.translate[1],
+        }
+    }
+}
+
+// Positions of quantized arcs are deltas from the previous position.
+fn decode_arc(arc: &Json, transform: Option<&Transform>) -> Vec<geo::Coord> {
+    let positions = arc
+        .as_array()
+        .map(|positions| positions.iter().filter_map(pair))
+        .into_iter()
+        .flatten();
+    match transform {
+        Some(transform) => {
+            let mut position = [0., 0.];
+            positions
+                .map(|[dx, dy]| {
+                    position = [position[0] + dx, position[1] + dy];
+                    transform.apply(position)
+                })
+                .collect()
+        }
+        None => positions.map(|[x, y]| geo::coord! { x: x, y: y }).collect(),
+    }
+}
+
+fn pair(json: &Json) -> Option<[f64; 2]> {
+    match json.as_array()?.as_slice() {
+        [x, y, ..] => Some([x.as_f64()?, y.as_f64()?]),
+        _ => None,
+    }
+}
+
+struct Decoder<'a> {
+    arcs: &'a [Vec<geo::Coord>],
+    transform: Option<&'a Transform>,
+}
+
+impl<'a> Decoder<'a> {
+    fn feature(&self, object: &Json) -> Result<geo_features::Feature, crate::Error> {
+        let mut properties: geo_features::Properties = object
+            .get("properties")
+            .and_then(Json::as_object)
+            .map(|properties| {
+                properties
+                    .iter()
+                    .map(|(key, value)| (key.clone(), crate::geojson::json_to_value(value)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        // Boundary sets usually identify each geometry (e.g. with a FIPS code) by its `id`
+        if let Some(id) = object.get("id") {
+            properties.insert("id".into(), crate::geojson::json_to_value(id));
+        }
+        let mut builder = geo_features::FeatureBuilder::new().with_properties(properties);
+        if let Some(geometry) = self.geometry(object)? {
+            builder = builder.with_geometry(geometry);
+        }
+        Ok(builder.build())
+    }
+
+    fn geometry(&self, object: &Json) -> Result<Option<geo::Geometry>, crate::Error> {
+        let coordinates = object.get("coordinates");
+        let arcs = object.get("arcs");
+        Ok(Some(match object.get("type").and_then(Json::as_str) {
+            Some("Point") => geo::Point(self.position(coordinates)?).into(),
+            Some("MultiPoint") => geo::MultiPoint::new(
+                nested(coordinates)?
+                    .iter()
+                    .map(|position| Ok(geo::Point(self.position(Some(position))?)))
+                    .collect::<Result<_, crate::Error>>()?,
+            )
+            .into(),
+            Some("LineString") => self.line(arcs)?.into(),
+            Some("MultiLineString") => geo::MultiLineString::new(
+                nested(arcs)?
+                    .iter()
+                    .map(|line| self.line(Some(line)))
+                    .collect::<Result<_, _>>()?,
+            )
+            .into(),
+            Some("Polygon") => self.polygon(arcs)?.into(),
+            Some("MultiPolygon") => geo::MultiPolygon::new(
+                nested(arcs)?
+                    .iter()
+                    .map(|polygon| self.polygon(Some(polygon)))
+                    .collect::<Result<_, _>>()?,
+            )
+            .into(),
+            Some("GeometryCollection") => {
+                geo::Geometry::GeometryCollection(geo::GeometryCollection(
+                    nested(object.get("geometries"))?
+                        .iter()
+                        .filter_map(|geometry| self.geometry(geometry).transpose())
+                        .collect::<Result<_, _>>()?,
+                ))
+            }
+            // Features without a geometry have a `null` type
+            _ => return Ok(None),
+        }))
+    }
+
+    // Unlike arcs, the positions of points aren't delta-encoded.
+    fn position(&self, json: Option<&Json>) -> Result<geo::Coord, crate::Error> {
+        let position = json.and_then(pair).ok_or(crate::Error::InvalidTopoJson)?;
+        Ok(match self.transform {
+            Some(transform) => transform.apply(position),
+            None => geo::coord! { x: position[0], y: position[1] },
+        })
+    }
+
+    fn polygon(&self, rings: Option<&Json>) -> Result<geo::Polygon, crate::Error> {
+        let mut rings = nested(rings)?
+            .iter()
+            .map(|ring| self.line(Some(ring)))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter();
+        let exterior = rings.next().unwrap_or_else(|| geo::LineString::new(vec![]));
+        Ok(geo::Polygon::new(exterior, rings.collect()))
+    }
+
+    // Consecutive arcs share their end and start positions, so the duplicate is skipped.
+    fn line(&self, arc_indices: Option<&Json>) -> Result<geo::LineString, crate::Error> {
+        let mut coords = Vec::<geo::Coord>::new();
+        for index in nested(arc_indices)? {
+            let index = index.as_i64().ok_or(crate::Error::InvalidTopoJson)?;
+            let (arc_index, is_reversed) = if index < 0 {
+                (!index, true)
+            } else {
+                (index, false)
+            };
+            let arc = usize::try_from(arc_index)
+                .ok()
+                .and_then(|arc_index| self.arcs.get(arc_index))
+                .ok_or(crate::Error::InvalidTopoJson)?;
+            let skip = usize::from(!coords.is_empty());
+            if is_reversed {
+                coords.extend(arc.iter().rev().skip(skip));
+            } else {
+                coords.extend(arc.iter().skip(skip));
+            }
+        }
+        Ok(geo::LineString::new(coords))
+    }
+}
+
+fn nested(json: Option<&Json>) -> Result<&Vec<Json>, crate::Error> {
+    json.and_then(Json::as_array)
+        .ok_or(crate::Error::InvalidTopoJson)
+}
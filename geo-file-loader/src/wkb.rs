@@ -0,0 +1,128 @@
+use geozero::ToGeo;
+
+// EWKB sets these bits of the geometry type for Z, M, and an SRID following the type.
+const EWKB_Z_FLAG: u32 = 0x8000_0000;
+const EWKB_M_FLAG: u32 = 0x4000_0000;
+const EWKB_SRID_FLAG: u32 = 0x2000_0000;
+
+/// Either a binary WKB/EWKB file, or hex-encoded WKB/EWKB text with one geometry per line (as
+/// copied from PostGIS query results).
+pub struct WkbSource {
+    pub bytes: bytes::Bytes,
+}
+
+impl crate::FileLoader for WkbSource {
+    fn from_bytes(bytes: bytes::Bytes) -> Self {
+        WkbSource { bytes }
+    }
+
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
+        let wkbs = match hex_lines(&self.bytes) {
+            Some(lines) => lines.map(decode_hex).collect::<Result<Vec<_>, _>>()?,
+            None => vec![self.bytes.to_vec()],
+        };
+        let features = wkbs
+            .iter()
+            .map(|wkb| {
+                let geometry = if is_ewkb(wkb) {
+                    geozero::wkb::Ewkb(wkb).to_geo()?
+                } else {
+                    geozero::wkb::Wkb(wkb).to_geo()?
+                };
+                Ok(geo_features::FeatureBuilder::new()
+                    .with_geometry(geometry)
+                    .build())
+            })
+            .collect::<Result<Vec<_>, crate::Error>>()?;
+        if features.is_empty() {
+            return Err(crate::Error::NoGeometry);
+        }
+        Ok(crate::LoadedFile {
+            feature_collection: geo_features::FeatureCollection::from_features(features),
+            crs_epsg_code: wkbs.first().and_then(|wkb| ewkb_srid(wkb)),
+        })
+    }
+}
+
+/// SRID of the first geometry of EWKB input, binary or hex-encoded, without decoding it.
+pub fn wkb_srid(bytes: &[u8]) -> Option<u16> {
+    match hex_lines(bytes) {
+        // Only the byte order, type, and SRID are needed
+        Some(mut lines) => ewkb_srid(&decode_hex(lines.next()?.get(..18)?).ok()?),
+        None => ewkb_srid(bytes),
+    }
+}
+
+fn ewkb_srid(wkb: &[u8]) -> Option<u16> {
+    let geometry_type = geometry_type(wkb)?;
+    if geometry_type & EWKB_SRID_FLAG == 0 {
+        return None;
+    }
+    let srid = read_u32(wkb.get(5..9)?, is_little_endian(wkb)?)?;
+    u16::try_from(srid).ok().filter(|srid| *srid != 0)
+}
+
+// ISO WKB encodes Z and M as thousands of the geometry type instead, which geozero's EWKB reader
+// doesn't understand.
+fn is_ewkb(wkb: &[u8]) -> bool {
+    geometry_type(wkb).is_some_and(|t| t & (EWKB_Z_FLAG | EWKB_M_FLAG | EWKB_SRID_FLAG) != 0)
+}
+
+fn geometry_type(wkb: &[u8]) -> Option<u32> {
+    read_u32(wkb.get(1..5)?, is_little_endian(wkb)?)
+}
+
+fn is_little_endian(wkb: &[u8]) -> Option<bool> {
+    match wkb.first()? {
+        0 => Some(false),
+        1 => Some(true),
+        _ => None,
+    }
+}
+
+fn read_u32(bytes: &[u8], is_little_endian: bool) -> Option<u32> {
+    let bytes = <[u8; 4]>::try_from(bytes).ok()?;
+    Some(if is_little_endian {
+        u32::from_le_bytes(bytes)
+    } else {
+        u32::from_be_bytes(bytes)
+    })
+}
+
+// The non-empty lines of hex-encoded input, `None` if the input isn't hex. PostgreSQL prefixes
+// `bytea` output with `\x`.
+fn hex_lines(bytes: &[u8]) -> Option<impl Iterator<Item = &str>> {
+    let text = std::str::from_utf8(bytes).ok()?;
+    let lines = text
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            line.strip_prefix("\\x")
+                .or_else(|| line.strip_prefix("0x"))
+                .unwrap_or(line)
+        })
+        .filter(|line| !line.is_empty());
+    if lines.clone().next().is_none()
+        || !lines
+            .clone()
+            .all(|line| line.bytes().all(|b| b.is_ascii_hexdigit()))
+    {
+        return None;
+    }
+    Some(lines)
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, crate::Error> {
+    let pairs = hex.as_bytes().chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err(crate::Error::InvalidHex);
+    }
+    pairs
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or(crate::Error::InvalidHex)
+        })
+        .collect()
+}
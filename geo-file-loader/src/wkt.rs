@@ -17,14 +17,14 @@ impl crate::FileLoader for WktSource {
         WktSource { bytes }
     }
 
-    fn load(self) -> Result<geo_features::FeatureCollection, crate::Error> {
+    fn load(self) -> Result<crate::LoadedFile, crate::Error> {
         let mut bytes_cursor = io::Cursor::new(&self.bytes);
         let mut wkt_reader = geozero::wkt::WktReader(&mut bytes_cursor);
         let mut geo_writer = geozero::geo_types::GeoWriter::new();
         wkt_reader.process(&mut geo_writer)?;
         match geo_writer.take_geometry() {
-            Some(geometry) => Ok(geo_features::FeatureCollection::from_geometry(geometry)),
-            None => Ok(geo_features::FeatureCollection::default()),
+            Some(geometry) => Ok(geo_features::FeatureCollection::from_geometry(geometry).into()),
+            None => Ok(geo_features::FeatureCollection::default().into()),
         }
     }
 }
@@ -9,6 +9,10 @@
 use bevy::prelude::*;
 use clap::{Arg, ArgAction, Command};
 
+mod render;
+
+pub use render::{render, RenderArgs, StyleArg, StyleSpec};
+
 static DEFAULT_MSAA: &str = "4";
 
 type MsaaSampleCount = u32;
@@ -18,7 +22,14 @@ pub struct Values {
     pub msaa_sample_count: MsaaSampleCount,
 }
 
-pub fn run() -> Result<Values, String> {
+pub enum Mode {
+    /// Open the viewer
+    Viewer(Values),
+    /// Render files to an image, without opening a window
+    Render(RenderArgs),
+}
+
+pub fn run() -> Result<Mode, String> {
     let matches = Command::new("rgis")
         .author("Corey Farwell <coreyf@rwell.org>")
         .about("Geospatial data viewer written in Rust")
@@ -30,13 +41,18 @@ pub fn run() -> Result<Values, String> {
                 .help("Multi-Sample Anti-Aliasing sample count. Setting the sample count higher will result in smoother edges, but it will also increase the cost to render those edges. The range should generally be somewhere between 1 (no multi sampling, but cheap) to 8 (crisp but expensive).")
                 .value_parser(clap::value_parser!(u32))
         )
+        .subcommand(render::command())
         .get_matches();
 
-    Ok(Values {
+    if let Some(render_matches) = matches.subcommand_matches("render") {
+        return Ok(Mode::Render(RenderArgs::from_matches(render_matches)?));
+    }
+
+    Ok(Mode::Viewer(Values {
         msaa_sample_count: *matches
             .get_one::<MsaaSampleCount>("MSAA SAMPLE COUNT")
             .ok_or("Could not fetch MSAA sample count from clap")?,
-    })
+    }))
 }
 
 #[derive(Copy, Clone, Resource)]
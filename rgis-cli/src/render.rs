@@ -0,0 +1,428 @@
+// `rgis render`: loads files and rasterizes the map to a PNG without opening a window. The layers go
+// through the same plugins as in the viewer, minus everything that needs a window or a GPU.
+
+use bevy::prelude::*;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::path::PathBuf;
+
+static DEFAULT_WIDTH: &str = "800";
+static DEFAULT_HEIGHT: &str = "600";
+static DEFAULT_SOURCE_CRS: &str = "4326";
+// Same as the manage layer window
+const DEFAULT_NUM_CLASSES: usize = 5;
+
+// Updates without any job in progress before giving up on layers which never got reprojected
+const MAX_IDLE_UPDATES: usize = 3;
+
+pub struct RenderArgs {
+    pub files: Vec<PathBuf>,
+    pub output: PathBuf,
+    pub width: u32,
+    pub height: u32,
+    /// CRS of the map. When `None`, the default CRS of the viewer is used.
+    pub crs_epsg_code: Option<u16>,
+    /// CRS of the files which don't specify one.
+    pub source_crs_epsg_code: u16,
+    /// In the CRS of the map. When `None`, every layer is in view.
+    pub extent: Option<geo::Rect>,
+    pub styles: Vec<StyleArg>,
+    pub background: Option<Color>,
+}
+
+/// Style of the layer loaded from the `file_number`th file, starting at 1.
+#[derive(Clone, Debug)]
+pub struct StyleArg {
+    pub file_number: usize,
+    pub fill: Option<Color>,
+    pub stroke: Option<Color>,
+    pub style: Option<StyleSpec>,
+}
+
+#[derive(Clone, Debug)]
+pub enum StyleSpec {
+    Graduated {
+        property: String,
+        ramp: rgis_layers::ColorRamp,
+        classification: Option<(rgis_layers::ClassificationMethod, usize)>,
+    },
+    Categorized {
+        property: String,
+        max_categories: usize,
+    },
+}
+
+pub(crate) fn command() -> Command {
+    Command::new("render")
+        .about("Render files to a PNG image, without opening a window")
+        .arg(
+            Arg::new("FILES")
+                .required(true)
+                .num_args(1..)
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Files to load, each one becomes a layer. The first file is drawn at the bottom.")
+        )
+        .arg(
+            Arg::new("OUTPUT")
+                .long("output")
+                .short('o')
+                .required(true)
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Path of the PNG image to write")
+        )
+        .arg(
+            Arg::new("WIDTH")
+                .long("width")
+                .default_value(DEFAULT_WIDTH)
+                .value_parser(clap::value_parser!(u32))
+                .help("Width of the image, in pixels")
+        )
+        .arg(
+            Arg::new("HEIGHT")
+                .long("height")
+                .default_value(DEFAULT_HEIGHT)
+                .value_parser(clap::value_parser!(u32))
+                .help("Height of the image, in pixels")
+        )
+        .arg(
+            Arg::new("CRS")
+                .long("crs")
+                .value_parser(clap::value_parser!(u16))
+                .help("EPSG code of the CRS the map is rendered in. Defaults to the CRS of the viewer.")
+        )
+        .arg(
+            Arg::new("SOURCE CRS")
+                .long("source-crs")
+                .default_value(DEFAULT_SOURCE_CRS)
+                .value_parser(clap::value_parser!(u16))
+                .help("EPSG code of the CRS of the files which don't specify one")
+        )
+        .arg(
+            Arg::new("EXTENT")
+                .long("extent")
+                .value_parser(parse_extent)
+                .help("Part of the map to render, as MIN_X,MIN_Y,MAX_X,MAX_Y in the CRS of the map. It's grown to match the aspect ratio of the image. Defaults to the extent of every layer.")
+        )
+        .arg(
+            Arg::new("STYLE")
+                .long("style")
+                .action(ArgAction::Append)
+                .value_parser(parse_style)
+                .help("Style of a layer, as FILE_NUMBER:OPTIONS where FILE_NUMBER is the position of the file starting at 1 and OPTIONS is a comma separated list of: fill=#RRGGBB, stroke=#RRGGBB, graduated=PROPERTY, ramp=RAMP, method=equal-interval|quantile|natural-breaks|standard-deviation, classes=N, breaks=A/B/C, categorized=PROPERTY, categories=N. For example: 1:graduated=population,ramp=viridis,method=quantile,classes=5")
+        )
+        .arg(
+            Arg::new("BACKGROUND")
+                .long("background")
+                .value_parser(parse_color)
+                .help("Background color, as #RRGGBB. Defaults to transparent.")
+        )
+}
+
+impl RenderArgs {
+    pub(crate) fn from_matches(matches: &ArgMatches) -> Result<Self, String> {
+        Ok(RenderArgs {
+            files: matches
+                .get_many::<PathBuf>("FILES")
+                .ok_or("Could not fetch files from clap")?
+                .cloned()
+                .collect(),
+            output: matches
+                .get_one::<PathBuf>("OUTPUT")
+                .ok_or("Could not fetch output path from clap")?
+                .clone(),
+            width: *matches
+                .get_one::<u32>("WIDTH")
+                .ok_or("Could not fetch width from clap")?,
+            height: *matches
+                .get_one::<u32>("HEIGHT")
+                .ok_or("Could not fetch height from clap")?,
+            crs_epsg_code: matches.get_one::<u16>("CRS").copied(),
+            source_crs_epsg_code: *matches
+                .get_one::<u16>("SOURCE CRS")
+                .ok_or("Could not fetch source CRS from clap")?,
+            extent: matches.get_one::<geo::Rect>("EXTENT").copied(),
+            styles: matches
+                .get_many::<StyleArg>("STYLE")
+                .map(|styles| styles.cloned().collect())
+                .unwrap_or_default(),
+            background: matches.get_one::<Color>("BACKGROUND").copied(),
+        })
+    }
+}
+
+pub fn render(args: RenderArgs) -> Result<(), String> {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(bevy::log::LogPlugin::default())
+        .add_plugins(rgis_events::Plugin)
+        .add_plugins(rgis_layers::Plugin)
+        .add_plugins(rgis_settings::Plugin)
+        .add_plugins(rgis_transform::Plugin)
+        .add_plugins(bevy_jobs::Plugin);
+    if let Some(crs_epsg_code) = args.crs_epsg_code {
+        app.world
+            .resource_mut::<rgis_settings::RgisSettings>()
+            .target_crs_epsg_code = crs_epsg_code;
+    }
+
+    for path in &args.files {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+        let name = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        let file_format = geo_file_loader::detect_file_format(Some(&name), None, &bytes)
+            .ok_or_else(|| format!("Could not detect the format of {}", path.display()))?;
+        let loaded = geo_file_loader::load_file(file_format, bytes.into(), Default::default())
+            .map_err(|e| format!("Could not load {}: {e}", path.display()))?;
+        app.world.send_event(rgis_events::CreateLayerEvent {
+            feature_collection: geo_projected::Unprojected::new(loaded.feature_collection),
+            name,
+            source_crs_epsg_code: loaded.crs_epsg_code.unwrap_or(args.source_crs_epsg_code),
+        });
+    }
+    // Creates the layers
+    app.update();
+
+    for style_arg in &args.styles {
+        apply_style(&mut app, style_arg)?;
+    }
+    wait_for_reprojection(&mut app)?;
+
+    let layers = app.world.resource::<rgis_layers::Layers>();
+    let extent = match args.extent {
+        Some(extent) => extent,
+        None => layers
+            .iter()
+            .filter_map(|layer| layer.projected_feature_collection.as_ref()?.0.bounding_rect)
+            .reduce(|a, b| {
+                geo::Rect::new(
+                    geo::coord! { x: a.min().x.min(b.min().x), y: a.min().y.min(b.min().y) },
+                    geo::coord! { x: a.max().x.max(b.max().x), y: a.max().y.max(b.max().y) },
+                )
+            })
+            .ok_or("None of the files have any geometry")?,
+    };
+    let png = rgis_renderer::render_png(
+        layers,
+        &rgis_renderer::RasterOptions {
+            view_extent: geo_projected::Projected(fit_to_aspect_ratio(
+                extent,
+                args.width,
+                args.height,
+            )),
+            width: args.width,
+            height: args.height,
+            background: args.background,
+        },
+    )
+    .map_err(|e| e.to_string())?;
+    std::fs::write(&args.output, png)
+        .map_err(|e| format!("Could not write {}: {e}", args.output.display()))
+}
+
+fn apply_style(app: &mut App, style_arg: &StyleArg) -> Result<(), String> {
+    let layers = app.world.resource::<rgis_layers::Layers>();
+    let layer = style_arg
+        .file_number
+        .checked_sub(1)
+        .and_then(|index| layers.iter_bottom_to_top().nth(index))
+        .ok_or_else(|| format!("No file number {} to style", style_arg.file_number))?;
+    let layer_id = layer.id;
+    let style = style_arg.style.as_ref().map(|style| {
+        let feature_collection = layer.unprojected_feature_collection.as_raw();
+        match style {
+            StyleSpec::Graduated {
+                property,
+                ramp,
+                classification,
+            } => rgis_layers::LayerStyle::Graduated(rgis_layers::GraduatedStyle::new(
+                property.clone(),
+                *ramp,
+                classification.clone(),
+                feature_collection,
+            )),
+            StyleSpec::Categorized {
+                property,
+                max_categories,
+            } => rgis_layers::LayerStyle::Categorized(rgis_layers::CategorizedStyle::new(
+                property.clone(),
+                *max_categories,
+                feature_collection,
+            )),
+        }
+    });
+
+    if let Some(fill) = style_arg.fill {
+        app.world
+            .send_event(rgis_events::UpdateLayerColorEvent::Fill(layer_id, fill));
+    }
+    if let Some(stroke) = style_arg.stroke {
+        app.world
+            .send_event(rgis_events::UpdateLayerColorEvent::Stroke(layer_id, stroke));
+    }
+    if let Some(style) = style {
+        app.world
+            .send_event(rgis_layers::UpdateLayerStyleEvent(layer_id, style));
+    }
+    Ok(())
+}
+
+// Layers are reprojected by background jobs, so keep updating until every layer is.
+fn wait_for_reprojection(app: &mut App) -> Result<(), String> {
+    let mut idle_updates = 0;
+    loop {
+        app.update();
+        let layers = app.world.resource::<rgis_layers::Layers>();
+        if layers.iter().all(rgis_layers::Layer::is_active) {
+            return Ok(());
+        }
+        let unprojected = layers
+            .iter()
+            .filter(|layer| !layer.is_active())
+            .map(|layer| layer.name.clone())
+            .collect::<Vec<_>>();
+        let in_progress_jobs = app
+            .world
+            .query::<&bevy_jobs::InProgressJob>()
+            .iter(&app.world)
+            .count();
+        if in_progress_jobs == 0 {
+            idle_updates += 1;
+            if idle_updates > MAX_IDLE_UPDATES {
+                return Err(format!("Could not reproject {}", unprojected.join(", ")));
+            }
+        } else {
+            idle_updates = 0;
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+}
+
+// Grows the extent around its center, so the map isn't stretched.
+fn fit_to_aspect_ratio(extent: geo::Rect, width: u32, height: u32) -> geo::Rect {
+    let scale = (extent.width() / f64::from(width)).max(extent.height() / f64::from(height));
+    // A single point, or a line along an axis
+    let scale = if scale > 0. { scale } else { 1. };
+    let half_size = geo::coord! {
+        x: scale * f64::from(width) / 2.,
+        y: scale * f64::from(height) / 2.,
+    };
+    let center = extent.center();
+    geo::Rect::new(center - half_size, center + half_size)
+}
+
+fn parse_extent(s: &str) -> Result<geo::Rect, String> {
+    let numbers = s
+        .split(',')
+        .map(|n| n.trim().parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    match *numbers.as_slice() {
+        [min_x, min_y, max_x, max_y] if min_x < max_x && min_y < max_y => Ok(geo::Rect::new(
+            geo::coord! { x: min_x, y: min_y },
+            geo::coord! { x: max_x, y: max_y },
+        )),
+        _ => Err("Expected MIN_X,MIN_Y,MAX_X,MAX_Y".into()),
+    }
+}
+
+fn parse_color(s: &str) -> Result<Color, String> {
+    Color::hex(s).map_err(|e| e.to_string())
+}
+
+fn parse_style(s: &str) -> Result<StyleArg, String> {
+    let (file_number, options) = s.split_once(':').ok_or("Expected FILE_NUMBER:OPTIONS")?;
+    let file_number = file_number
+        .trim()
+        .parse::<usize>()
+        .map_err(|e| format!("Invalid file number: {e}"))?;
+
+    let mut style_arg = StyleArg {
+        file_number,
+        fill: None,
+        stroke: None,
+        style: None,
+    };
+    let (mut graduated, mut categorized) = (None, None);
+    let mut ramp = rgis_layers::ColorRamp::Blues;
+    let (mut method, mut classes, mut breaks) = (None, None, None);
+    let mut max_categories = rgis_layers::CategorizedStyle::DEFAULT_MAX_CATEGORIES;
+    for option in options
+        .split(',')
+        .filter(|option| !option.trim().is_empty())
+    {
+        let (key, value) = option
+            .split_once('=')
+            .ok_or_else(|| format!("Expected KEY=VALUE, found {option}"))?;
+        let value = value.trim();
+        match key.trim() {
+            "fill" => style_arg.fill = Some(parse_color(value)?),
+            "stroke" => style_arg.stroke = Some(parse_color(value)?),
+            "graduated" => graduated = Some(value.to_owned()),
+            "categorized" => categorized = Some(value.to_owned()),
+            "ramp" => {
+                ramp = rgis_layers::ColorRamp::ALL
+                    .into_iter()
+                    .find(|ramp| ramp.display_name().eq_ignore_ascii_case(value))
+                    .ok_or_else(|| format!("Unknown color ramp: {value}"))?;
+            }
+            "method" => {
+                method = Some(match value {
+                    "equal-interval" => rgis_layers::ClassificationMethod::EqualInterval,
+                    "quantile" => rgis_layers::ClassificationMethod::Quantile,
+                    "natural-breaks" => rgis_layers::ClassificationMethod::NaturalBreaks,
+                    "standard-deviation" => rgis_layers::ClassificationMethod::StandardDeviation,
+                    _ => return Err(format!("Unknown classification method: {value}")),
+                });
+            }
+            "classes" => {
+                classes = Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|e| format!("Invalid number of classes: {e}"))?,
+                );
+            }
+            "breaks" => {
+                breaks = Some(
+                    value
+                        .split('/')
+                        .map(|n| n.trim().parse::<f64>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|e| format!("Invalid breaks: {e}"))?,
+                );
+            }
+            "categories" => {
+                max_categories = value
+                    .parse::<usize>()
+                    .map_err(|e| format!("Invalid number of categories: {e}"))?;
+            }
+            key => return Err(format!("Unknown style option: {key}")),
+        }
+    }
+
+    style_arg.style = match (graduated, categorized) {
+        (Some(_), Some(_)) => {
+            return Err("A layer can't be both graduated and categorized".into());
+        }
+        (Some(property), None) => {
+            let classification = match (breaks, method) {
+                (Some(breaks), _) => Some((rgis_layers::ClassificationMethod::Manual(breaks), 0)),
+                (None, Some(method)) => Some((method, classes.unwrap_or(DEFAULT_NUM_CLASSES))),
+                // Continuous ramp
+                (None, None) => None,
+            };
+            Some(StyleSpec::Graduated {
+                property,
+                ramp,
+                classification,
+            })
+        }
+        (None, Some(property)) => Some(StyleSpec::Categorized {
+            property,
+            max_categories,
+        }),
+        (None, None) => None,
+    };
+    Ok(style_arg)
+}
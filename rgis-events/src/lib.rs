@@ -17,6 +17,9 @@ pub struct LayerCreatedEvent(pub rgis_layer_id::LayerId);
 #[derive(Debug, Event)]
 pub struct ShowManageLayerWindowEvent(pub rgis_layer_id::LayerId);
 
+#[derive(Debug, Event)]
+pub struct ShowExportLayerWindowEvent(pub rgis_layer_id::LayerId);
+
 #[derive(Event, Debug)]
 pub struct ToggleLayerVisibilityEvent(pub rgis_layer_id::LayerId);
 
@@ -52,6 +55,10 @@ pub enum LayerColorUpdatedEvent {
     Stroke(rgis_layer_id::LayerId),
 }
 
+/// After a `Layer`'s style is changed
+#[derive(Clone, Copy, Event)]
+pub struct LayerStyleUpdatedEvent(pub rgis_layer_id::LayerId);
+
 #[derive(Event)]
 pub struct DeleteLayerEvent(pub rgis_layer_id::LayerId);
 
@@ -82,6 +89,10 @@ pub struct LayerZIndexUpdatedEvent(pub rgis_layer_id::LayerId);
 #[derive(Event)]
 pub struct MapClickedEvent(pub geo_projected::Projected<geo::Coord>);
 
+/// A rectangle dragged on the map with the `DrawRect` tool.
+#[derive(Event)]
+pub struct MapRectDrawnEvent(pub geo_projected::Projected<geo::Rect>);
+
 #[derive(Event)]
 pub struct FeaturesDeselectedEvent;
 
@@ -100,9 +111,38 @@ pub enum LoadFileEvent {
         file_format: geo_file_loader::FileFormat,
         bytes: bytes::Bytes,
         crs_epsg_code: u16,
+        options: geo_file_loader::LoadOptions,
+    },
+    /// A file read from disk while loading, so big files don't have to fit in memory.
+    FromPath {
+        file_name: String,
+        file_format: geo_file_loader::FileFormat,
+        path: std::path::PathBuf,
+        crs_epsg_code: u16,
+        options: geo_file_loader::LoadOptions,
     },
 }
 
+/// Save the features of a layer to a file chosen by the user.
+#[derive(Event, Debug)]
+pub struct ExportLayerEvent {
+    pub layer_id: rgis_layer_id::LayerId,
+    pub file_format: geo_file_exporter::FileFormat,
+    /// Export the coordinates in the CRS of the map instead of the CRS of the layer.
+    pub projected: bool,
+}
+
+/// Save the visible layers, as they appear in the map view, to an SVG file chosen by the user.
+#[derive(Event, Debug)]
+pub struct ExportMapSvgEvent {
+    pub view_extent: geo_projected::Projected<geo::Rect>,
+    /// Size of the map view in pixels, which becomes the size of the SVG.
+    pub width: f32,
+    pub height: f32,
+    pub legend: bool,
+    pub scale_bar: bool,
+}
+
 pub struct Plugin;
 
 #[derive(Event, Debug)]
@@ -180,9 +220,40 @@ pub struct CreateLayerEvent {
     pub source_crs_epsg_code: u16,
 }
 
+/// Features of a file that's loaded progressively, one chunk at a time.
+#[derive(Event)]
+pub enum StreamedFeaturesEvent {
+    /// The first chunk, which creates the layer.
+    Create {
+        layer_id: rgis_layer_id::LayerId,
+        feature_collection: geo_projected::Unprojected<geo_features::FeatureCollection>,
+        name: String,
+        source_crs_epsg_code: u16,
+    },
+    /// A following chunk, appended to the layer.
+    Append {
+        layer_id: rgis_layer_id::LayerId,
+        feature_collection: geo_projected::Unprojected<geo_features::FeatureCollection>,
+    },
+}
+
 #[derive(Event)]
 pub struct LayerReprojectedEvent(pub rgis_layer_id::LayerId);
 
+/// Features were appended to the layer, starting at `first_feature_index`.
+#[derive(Event)]
+pub struct LayerFeaturesAppendedEvent {
+    pub layer_id: rgis_layer_id::LayerId,
+    pub first_feature_index: usize,
+}
+
+/// The features appended to the layer, starting at `first_feature_index`, were reprojected.
+#[derive(Event)]
+pub struct AppendedFeaturesReprojectedEvent {
+    pub layer_id: rgis_layer_id::LayerId,
+    pub first_feature_index: usize,
+}
+
 #[derive(Default, Event)]
 pub struct ShowAddLayerWindow;
 
@@ -193,6 +264,9 @@ impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut App) {
         app.add_event::<LoadFileEvent>()
             .add_event::<CreateLayerEvent>()
+            .add_event::<StreamedFeaturesEvent>()
+            .add_event::<LayerFeaturesAppendedEvent>()
+            .add_event::<AppendedFeaturesReprojectedEvent>()
             .add_event::<LayerCreatedEvent>()
             .add_event::<ToggleLayerVisibilityEvent>()
             .add_event::<LayerBecameHiddenEvent>()
@@ -202,6 +276,7 @@ impl bevy::app::Plugin for Plugin {
             .add_event::<CenterCameraEvent>()
             .add_event::<LayerColorUpdatedEvent>()
             .add_event::<UpdateLayerColorEvent>()
+            .add_event::<LayerStyleUpdatedEvent>()
             .add_event::<MoveLayerEvent>()
             .add_event::<LayerZIndexUpdatedEvent>()
             .add_event::<DeleteLayerEvent>()
@@ -209,6 +284,7 @@ impl bevy::app::Plugin for Plugin {
             .add_event::<ChangeCrsEvent>()
             .add_event::<CrsChangedEvent>()
             .add_event::<MapClickedEvent>()
+            .add_event::<MapRectDrawnEvent>()
             .add_event::<RenderMessageEvent>()
             .add_event::<RenderFeaturePropertiesEvent>()
             .add_event::<OpenChangeCrsWindow>()
@@ -218,6 +294,9 @@ impl bevy::app::Plugin for Plugin {
             .add_event::<DespawnMeshesEvent>()
             .add_event::<FeatureSelectedEvent>()
             .add_event::<FeaturesDeselectedEvent>()
-            .add_event::<ShowManageLayerWindowEvent>();
+            .add_event::<ShowManageLayerWindowEvent>()
+            .add_event::<ShowExportLayerWindowEvent>()
+            .add_event::<ExportLayerEvent>()
+            .add_event::<ExportMapSvgEvent>();
     }
 }
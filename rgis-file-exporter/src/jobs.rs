@@ -0,0 +1,84 @@
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("{0}")]
+    Export(#[from] geo_file_exporter::Error),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Encodes the features, then asks the user where to save them. On the web, the file is
+/// downloaded by the browser instead.
+pub struct ExportLayerJob {
+    pub feature_collection: geo_features::FeatureCollection,
+    pub file_format: geo_file_exporter::FileFormat,
+    pub layer_name: String,
+    /// CRS of the features, which is recorded by the formats that support it.
+    pub crs_epsg_code: u16,
+}
+
+impl bevy_jobs::Job for ExportLayerJob {
+    type Outcome = Result<(), Error>;
+    const JOB_TYPE: bevy_jobs::JobType = bevy_jobs::JobType::Io;
+
+    fn name(&self) -> String {
+        format!("Exporting {} file", self.file_format.display_name())
+    }
+
+    fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move {
+            let bytes = geo_file_exporter::export_file(
+                self.file_format,
+                &self.feature_collection,
+                &self.layer_name,
+                self.crs_epsg_code,
+            )?;
+            save_file(
+                format!("{}.{}", self.layer_name, self.file_format.extension()),
+                self.file_format.display_name(),
+                self.file_format.extension(),
+                &bytes,
+            )
+            .await?;
+            Ok(())
+        })
+    }
+}
+
+/// Writes the map as SVG, then asks the user where to save it.
+pub struct ExportMapSvgJob {
+    pub map_svg: crate::MapSvg,
+}
+
+impl bevy_jobs::Job for ExportMapSvgJob {
+    type Outcome = Result<(), std::io::Error>;
+    const JOB_TYPE: bevy_jobs::JobType = bevy_jobs::JobType::Io;
+
+    fn name(&self) -> String {
+        "Exporting map as SVG".into()
+    }
+
+    fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move {
+            let svg = self.map_svg.write();
+            save_file("map.svg".into(), "SVG", "svg", svg.as_bytes()).await
+        })
+    }
+}
+
+async fn save_file(
+    file_name: String,
+    filter_name: &str,
+    extension: &str,
+    bytes: &[u8],
+) -> Result<(), std::io::Error> {
+    let file_handle = rfd::AsyncFileDialog::new()
+        .set_file_name(file_name)
+        .add_filter(filter_name, &[extension])
+        .save_file()
+        .await;
+    // The user cancelled the dialog
+    let Some(file_handle) = file_handle else {
+        return Ok(());
+    };
+    file_handle.write(bytes).await
+}
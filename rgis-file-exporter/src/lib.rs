@@ -0,0 +1,23 @@
+#![warn(
+    clippy::unwrap_used,
+    clippy::cast_lossless,
+    clippy::unimplemented,
+    clippy::indexing_slicing,
+    clippy::expect_used
+)]
+
+use bevy::prelude::*;
+
+mod jobs;
+mod svg;
+mod systems;
+
+pub use svg::{MapSvg, MapSvgOptions};
+
+pub struct Plugin;
+
+impl bevy::app::Plugin for Plugin {
+    fn build(&self, app: &mut App) {
+        systems::configure(app);
+    }
+}
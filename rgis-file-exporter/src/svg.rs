@@ -0,0 +1,427 @@
+// Writes the map view as an SVG meant to be edited by designers: every layer is a group (which
+// Inkscape and Illustrator both show as a layer), and geometries are clipped to the view instead of
+// being hidden behind a clip path.
+
+use bevy::prelude::Color;
+use geo::GeodesicDistance;
+use std::sync;
+
+const POINT_RADIUS: f64 = 3.;
+const STROKE_WIDTH: f64 = 1.;
+const FONT_SIZE: f64 = 12.;
+// Rough width of a character, used to size the legend box since the SVG has no text layout
+const CHARACTER_WIDTH: f64 = 7.;
+const MARGIN: f64 = 16.;
+const LEGEND_PADDING: f64 = 8.;
+const LEGEND_LINE_HEIGHT: f64 = 18.;
+const LEGEND_SWATCH_SIZE: f64 = 14.;
+const WGS_84_EPSG_CODE: u16 = 4326;
+
+pub struct MapSvgOptions {
+    pub view_extent: geo_projected::Projected<geo::Rect>,
+    /// Pixels
+    pub width: f64,
+    /// Pixels
+    pub height: f64,
+    /// CRS of the map, used to measure the scale bar.
+    pub crs_epsg_code: u16,
+    pub legend: bool,
+    pub scale_bar: bool,
+}
+
+/// The visible layers of the map, ordered from bottom to top.
+pub struct MapSvg {
+    options: MapSvgOptions,
+    layers: Vec<SvgLayer>,
+    // Ordered from top to bottom, like the legend window
+    legends: Vec<rgis_layers::Legend>,
+}
+
+struct SvgLayer {
+    name: String,
+    stroke: Color,
+    fill: Option<Color>,
+    style: rgis_layers::LayerStyle,
+    features: sync::Arc<geo_projected::Projected<geo_features::FeatureCollection>>,
+}
+
+impl MapSvg {
+    /// Takes the visible layers. Their features are shared with the layers rather than copied, and
+    /// everything else, from picking the ones in view to clipping them, is left to
+    /// [`MapSvg::write`].
+    pub fn new(layers: &rgis_layers::Layers, options: MapSvgOptions) -> Self {
+        let svg_layers = layers
+            .iter_bottom_to_top()
+            .filter(|layer| layer.visible)
+            .filter_map(|layer| {
+                Some(SvgLayer {
+                    name: layer.name.clone(),
+                    stroke: layer.color.stroke,
+                    fill: layer.color.fill,
+                    style: layer.style.clone(),
+                    features: layer.projected_feature_collection.clone()?,
+                })
+            })
+            .collect();
+        let legends = if options.legend {
+            layers
+                .iter_top_to_bottom()
+                .filter(|layer| layer.visible)
+                .map(rgis_layers::Layer::legend)
+                .collect()
+        } else {
+            vec![]
+        };
+        MapSvg {
+            options,
+            layers: svg_layers,
+            legends,
+        }
+    }
+
+    pub fn write(&self) -> String {
+        let (width, height) = (self.options.width, self.options.height);
+        let view_extent = self.options.view_extent.0;
+        let mut svg = String::new();
+        svg.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        svg.push_str(&format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:inkscape=\"http://www.inkscape.org/namespaces/inkscape\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+        ));
+        for (i, layer) in self.layers.iter().enumerate() {
+            svg.push_str(&format!(
+                "<g id=\"layer-{}\" inkscape:groupmode=\"layer\" inkscape:label=\"{}\">\n",
+                i + 1,
+                escape(&layer.name),
+            ));
+            for feature in &layer.features.as_raw().features {
+                let Some(geometry) = feature
+                    .geometry
+                    .as_ref()
+                    .and_then(|geometry| geo_clip::clip_to_rect(geometry, &view_extent))
+                else {
+                    continue;
+                };
+                let fill = layer.style.feature_fill_color(feature, layer.fill);
+                self.write_geometry(&mut svg, &geometry, fill, layer.stroke);
+            }
+            svg.push_str("</g>\n");
+        }
+        if self.options.scale_bar {
+            self.write_scale_bar(&mut svg);
+        }
+        if !self.legends.is_empty() {
+            self.write_legend(&mut svg);
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn write_geometry(
+        &self,
+        svg: &mut String,
+        geometry: &geo::Geometry,
+        fill: Option<Color>,
+        stroke: Color,
+    ) {
+        match geometry {
+            geo::Geometry::Point(point) => self.write_point(svg, point.0, fill, stroke),
+            geo::Geometry::MultiPoint(multi_point) => {
+                for point in multi_point {
+                    self.write_point(svg, point.0, fill, stroke);
+                }
+            }
+            geo::Geometry::Line(line) => self.write_lines(
+                svg,
+                [&geo::LineString::new(vec![line.start, line.end])],
+                stroke,
+            ),
+            geo::Geometry::LineString(line_string) => self.write_lines(svg, [line_string], stroke),
+            geo::Geometry::MultiLineString(multi_line_string) => {
+                self.write_lines(svg, multi_line_string, stroke)
+            }
+            geo::Geometry::Polygon(polygon) => self.write_polygons(svg, [polygon], fill, stroke),
+            geo::Geometry::MultiPolygon(multi_polygon) => {
+                self.write_polygons(svg, multi_polygon, fill, stroke)
+            }
+            geo::Geometry::Rect(rect) => {
+                self.write_polygons(svg, [&rect.to_polygon()], fill, stroke)
+            }
+            geo::Geometry::Triangle(triangle) => {
+                self.write_polygons(svg, [&triangle.to_polygon()], fill, stroke)
+            }
+            geo::Geometry::GeometryCollection(geometry_collection) => {
+                for geometry in geometry_collection {
+                    self.write_geometry(svg, geometry, fill, stroke);
+                }
+            }
+        }
+    }
+
+    fn write_point(&self, svg: &mut String, coord: geo::Coord, fill: Option<Color>, stroke: Color) {
+        let (x, y) = self.to_svg_coord(coord);
+        svg.push_str(&format!(
+            "<circle cx=\"{x:.2}\" cy=\"{y:.2}\" r=\"{POINT_RADIUS}\" {} {}/>\n",
+            fill_attributes(fill),
+            stroke_attributes(stroke),
+        ));
+    }
+
+    fn write_lines<'a>(
+        &self,
+        svg: &mut String,
+        line_strings: impl IntoIterator<Item = &'a geo::LineString>,
+        stroke: Color,
+    ) {
+        let mut path = String::new();
+        for line_string in line_strings {
+            self.push_path(&mut path, line_string, false);
+        }
+        if !path.is_empty() {
+            svg.push_str(&format!(
+                "<path d=\"{path}\" fill=\"none\" {}/>\n",
+                stroke_attributes(stroke),
+            ));
+        }
+    }
+
+    fn write_polygons<'a>(
+        &self,
+        svg: &mut String,
+        polygons: impl IntoIterator<Item = &'a geo::Polygon>,
+        fill: Option<Color>,
+        stroke: Color,
+    ) {
+        let mut path = String::new();
+        for polygon in polygons {
+            for ring in std::iter::once(polygon.exterior()).chain(polygon.interiors()) {
+                self.push_path(&mut path, ring, true);
+            }
+        }
+        if !path.is_empty() {
+            svg.push_str(&format!(
+                "<path d=\"{path}\" fill-rule=\"evenodd\" {} {}/>\n",
+                fill_attributes(fill),
+                stroke_attributes(stroke),
+            ));
+        }
+    }
+
+    fn push_path(&self, path: &mut String, line_string: &geo::LineString, close: bool) {
+        for (i, coord) in line_string.coords().enumerate() {
+            let (x, y) = self.to_svg_coord(*coord);
+            let command = if i == 0 { 'M' } else { 'L' };
+            if !path.is_empty() {
+                path.push(' ');
+            }
+            path.push_str(&format!("{command}{x:.2},{y:.2}"));
+        }
+        if close && !line_string.0.is_empty() {
+            path.push_str(" Z");
+        }
+    }
+
+    fn to_svg_coord(&self, coord: geo::Coord) -> (f64, f64) {
+        let view_extent = self.options.view_extent.0;
+        (
+            (coord.x - view_extent.min().x) / view_extent.width() * self.options.width,
+            (view_extent.max().y - coord.y) / view_extent.height() * self.options.height,
+        )
+    }
+
+    // A bar spanning a round distance on the ground, about a quarter of the width of the map.
+    fn write_scale_bar(&self, svg: &mut String) {
+        let Some(meters_per_unit) = self.ground_meters_per_unit() else {
+            return;
+        };
+        let view_width = self.options.view_extent.0.width();
+        let target = view_width / 4. * meters_per_unit;
+        if !(target.is_finite() && target > 0.) {
+            return;
+        }
+        let magnitude = 10f64.powi(target.log10().floor() as i32);
+        let mantissa = [5., 2., 1.]
+            .into_iter()
+            .find(|mantissa| mantissa * magnitude <= target)
+            .unwrap_or(1.);
+        let meters = mantissa * magnitude;
+        let length = meters / meters_per_unit / view_width * self.options.width;
+        let (x, y) = (MARGIN, self.options.height - MARGIN);
+        let label = if meters >= 1000. {
+            format!("{} km", meters / 1000.)
+        } else {
+            format!("{meters} m")
+        };
+
+        svg.push_str("<g id=\"scale-bar\">\n");
+        svg.push_str(&format!(
+            "<path d=\"M{x},{} V{y} H{:.2} V{}\" fill=\"none\" stroke=\"#000000\" stroke-width=\"1.5\"/>\n",
+            y - 6.,
+            x + length,
+            y - 6.,
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{:.2}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{FONT_SIZE}\" text-anchor=\"middle\">{}</text>\n",
+            x + length / 2.,
+            y - 4.,
+            escape(&label),
+        ));
+        svg.push_str("</g>\n");
+    }
+
+    // Meters on the ground per unit of the map's CRS, measured geodesically across the center of
+    // the view. Units of projected CRSs rarely match meters on the ground, in Web Mercator they
+    // shrink with the cosine of the latitude.
+    fn ground_meters_per_unit(&self) -> Option<f64> {
+        let view_extent = self.options.view_extent.0;
+        let center = view_extent.center();
+        let half_width = view_extent.width() / 8.;
+        let mut line = geo::Geometry::Line(geo::Line::new(
+            geo::coord! { x: center.x - half_width, y: center.y },
+            geo::coord! { x: center.x + half_width, y: center.y },
+        ));
+        if self.options.crs_epsg_code != WGS_84_EPSG_CODE {
+            let transformed =
+                transform::Transformer::setup(self.options.crs_epsg_code, WGS_84_EPSG_CODE)
+                    .and_then(|transformer| Ok(transformer.transform(&mut line)?));
+            if let Err(e) = transformed {
+                bevy::log::warn!("Could not measure the scale bar, leaving it out: {e}");
+                return None;
+            }
+        }
+        let geo::Geometry::Line(line) = line else {
+            return None;
+        };
+        let meters = geo::Point::from(line.start).geodesic_distance(&geo::Point::from(line.end));
+        Some(meters / (2. * half_width))
+    }
+
+    // Drawn in the top right corner, in the same layout as the legend window.
+    fn write_legend(&self, svg: &mut String) {
+        let lines = self
+            .legends
+            .iter()
+            .map(|legend| usize::from(legend.title.is_some()) + legend.entries.len())
+            .sum::<usize>();
+        let max_characters = self
+            .legends
+            .iter()
+            .flat_map(|legend| {
+                let title = legend.title.iter().map(|title| title.chars().count());
+                let entries = legend
+                    .entries
+                    .iter()
+                    .map(|entry| entry.label.chars().count() + 3);
+                title.chain(entries)
+            })
+            .max()
+            .unwrap_or(0);
+        let box_width = max_characters as f64 * CHARACTER_WIDTH + 2. * LEGEND_PADDING;
+        let box_height = lines as f64 * LEGEND_LINE_HEIGHT + 2. * LEGEND_PADDING;
+        let left = self.options.width - MARGIN - box_width;
+        let top = MARGIN;
+
+        svg.push_str("<g id=\"legend\">\n");
+        svg.push_str(&format!(
+            "<rect x=\"{left:.2}\" y=\"{top}\" width=\"{box_width:.2}\" height=\"{box_height:.2}\" fill=\"#ffffff\" fill-opacity=\"0.85\" stroke=\"#999999\"/>\n"
+        ));
+        let x = left + LEGEND_PADDING;
+        let mut y = top + LEGEND_PADDING;
+        for legend in &self.legends {
+            if let Some(ref title) = legend.title {
+                svg.push_str(&format!(
+                    "<text x=\"{x:.2}\" y=\"{:.2}\" font-family=\"sans-serif\" font-size=\"{FONT_SIZE}\" font-weight=\"bold\">{}</text>\n",
+                    y + LEGEND_LINE_HEIGHT - 5.,
+                    escape(title),
+                ));
+                y += LEGEND_LINE_HEIGHT;
+            }
+            for entry in &legend.entries {
+                let swatch_top = y + (LEGEND_LINE_HEIGHT - LEGEND_SWATCH_SIZE) / 2.;
+                write_legend_symbol(svg, legend, entry.fill, x, swatch_top);
+                svg.push_str(&format!(
+                    "<text x=\"{:.2}\" y=\"{:.2}\" font-family=\"sans-serif\" font-size=\"{FONT_SIZE}\">{}</text>\n",
+                    x + LEGEND_SWATCH_SIZE + 6.,
+                    y + LEGEND_LINE_HEIGHT - 5.,
+                    escape(&entry.label),
+                ));
+                y += LEGEND_LINE_HEIGHT;
+            }
+        }
+        svg.push_str("</g>\n");
+    }
+}
+
+fn write_legend_symbol(
+    svg: &mut String,
+    legend: &rgis_layers::Legend,
+    fill: Option<Color>,
+    left: f64,
+    top: f64,
+) {
+    let size = LEGEND_SWATCH_SIZE;
+    match legend.symbol {
+        rgis_layers::LegendSymbol::Point => svg.push_str(&format!(
+            "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" {} {}/>\n",
+            left + size / 2.,
+            top + size / 2.,
+            size / 3.,
+            fill_attributes(fill),
+            stroke_attributes(legend.stroke),
+        )),
+        rgis_layers::LegendSymbol::Line => svg.push_str(&format!(
+            "<path d=\"M{left:.2},{:.2} L{:.2},{top:.2}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>\n",
+            top + size,
+            left + size,
+            hex_color(legend.stroke),
+        )),
+        rgis_layers::LegendSymbol::Polygon => svg.push_str(&format!(
+            "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" {} {}/>\n",
+            left + 1.,
+            top + 1.,
+            size - 2.,
+            size - 2.,
+            fill_attributes(fill),
+            stroke_attributes(legend.stroke),
+        )),
+    }
+}
+
+fn fill_attributes(fill: Option<Color>) -> String {
+    match fill {
+        Some(fill) => format!(
+            "fill=\"{}\"{}",
+            hex_color(fill),
+            opacity_attribute("fill", fill)
+        ),
+        None => "fill=\"none\"".into(),
+    }
+}
+
+fn stroke_attributes(stroke: Color) -> String {
+    format!(
+        "stroke=\"{}\"{} stroke-width=\"{STROKE_WIDTH}\" stroke-linejoin=\"round\"",
+        hex_color(stroke),
+        opacity_attribute("stroke", stroke),
+    )
+}
+
+// Left out for opaque colors, which most are.
+fn opacity_attribute(name: &str, color: Color) -> String {
+    if color.a() < 1. {
+        format!(" {name}-opacity=\"{}\"", color.a())
+    } else {
+        String::new()
+    }
+}
+
+fn hex_color(color: Color) -> String {
+    let [r, g, b, _] = color.as_rgba_u8();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+
+fn handle_export_layer_events(
+    mut export_layer_event_reader: EventReader<rgis_events::ExportLayerEvent>,
+    layers: Res<rgis_layers::Layers>,
+    rgis_settings: Res<rgis_settings::RgisSettings>,
+    mut job_spawner: bevy_jobs::JobSpawner,
+) {
+    for event in export_layer_event_reader.read() {
+        let Some(layer) = layers.get(event.layer_id) else {
+            bevy::log::warn!("Could not find layer with ID {:?}", event.layer_id);
+            continue;
+        };
+        let (feature_collection, crs_epsg_code) = if event.projected {
+            let Some(projected) = layer.get_projected_feature_collection_or_log() else {
+                continue;
+            };
+            (
+                projected.as_raw().clone(),
+                rgis_settings.target_crs_epsg_code,
+            )
+        } else {
+            (
+                layer.unprojected_feature_collection.as_raw().clone(),
+                layer.crs_epsg_code,
+            )
+        };
+        job_spawner.spawn(crate::jobs::ExportLayerJob {
+            feature_collection,
+            file_format: event.file_format,
+            layer_name: layer.name.clone(),
+            crs_epsg_code,
+        });
+    }
+}
+
+fn handle_export_map_svg_events(
+    mut export_map_svg_event_reader: EventReader<rgis_events::ExportMapSvgEvent>,
+    layers: Res<rgis_layers::Layers>,
+    rgis_settings: Res<rgis_settings::RgisSettings>,
+    mut job_spawner: bevy_jobs::JobSpawner,
+) {
+    for event in export_map_svg_event_reader.read() {
+        let map_svg = crate::MapSvg::new(
+            &layers,
+            crate::MapSvgOptions {
+                view_extent: event.view_extent,
+                width: f64::from(event.width),
+                height: f64::from(event.height),
+                crs_epsg_code: rgis_settings.target_crs_epsg_code,
+                legend: event.legend,
+                scale_bar: event.scale_bar,
+            },
+        );
+        job_spawner.spawn(crate::jobs::ExportMapSvgJob { map_svg });
+    }
+}
+
+fn handle_export_layer_job_finished_events(mut finished_jobs: bevy_jobs::FinishedJobs) {
+    while let Some(outcome) = finished_jobs.take_next::<crate::jobs::ExportLayerJob>() {
+        if let Err(e) = outcome {
+            bevy::log::error!("Encountered error when exporting layer: {:?}", e);
+        }
+    }
+    while let Some(outcome) = finished_jobs.take_next::<crate::jobs::ExportMapSvgJob>() {
+        if let Err(e) = outcome {
+            bevy::log::error!("Encountered error when exporting map: {:?}", e);
+        }
+    }
+}
+
+pub fn configure(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            handle_export_layer_events,
+            handle_export_map_svg_events,
+            handle_export_layer_job_finished_events,
+        ),
+    );
+}
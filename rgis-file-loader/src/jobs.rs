@@ -1,8 +1,15 @@
 pub struct LoadFileJob {
     pub file_format: geo_file_loader::FileFormat,
-    pub bytes: bytes::Bytes,
+    pub contents: FileContents,
     pub name: String,
     pub source_crs_epsg_code: u16,
+    pub options: geo_file_loader::LoadOptions,
+}
+
+pub enum FileContents {
+    Bytes(bytes::Bytes),
+    /// Read from disk by the job itself.
+    Path(std::path::PathBuf),
 }
 
 pub struct LoadFileJobOutcome {
@@ -21,13 +28,89 @@ impl bevy_jobs::Job for LoadFileJob {
 
     fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
         Box::pin(async move {
+            let loaded = match self.contents {
+                FileContents::Bytes(bytes) => {
+                    geo_file_loader::load_file(self.file_format, bytes, self.options)?
+                }
+                FileContents::Path(path) => {
+                    geo_file_loader::load_file_from_path(self.file_format, &path, self.options)?
+                }
+            };
             Ok(LoadFileJobOutcome {
-                feature_collection: geo_projected::Unprojected::new(geo_file_loader::load_file(
-                    self.file_format,
-                    self.bytes,
-                )?),
+                feature_collection: geo_projected::Unprojected::new(loaded.feature_collection),
                 name: self.name,
+                // Prefer the CRS declared by the file over the one the user entered
+                source_crs_epsg_code: loaded.crs_epsg_code.unwrap_or(self.source_crs_epsg_code),
+            })
+        })
+    }
+}
+
+// Features are read in steps, to report the progress along the way
+const GEOJSON_SEQ_CHUNK_SIZE: usize = 10_000;
+const GEOJSON_SEQ_STEP_SIZE: usize = 1_000;
+
+/// Loads the next chunk of features of a GeoJSONSeq file. The job for the following chunk is only
+/// spawned once this one is on the map, so big files show up progressively.
+pub struct LoadGeoJsonSeqChunkJob {
+    pub reader: geo_file_loader::GeoJsonSeqReader,
+    pub layer_id: rgis_layer_id::LayerId,
+    pub is_first_chunk: bool,
+    pub name: String,
+    pub source_crs_epsg_code: u16,
+}
+
+pub struct LoadGeoJsonSeqChunkJobOutcome {
+    pub feature_collection: geo_projected::Unprojected<geo_features::FeatureCollection>,
+    pub layer_id: rgis_layer_id::LayerId,
+    pub is_first_chunk: bool,
+    pub name: String,
+    pub source_crs_epsg_code: u16,
+    /// The job loading the following chunk, `None` once the whole file is loaded.
+    pub next_job: Option<LoadGeoJsonSeqChunkJob>,
+}
+
+impl bevy_jobs::Job for LoadGeoJsonSeqChunkJob {
+    type Outcome = Result<LoadGeoJsonSeqChunkJobOutcome, geo_file_loader::Error>;
+    const JOB_TYPE: bevy_jobs::JobType = bevy_jobs::JobType::Io;
+
+    fn name(&self) -> String {
+        format!(
+            "Loading {} file",
+            geo_file_loader::FileFormat::GeoJsonSeq.display_name()
+        )
+    }
+
+    fn perform(mut self, ctx: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move {
+            let mut feature_collection = geo_features::FeatureCollection::new();
+            while feature_collection.features.len() < GEOJSON_SEQ_CHUNK_SIZE
+                && !self.reader.is_finished()
+            {
+                feature_collection.append(self.reader.read_chunk(GEOJSON_SEQ_STEP_SIZE)?);
+                let _ = ctx.send_progress(self.reader.progress()).await;
+            }
+            if self.is_first_chunk && feature_collection.features.is_empty() {
+                return Err(geo_file_loader::Error::NoGeometry);
+            }
+            let outcome = LoadGeoJsonSeqChunkJobOutcome {
+                feature_collection: geo_projected::Unprojected::new(feature_collection),
+                layer_id: self.layer_id,
+                is_first_chunk: self.is_first_chunk,
+                name: self.name.clone(),
                 source_crs_epsg_code: self.source_crs_epsg_code,
+                next_job: None,
+            };
+            Ok(if self.reader.is_finished() {
+                outcome
+            } else {
+                LoadGeoJsonSeqChunkJobOutcome {
+                    next_job: Some(LoadGeoJsonSeqChunkJob {
+                        is_first_chunk: false,
+                        ..self
+                    }),
+                    ..outcome
+                }
             })
         })
     }
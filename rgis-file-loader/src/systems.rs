@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
 
 fn handle_network_fetch_finished_jobs(
     mut load_event_reader: ResMut<Events<rgis_events::LoadFileEvent>>,
@@ -7,11 +8,19 @@ fn handle_network_fetch_finished_jobs(
     while let Some(outcome) = finished_jobs.take_next::<rgis_network::NetworkFetchJob>() {
         match outcome {
             Ok(fetched) => {
+                // Most of the files in the library are GeoJSON, so fall back to it
+                let file_format = geo_file_loader::detect_file_format(
+                    fetched.file_name.as_deref(),
+                    fetched.content_type.as_deref(),
+                    &fetched.bytes,
+                )
+                .unwrap_or(geo_file_loader::FileFormat::GeoJson);
                 load_event_reader.send(rgis_events::LoadFileEvent::FromBytes {
-                    file_format: geo_file_loader::FileFormat::GeoJson,
+                    file_format,
                     bytes: fetched.bytes,
                     file_name: fetched.name,
                     crs_epsg_code: fetched.crs_epsg_code,
+                    options: Default::default(),
                 });
             }
             Err(e) => {
@@ -36,16 +45,44 @@ fn handle_load_file_events(
                 crs_epsg_code,
                 name,
             }),
+            rgis_events::LoadFileEvent::FromBytes {
+                file_name,
+                bytes,
+                file_format: geo_file_loader::FileFormat::GeoJsonSeq,
+                crs_epsg_code,
+                options: _,
+            } => job_spawner.spawn(crate::jobs::LoadGeoJsonSeqChunkJob {
+                reader: geo_file_loader::GeoJsonSeqReader::new(bytes),
+                layer_id: rgis_layer_id::LayerId::new(),
+                is_first_chunk: true,
+                name: file_name,
+                source_crs_epsg_code: crs_epsg_code,
+            }),
             rgis_events::LoadFileEvent::FromBytes {
                 file_name,
                 bytes,
                 file_format,
                 crs_epsg_code,
+                options,
             } => job_spawner.spawn(crate::jobs::LoadFileJob {
                 source_crs_epsg_code: crs_epsg_code,
                 name: file_name,
-                bytes,
+                contents: crate::jobs::FileContents::Bytes(bytes),
+                file_format,
+                options,
+            }),
+            rgis_events::LoadFileEvent::FromPath {
+                file_name,
+                path,
                 file_format,
+                crs_epsg_code,
+                options,
+            } => job_spawner.spawn(crate::jobs::LoadFileJob {
+                source_crs_epsg_code: crs_epsg_code,
+                name: file_name,
+                contents: crate::jobs::FileContents::Path(path),
+                file_format,
+                options,
             }),
         }
     }
@@ -71,6 +108,74 @@ fn handle_load_file_job_finished_events(
     }
 }
 
+#[derive(Default)]
+struct GeoJsonSeqLoads {
+    // Jobs waiting for the previous chunk of their layer to be reprojected
+    next_jobs: HashMap<rgis_layer_id::LayerId, crate::jobs::LoadGeoJsonSeqChunkJob>,
+    // Layers deleted by the user, which are no longer loaded
+    deleted_layer_ids: HashSet<rgis_layer_id::LayerId>,
+}
+
+// Each chunk is sent to the layer, and the next one only gets loaded once this one is reprojected.
+fn handle_load_geojson_seq_chunk_job_finished_events(
+    mut finished_jobs: bevy_jobs::FinishedJobs,
+    mut streamed_features_event_writer: EventWriter<rgis_events::StreamedFeaturesEvent>,
+    mut layer_reprojected_event_reader: EventReader<rgis_events::LayerReprojectedEvent>,
+    mut appended_features_reprojected_event_reader: EventReader<
+        rgis_events::AppendedFeaturesReprojectedEvent,
+    >,
+    mut delete_layer_event_reader: EventReader<rgis_events::DeleteLayerEvent>,
+    mut job_spawner: bevy_jobs::JobSpawner,
+    mut loads: Local<GeoJsonSeqLoads>,
+) {
+    for event in delete_layer_event_reader.read() {
+        loads.next_jobs.remove(&event.0);
+        loads.deleted_layer_ids.insert(event.0);
+    }
+
+    while let Some(outcome) = finished_jobs.take_next::<crate::jobs::LoadGeoJsonSeqChunkJob>() {
+        let outcome = match outcome {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                bevy::log::error!("Encountered error when loading file: {:?}", e);
+                continue;
+            }
+        };
+        streamed_features_event_writer.send(if outcome.is_first_chunk {
+            rgis_events::StreamedFeaturesEvent::Create {
+                layer_id: outcome.layer_id,
+                feature_collection: outcome.feature_collection,
+                name: outcome.name,
+                source_crs_epsg_code: outcome.source_crs_epsg_code,
+            }
+        } else {
+            rgis_events::StreamedFeaturesEvent::Append {
+                layer_id: outcome.layer_id,
+                feature_collection: outcome.feature_collection,
+            }
+        });
+        if let Some(next_job) = outcome.next_job {
+            if !loads.deleted_layer_ids.contains(&outcome.layer_id) {
+                loads.next_jobs.insert(outcome.layer_id, next_job);
+            }
+        }
+    }
+
+    let reprojected_layer_ids = layer_reprojected_event_reader
+        .read()
+        .map(|event| event.0)
+        .chain(
+            appended_features_reprojected_event_reader
+                .read()
+                .map(|event| event.layer_id),
+        );
+    for layer_id in reprojected_layer_ids {
+        if let Some(next_job) = loads.next_jobs.remove(&layer_id) {
+            job_spawner.spawn(next_job);
+        }
+    }
+}
+
 pub fn configure(app: &mut App) {
     app.add_systems(
         Update,
@@ -78,6 +183,7 @@ pub fn configure(app: &mut App) {
             handle_network_fetch_finished_jobs,
             handle_load_file_events,
             handle_load_file_job_finished_events,
+            handle_load_geojson_seq_chunk_job_finished_events,
         ),
     );
 }
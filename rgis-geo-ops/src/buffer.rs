@@ -0,0 +1,413 @@
+use crate::{Operation, OperationEntry, Outcome};
+use bevy_egui::egui;
+use geo::{BooleanOps, BoundingRect, CoordsIter, GeodesicBearing, GeodesicDestination, MapCoords};
+use std::{error, f64::consts::PI, mem};
+
+// Segments used to approximate a quarter circle
+const QUADRANT_SEGMENTS: u32 = 8;
+
+const WGS_84_EPSG_CODE: u16 = 4326;
+
+#[derive(Default)]
+pub struct Buffer {
+    buffered: Vec<geo_features::Feature>,
+    distance_text: String,
+    unit: DistanceUnit,
+    distance: Option<Distance>,
+    crs_epsg_code: u16,
+    // Distance of the last preview
+    preview: Option<(Distance, Preview)>,
+    preview_requested: bool,
+    execute_pressed: bool,
+}
+
+enum Preview {
+    Pending,
+    NumNodes(usize),
+    Failed,
+}
+
+impl OperationEntry for Buffer {
+    const ALLOWED_GEOM_TYPES: geo_geom_type::GeomType = geo_geom_type::GeomType::all();
+    const NAME: &'static str = "Buffer geometries";
+
+    fn build() -> Box<dyn Operation + Send + Sync> {
+        Box::<Buffer>::default()
+    }
+}
+
+impl Operation for Buffer {
+    fn next_action(&self) -> crate::Action {
+        if self.execute_pressed {
+            crate::Action::Perform
+        } else {
+            crate::Action::RenderUi
+        }
+    }
+
+    fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        feature_collection: &geo_projected::Unprojected<geo_features::FeatureCollection>,
+        crs_epsg_code: u16,
+    ) {
+        let crs_units = transform::crs_units(crs_epsg_code);
+        let has_local_scale = transform::has_local_scale(crs_epsg_code);
+
+        ui.label("Distance:");
+        ui.text_edit_singleline(&mut self.distance_text);
+        egui::ComboBox::from_label("Unit")
+            .selected_text(self.unit.name())
+            .show_ui(ui, |ui| {
+                for unit in DistanceUnit::ALL {
+                    ui.selectable_value(&mut self.unit, unit, unit.name());
+                }
+            });
+        let button = egui::Button::new("Execute");
+        let Some(distance) = self
+            .distance_text
+            .parse::<f64>()
+            .ok()
+            .filter(|distance| distance.is_finite())
+        else {
+            ui.add_enabled(false, button);
+            return;
+        };
+        let Some(distance) = Distance::new(distance, self.unit, crs_units, has_local_scale) else {
+            ui.label(format!(
+                "The units of EPSG:{crs_epsg_code} are unknown, use CRS units instead"
+            ));
+            ui.add_enabled(false, button);
+            return;
+        };
+        self.distance = Some(distance);
+        self.crs_epsg_code = crs_epsg_code;
+
+        if let Distance::Geodesic(_) | Distance::Reprojected(_) = distance {
+            ui.label("The distance is measured geodesically");
+        }
+        ui.label(format!(
+            "Previous # of nodes: {}",
+            feature_collection.0.coords_count()
+        ));
+        if ui.button("Preview").clicked() {
+            self.preview = Some((distance, Preview::Pending));
+            self.preview_requested = true;
+        }
+        match self.preview {
+            Some((previous, ref preview)) if previous == distance => match preview {
+                Preview::Pending => {
+                    ui.label("Buffering…");
+                }
+                Preview::NumNodes(num_nodes) => {
+                    ui.label(format!("Buffered # of nodes: {num_nodes}"));
+                }
+                Preview::Failed => {
+                    ui.label("<ENCOUNTERED AN ERROR>");
+                }
+            },
+            _ => {}
+        }
+        if ui.add_enabled(true, button).clicked() {
+            self.execute_pressed = true;
+        }
+    }
+
+    fn preview(&mut self) -> Option<Box<dyn Operation + Send + Sync>> {
+        if !mem::take(&mut self.preview_requested) {
+            return None;
+        }
+        Some(Box::new(Buffer {
+            distance: self.distance,
+            crs_epsg_code: self.crs_epsg_code,
+            ..Default::default()
+        }))
+    }
+
+    fn set_preview(&mut self, outcome: Result<Outcome, String>) {
+        let Some((_, ref mut preview)) = self.preview else {
+            return;
+        };
+        *preview = match outcome {
+            Ok(Outcome::FeatureCollection(feature_collection)) => {
+                Preview::NumNodes(feature_collection.0.coords_count())
+            }
+            Ok(Outcome::Text(_)) | Err(_) => Preview::Failed,
+        };
+    }
+
+    fn perform(
+        &mut self,
+        feature_collection: geo_projected::Unprojected<geo_features::FeatureCollection>,
+    ) -> Result<Outcome, Box<dyn error::Error>> {
+        let distance = self.distance.ok_or("No distance was entered")?;
+        let transformers = match distance {
+            Distance::Reprojected(_) => Some((
+                transform::Transformer::setup(self.crs_epsg_code, WGS_84_EPSG_CODE)?,
+                transform::Transformer::setup(WGS_84_EPSG_CODE, self.crs_epsg_code)?,
+            )),
+            Distance::Planar(_) | Distance::Geodesic(_) => None,
+        };
+        for feature in feature_collection.into_features_iter() {
+            let Some(ref geometry) = feature.0.geometry else {
+                continue;
+            };
+            let buffered = match (distance, &transformers) {
+                (Distance::Planar(distance), _) => buffer(geometry, distance),
+                (Distance::Geodesic(meters), _) => buffer_geodesic(geometry, meters),
+                (Distance::Reprojected(meters), Some((to_wgs_84, from_wgs_84))) => {
+                    let mut geometry = geometry.clone();
+                    to_wgs_84.transform(&mut geometry)?;
+                    let mut buffered = buffer_geodesic(&geometry, meters).into();
+                    from_wgs_84.transform(&mut buffered)?;
+                    geo::MultiPolygon::try_from(buffered)?
+                }
+                (Distance::Reprojected(_), None) => continue,
+            };
+            // Negative distances can erode polygons away entirely
+            if buffered.0.is_empty() {
+                continue;
+            }
+            self.buffered.push(
+                geo_features::FeatureBuilder::new()
+                    .with_geometry(buffered.into())
+                    .with_properties(feature.0.properties)
+                    .build(),
+            );
+        }
+        self.finalize()
+    }
+
+    fn finalize(&mut self) -> Result<Outcome, Box<dyn error::Error>> {
+        let buffered = mem::take(&mut self.buffered);
+        Ok(Outcome::FeatureCollection(geo_projected::Unprojected::new(
+            geo_features::FeatureCollection::from_features(buffered),
+        )))
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+enum DistanceUnit {
+    /// Same units as the coordinates of the layer
+    CrsUnits,
+    #[default]
+    Meters,
+    Kilometers,
+    Feet,
+    Miles,
+}
+
+impl DistanceUnit {
+    const ALL: [DistanceUnit; 5] = [
+        DistanceUnit::CrsUnits,
+        DistanceUnit::Meters,
+        DistanceUnit::Kilometers,
+        DistanceUnit::Feet,
+        DistanceUnit::Miles,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            DistanceUnit::CrsUnits => "CRS units",
+            DistanceUnit::Meters => "Meters",
+            DistanceUnit::Kilometers => "Kilometers",
+            DistanceUnit::Feet => "Feet",
+            DistanceUnit::Miles => "Miles",
+        }
+    }
+
+    fn meters(self) -> Option<f64> {
+        match self {
+            DistanceUnit::CrsUnits => None,
+            DistanceUnit::Meters => Some(1.),
+            DistanceUnit::Kilometers => Some(1000.),
+            DistanceUnit::Feet => Some(0.3048),
+            DistanceUnit::Miles => Some(1609.344),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Distance {
+    /// Same units as the coordinates of the layer
+    Planar(f64),
+    /// Meters, for layers with geographic coordinates
+    Geodesic(f64),
+    /// Meters, for layers in projections whose scale varies too much to buffer in, like Web
+    /// Mercator. The geometries are buffered geodesically in WGS 84 and projected back.
+    Reprojected(f64),
+}
+
+impl Distance {
+    fn new(
+        distance: f64,
+        unit: DistanceUnit,
+        crs_units: Option<transform::CrsUnits>,
+        has_local_scale: bool,
+    ) -> Option<Self> {
+        let Some(meters_per_unit) = unit.meters() else {
+            return Some(Distance::Planar(distance));
+        };
+        let meters = distance * meters_per_unit;
+        Some(match crs_units? {
+            transform::CrsUnits::Degrees => Distance::Geodesic(meters),
+            transform::CrsUnits::Linear { meters_per_unit } if has_local_scale => {
+                Distance::Planar(meters / meters_per_unit)
+            }
+            transform::CrsUnits::Linear { .. } => Distance::Reprojected(meters),
+        })
+    }
+}
+
+// Buffers each part of the geometry on its own, as parts far apart from each other would be
+// distorted in a projection centered on all of them.
+fn buffer_geodesic(geometry: &geo::Geometry, meters: f64) -> geo::MultiPolygon {
+    let parts: Vec<geo::Geometry> = match geometry {
+        geo::Geometry::MultiPoint(multi_point) => {
+            multi_point.iter().map(|&point| point.into()).collect()
+        }
+        geo::Geometry::MultiLineString(multi_line_string) => multi_line_string
+            .iter()
+            .map(|line_string| line_string.clone().into())
+            .collect(),
+        geo::Geometry::MultiPolygon(multi_polygon) => multi_polygon
+            .iter()
+            .map(|polygon| polygon.clone().into())
+            .collect(),
+        geo::Geometry::GeometryCollection(geometry_collection) => {
+            return crate::union::union_all(
+                geometry_collection
+                    .iter()
+                    .map(|geometry| buffer_geodesic(geometry, meters))
+                    .collect(),
+            );
+        }
+        _ => return buffer_azimuthal_equidistant(geometry, meters),
+    };
+    crate::union::union_all(
+        parts
+            .iter()
+            .map(|part| buffer_azimuthal_equidistant(part, meters))
+            .collect(),
+    )
+}
+
+// Buffers in an azimuthal equidistant projection centered on the geometry, in which distances from
+// the center are true to the ellipsoid.
+fn buffer_azimuthal_equidistant(geometry: &geo::Geometry, meters: f64) -> geo::MultiPolygon {
+    let Some(bounding_rect) = geometry.bounding_rect() else {
+        return geo::MultiPolygon::new(vec![]);
+    };
+    let center = geo::Point::from(bounding_rect.center());
+    let projected = geometry.map_coords(|coord| {
+        let (bearing, distance) = center.geodesic_bearing_distance(coord.into());
+        let bearing = bearing.to_radians();
+        geo::Coord {
+            x: distance * bearing.sin(),
+            y: distance * bearing.cos(),
+        }
+    });
+    buffer(&projected, meters).map_coords(|coord| {
+        let bearing = coord.x.atan2(coord.y).to_degrees();
+        let distance = coord.x.hypot(coord.y);
+        center.geodesic_destination(bearing, distance).0
+    })
+}
+
+fn buffer(geometry: &geo::Geometry, distance: f64) -> geo::MultiPolygon {
+    match geometry {
+        geo::Geometry::Point(point) => buffer_coords(&[point.0], distance),
+        geo::Geometry::MultiPoint(multi_point) => crate::union::union_all(
+            multi_point
+                .iter()
+                .map(|point| buffer_coords(&[point.0], distance))
+                .collect(),
+        ),
+        geo::Geometry::Line(line) => buffer_coords(&[line.start, line.end], distance),
+        geo::Geometry::LineString(line_string) => buffer_coords(&line_string.0, distance),
+        geo::Geometry::MultiLineString(multi_line_string) => crate::union::union_all(
+            multi_line_string
+                .iter()
+                .map(|line_string| buffer_coords(&line_string.0, distance))
+                .collect(),
+        ),
+        geo::Geometry::Polygon(polygon) => buffer_polygon(polygon, distance),
+        geo::Geometry::MultiPolygon(multi_polygon) => crate::union::union_all(
+            multi_polygon
+                .iter()
+                .map(|polygon| buffer_polygon(polygon, distance))
+                .collect(),
+        ),
+        geo::Geometry::Rect(rect) => buffer_polygon(&rect.to_polygon(), distance),
+        geo::Geometry::Triangle(triangle) => buffer_polygon(&triangle.to_polygon(), distance),
+        geo::Geometry::GeometryCollection(geometry_collection) => crate::union::union_all(
+            geometry_collection
+                .iter()
+                .map(|geometry| buffer(geometry, distance))
+                .collect(),
+        ),
+    }
+}
+
+fn buffer_polygon(polygon: &geo::Polygon, distance: f64) -> geo::MultiPolygon {
+    let rings = std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .map(|ring| buffer_coords(&ring.0, distance.abs()));
+    let polygon = geo::MultiPolygon::new(vec![polygon.clone()]);
+    if distance < 0. {
+        polygon.difference(&crate::union::union_all(rings.collect()))
+    } else {
+        crate::union::union_all(std::iter::once(polygon).chain(rings).collect())
+    }
+}
+
+// The area within `distance` of the path through the coordinates, as the union of a circle around
+// each coordinate and a rectangle along each segment. Pieces that share vertices trip up the boolean
+// operations, which is why the circles aren't joined to the rectangles directly.
+fn buffer_coords(coords: &[geo::Coord], distance: f64) -> geo::MultiPolygon {
+    if distance <= 0. {
+        return geo::MultiPolygon::new(vec![]);
+    }
+    let mut vertices = coords.to_vec();
+    vertices.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    vertices.dedup();
+    let circles = vertices.into_iter().map(|vertex| {
+        geo::MultiPolygon::new(vec![geo::Polygon::new(
+            circle(vertex, distance).collect(),
+            vec![],
+        )])
+    });
+    let rectangles = coords.windows(2).filter_map(|window| match window {
+        [start, end] if start != end => {
+            let (dx, dy) = (end.x - start.x, end.y - start.y);
+            let length = dx.hypot(dy);
+            let normal = geo::Coord {
+                x: -dy / length * distance,
+                y: dx / length * distance,
+            };
+            Some(geo::MultiPolygon::new(vec![geo::Polygon::new(
+                vec![
+                    *start + normal,
+                    *end + normal,
+                    *end - normal,
+                    *start - normal,
+                ]
+                .into(),
+                vec![],
+            )]))
+        }
+        _ => None,
+    });
+    crate::union::union_all(circles.chain(rectangles).collect())
+}
+
+fn circle(center: geo::Coord, radius: f64) -> impl Iterator<Item = geo::Coord> {
+    let num_segments = 4 * QUADRANT_SEGMENTS;
+    (0..num_segments).map(move |i| {
+        let angle = 2. * PI * f64::from(i) / f64::from(num_segments);
+        geo::Coord {
+            x: center.x + radius * angle.cos(),
+            y: center.y + radius * angle.sin(),
+        }
+    })
+}
@@ -0,0 +1,76 @@
+use crate::{Operation, OperationEntry, Outcome};
+use bevy_egui::egui;
+use std::{error, mem};
+
+#[derive(Default)]
+pub struct Clip {
+    polygon: Option<geo::Polygon>,
+    clipped: Vec<geo_features::Feature>,
+    execute_pressed: bool,
+}
+
+impl OperationEntry for Clip {
+    const ALLOWED_GEOM_TYPES: geo_geom_type::GeomType = geo_geom_type::GeomType::all();
+    const NAME: &'static str = "Clip to rectangle";
+
+    fn build() -> Box<dyn Operation + Send + Sync> {
+        Box::<Clip>::default()
+    }
+}
+
+impl Operation for Clip {
+    fn next_action(&self) -> crate::Action {
+        if self.execute_pressed {
+            crate::Action::Perform
+        } else {
+            crate::Action::RenderUi
+        }
+    }
+
+    fn needs_map_rect(&self) -> bool {
+        true
+    }
+
+    fn set_map_rect(&mut self, polygon: Option<geo_projected::Unprojected<geo::Polygon>>) {
+        self.polygon = polygon.map(|polygon| polygon.0);
+    }
+
+    fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        _feature_collection: &geo_projected::Unprojected<geo_features::FeatureCollection>,
+        _crs_epsg_code: u16,
+    ) {
+        if ui
+            .add_enabled(self.polygon.is_some(), egui::Button::new("Execute"))
+            .clicked()
+        {
+            self.execute_pressed = true;
+        }
+    }
+
+    fn visit_feature(&mut self, feature: &geo_projected::Unprojected<geo_features::Feature>) {
+        let Some(ref polygon) = self.polygon else {
+            return;
+        };
+        let Some(ref geometry) = feature.0.geometry else {
+            return;
+        };
+        let Some(clipped) = geo_clip::clip(geometry, polygon) else {
+            return;
+        };
+        self.clipped.push(
+            geo_features::FeatureBuilder::new()
+                .with_geometry(clipped)
+                .with_properties(feature.0.properties.clone())
+                .build(),
+        );
+    }
+
+    fn finalize(&mut self) -> Result<Outcome, Box<dyn error::Error>> {
+        let clipped = mem::take(&mut self.clipped);
+        Ok(Outcome::FeatureCollection(geo_projected::Unprojected::new(
+            geo_features::FeatureCollection::from_features(clipped),
+        )))
+    }
+}
@@ -0,0 +1,229 @@
+use crate::{Operation, OperationEntry, Outcome};
+use bevy_egui::egui;
+use std::{cmp, collections, error, mem};
+
+const ALL_FEATURES_LABEL: &str = "All features";
+const COUNT_PROPERTY_NAME: &str = "count";
+
+#[derive(Default)]
+pub struct Dissolve {
+    /// Features are grouped by the value of this property, or all together when `None`.
+    property: Option<String>,
+    count: bool,
+    aggregations: collections::BTreeMap<String, Aggregation>,
+    // Numeric and categorical property names, sorted, found the first time the UI is shown
+    property_names: Option<(Vec<String>, collections::BTreeSet<String>)>,
+    groups: collections::BTreeMap<GroupKey, Group>,
+    execute_pressed: bool,
+}
+
+// Value of the property features are grouped by, so that the number 1 and the string "1" end up in
+// different groups.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum GroupKey {
+    // Features without the property, or with a null value
+    Missing,
+    Boolean(bool),
+    Number(GroupNumber),
+    String(String),
+}
+
+impl GroupKey {
+    fn new(value: Option<&geo_features::Value>) -> Self {
+        match value {
+            None | Some(geo_features::Value::Null) => GroupKey::Missing,
+            Some(geo_features::Value::Boolean(b)) => GroupKey::Boolean(*b),
+            // Adding zero turns -0 into 0
+            Some(geo_features::Value::Number(n)) => GroupKey::Number(GroupNumber(n + 0.)),
+            Some(geo_features::Value::String(s)) => GroupKey::String(s.clone()),
+        }
+    }
+}
+
+struct GroupNumber(f64);
+
+impl PartialEq for GroupNumber {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == cmp::Ordering::Equal
+    }
+}
+
+impl Eq for GroupNumber {}
+
+impl PartialOrd for GroupNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GroupNumber {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct Aggregation {
+    sum: bool,
+    mean: bool,
+}
+
+#[derive(Default)]
+struct Group {
+    key: Option<geo_features::Value>,
+    polygons: Vec<geo::MultiPolygon>,
+    num_features: usize,
+    // Sum and number of the values of each aggregated property
+    sums: collections::HashMap<String, (f64, usize)>,
+}
+
+impl OperationEntry for Dissolve {
+    const ALLOWED_GEOM_TYPES: geo_geom_type::GeomType = geo_geom_type::GeomType::from_bits_truncate(
+        geo_geom_type::GeomType::POLYGON.bits() | geo_geom_type::GeomType::MULTI_POLYGON.bits(),
+    );
+    const NAME: &'static str = "Dissolve polygons";
+
+    fn build() -> Box<dyn Operation + Send + Sync> {
+        Box::<Dissolve>::default()
+    }
+}
+
+impl Operation for Dissolve {
+    fn next_action(&self) -> crate::Action {
+        if self.execute_pressed {
+            crate::Action::Perform
+        } else {
+            crate::Action::RenderUi
+        }
+    }
+
+    fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        feature_collection: &geo_projected::Unprojected<geo_features::FeatureCollection>,
+        _crs_epsg_code: u16,
+    ) {
+        let (numeric_property_names, property_names) =
+            self.property_names.get_or_insert_with(|| {
+                let numeric_property_names = feature_collection
+                    .0
+                    .numeric_property_names()
+                    .into_iter()
+                    .map(String::from)
+                    .collect::<Vec<_>>();
+                let property_names = numeric_property_names
+                    .iter()
+                    .cloned()
+                    .chain(
+                        feature_collection
+                            .0
+                            .categorical_property_names()
+                            .into_iter()
+                            .map(String::from),
+                    )
+                    .collect();
+                (numeric_property_names, property_names)
+            });
+
+        egui::ComboBox::from_label("Dissolve by")
+            .selected_text(self.property.as_deref().unwrap_or(ALL_FEATURES_LABEL))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.property, None, ALL_FEATURES_LABEL);
+                for name in property_names.iter() {
+                    ui.selectable_value(&mut self.property, Some(name.clone()), name);
+                }
+            });
+        ui.checkbox(&mut self.count, "Count features");
+        if !numeric_property_names.is_empty() {
+            ui.label("Aggregate:");
+            egui::Grid::new("dissolve_aggregations").show(ui, |ui| {
+                for name in numeric_property_names.iter() {
+                    let aggregation = self.aggregations.entry(name.clone()).or_default();
+                    ui.label(name);
+                    ui.checkbox(&mut aggregation.sum, "Sum");
+                    ui.checkbox(&mut aggregation.mean, "Mean");
+                    ui.end_row();
+                }
+            });
+        }
+        if ui.button("Execute").clicked() {
+            self.execute_pressed = true;
+        }
+    }
+
+    fn visit_feature(&mut self, feature: &geo_projected::Unprojected<geo_features::Feature>) {
+        let value = self
+            .property
+            .as_ref()
+            .and_then(|property| feature.0.properties.get(property));
+        let group = self
+            .groups
+            .entry(GroupKey::new(value))
+            .or_insert_with(|| Group {
+                key: value
+                    .filter(|value| !matches!(value, geo_features::Value::Null))
+                    .cloned(),
+                ..Default::default()
+            });
+        group.num_features += 1;
+
+        if let Some(ref geometry) = feature.0.geometry {
+            group
+                .polygons
+                .push(crate::multi_polygon::from_geometry(geometry));
+        }
+
+        for (name, aggregation) in &self.aggregations {
+            if !aggregation.sum && !aggregation.mean {
+                continue;
+            }
+            let Some(value) = feature.0.properties.get(name).and_then(|v| v.as_number()) else {
+                continue;
+            };
+            let (sum, num_values) = group.sums.entry(name.clone()).or_default();
+            *sum += value;
+            *num_values += 1;
+        }
+    }
+
+    fn finalize(&mut self) -> Result<Outcome, Box<dyn error::Error>> {
+        let features = mem::take(&mut self.groups)
+            .into_values()
+            .map(|group| {
+                let mut properties = geo_features::Properties::new();
+                if let (Some(property), Some(key)) = (&self.property, group.key) {
+                    properties.insert(property.clone(), key);
+                }
+                if self.count {
+                    properties.insert(
+                        COUNT_PROPERTY_NAME.into(),
+                        geo_features::Value::Number(group.num_features as f64),
+                    );
+                }
+                for (name, aggregation) in &self.aggregations {
+                    // Groups without any values get null for both
+                    let (sum, mean) = match group.sums.get(name) {
+                        Some(&(sum, num_values)) if num_values > 0 => (
+                            geo_features::Value::Number(sum),
+                            geo_features::Value::Number(sum / num_values as f64),
+                        ),
+                        _ => (geo_features::Value::Null, geo_features::Value::Null),
+                    };
+                    if aggregation.sum {
+                        properties.insert(format!("{name}_sum"), sum);
+                    }
+                    if aggregation.mean {
+                        properties.insert(format!("{name}_mean"), mean);
+                    }
+                }
+                geo_features::FeatureBuilder::new()
+                    .with_geometry(crate::union::union_all(group.polygons).into())
+                    .with_properties(properties)
+                    .build()
+            })
+            .collect();
+        Ok(Outcome::FeatureCollection(geo_projected::Unprojected::new(
+            geo_features::FeatureCollection::from_features(features),
+        )))
+    }
+}
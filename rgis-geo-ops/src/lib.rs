@@ -30,6 +30,21 @@ pub use smoothing::Smoothing;
 mod triangulate;
 pub use triangulate::Triangulate;
 
+mod buffer;
+pub use buffer::Buffer;
+
+mod dissolve;
+pub use dissolve::Dissolve;
+
+mod overlay;
+pub use overlay::Overlay;
+
+mod clip;
+pub use clip::Clip;
+
+mod multi_polygon;
+mod union;
+
 pub enum Outcome {
     Text(String),
     FeatureCollection(Unprojected<geo_features::FeatureCollection>),
@@ -84,10 +99,43 @@ pub trait Operation {
         Action::Perform
     }
 
+    /// Whether the operation also takes another layer as input, which is picked in the operation
+    /// window. Before the operation is performed, the layer is passed to `set_other_layer` in the
+    /// CRS of the layer the operation is performed on.
+    fn needs_other_layer(&self) -> bool {
+        false
+    }
+
+    fn set_other_layer(
+        &mut self,
+        _feature_collection: Option<Unprojected<geo_features::FeatureCollection>>,
+    ) {
+    }
+
+    /// Whether the operation also takes a rectangle on the map as input, either the current view or
+    /// one the user draws. It's passed to `set_map_rect` as a polygon in the CRS of the layer, as
+    /// its edges may curve there.
+    fn needs_map_rect(&self) -> bool {
+        false
+    }
+
+    fn set_map_rect(&mut self, _polygon: Option<Unprojected<geo::Polygon>>) {}
+
+    /// A copy of the operation with its current parameters, which the operation window performs in
+    /// the background to preview its outcome. It's asked for every frame, so it's only `Some` right
+    /// after the user asks for a preview.
+    fn preview(&mut self) -> Option<Box<dyn Operation + Send + Sync>> {
+        None
+    }
+
+    /// Outcome of the last operation returned by `preview`.
+    fn set_preview(&mut self, _outcome: Result<Outcome, String>) {}
+
     fn ui(
         &mut self,
         _ui: &mut bevy_egui::egui::Ui,
         _feature_collection: &Unprojected<geo_features::FeatureCollection>,
+        _crs_epsg_code: u16,
     ) {
     }
 
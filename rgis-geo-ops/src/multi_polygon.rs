@@ -0,0 +1,27 @@
+/// The polygons of the geometry, leaving out points and lines.
+pub(crate) fn from_geometry(geometry: &geo::Geometry) -> geo::MultiPolygon {
+    let mut polygons = vec![];
+    push_polygons(geometry, &mut polygons);
+    geo::MultiPolygon::new(polygons)
+}
+
+fn push_polygons(geometry: &geo::Geometry, polygons: &mut Vec<geo::Polygon>) {
+    match geometry {
+        geo::Geometry::Polygon(polygon) => polygons.push(polygon.clone()),
+        geo::Geometry::MultiPolygon(multi_polygon) => {
+            polygons.extend(multi_polygon.iter().cloned())
+        }
+        geo::Geometry::Rect(rect) => polygons.push(rect.to_polygon()),
+        geo::Geometry::Triangle(triangle) => polygons.push(triangle.to_polygon()),
+        geo::Geometry::GeometryCollection(geometry_collection) => {
+            for geometry in geometry_collection {
+                push_polygons(geometry, polygons);
+            }
+        }
+        geo::Geometry::Point(_)
+        | geo::Geometry::Line(_)
+        | geo::Geometry::LineString(_)
+        | geo::Geometry::MultiPoint(_)
+        | geo::Geometry::MultiLineString(_) => {}
+    }
+}
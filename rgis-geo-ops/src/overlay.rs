@@ -0,0 +1,277 @@
+use crate::{Operation, OperationEntry, Outcome};
+use bevy_egui::egui;
+use geo::BooleanOps;
+use std::{collections, error, mem};
+
+#[derive(Default)]
+pub struct Overlay {
+    kind: OverlayKind,
+    other: Option<geo_projected::Unprojected<geo_features::FeatureCollection>>,
+    pieces: Vec<Piece>,
+    execute_pressed: bool,
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+enum OverlayKind {
+    #[default]
+    Intersection,
+    Union,
+    Difference,
+    SymmetricDifference,
+}
+
+impl OverlayKind {
+    const ALL: [OverlayKind; 4] = [
+        OverlayKind::Intersection,
+        OverlayKind::Union,
+        OverlayKind::Difference,
+        OverlayKind::SymmetricDifference,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            OverlayKind::Intersection => "Intersection",
+            OverlayKind::Union => "Union",
+            OverlayKind::Difference => "Difference",
+            OverlayKind::SymmetricDifference => "Symmetric difference",
+        }
+    }
+}
+
+// The polygons of a feature
+struct Piece {
+    polygons: geo::MultiPolygon,
+    properties: geo_features::Properties,
+    bounding_rect: geo::Rect,
+}
+
+type IndexedRect = rstar::primitives::GeomWithData<rstar::primitives::Rectangle<[f64; 2]>, usize>;
+
+// Finds the pieces whose bounding rectangles intersect another piece's.
+struct PieceIndex<'a> {
+    pieces: &'a [Piece],
+    tree: rstar::RTree<IndexedRect>,
+}
+
+impl<'a> PieceIndex<'a> {
+    fn new(pieces: &'a [Piece]) -> Self {
+        let rects = pieces
+            .iter()
+            .enumerate()
+            .map(|(i, piece)| {
+                let rect = piece.bounding_rect;
+                IndexedRect::new(
+                    rstar::primitives::Rectangle::from_corners(
+                        rect.min().into(),
+                        rect.max().into(),
+                    ),
+                    i,
+                )
+            })
+            .collect();
+        PieceIndex {
+            pieces,
+            tree: rstar::RTree::bulk_load(rects),
+        }
+    }
+
+    fn intersecting(&self, piece: &Piece) -> impl Iterator<Item = &'a Piece> + '_ {
+        let rect = piece.bounding_rect;
+        let envelope = rstar::AABB::from_corners(rect.min().into(), rect.max().into());
+        self.tree
+            .locate_in_envelope_intersecting(&envelope)
+            .filter_map(|indexed| self.pieces.get(indexed.data))
+    }
+}
+
+impl Piece {
+    fn from_feature(feature: &geo_features::Feature) -> Option<Self> {
+        let polygons = crate::multi_polygon::from_geometry(feature.geometry.as_ref()?);
+        if polygons.0.is_empty() {
+            return None;
+        }
+        Some(Piece {
+            polygons,
+            properties: feature.properties.clone(),
+            bounding_rect: feature.bounding_rect?,
+        })
+    }
+}
+
+impl OperationEntry for Overlay {
+    const ALLOWED_GEOM_TYPES: geo_geom_type::GeomType = geo_geom_type::GeomType::from_bits_truncate(
+        geo_geom_type::GeomType::POLYGON.bits() | geo_geom_type::GeomType::MULTI_POLYGON.bits(),
+    );
+    const NAME: &'static str = "Overlay with layer";
+
+    fn build() -> Box<dyn Operation + Send + Sync> {
+        Box::<Overlay>::default()
+    }
+}
+
+impl Operation for Overlay {
+    fn next_action(&self) -> crate::Action {
+        if self.execute_pressed {
+            crate::Action::Perform
+        } else {
+            crate::Action::RenderUi
+        }
+    }
+
+    fn needs_other_layer(&self) -> bool {
+        true
+    }
+
+    fn set_other_layer(
+        &mut self,
+        feature_collection: Option<geo_projected::Unprojected<geo_features::FeatureCollection>>,
+    ) {
+        self.other = feature_collection;
+    }
+
+    fn ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        _feature_collection: &geo_projected::Unprojected<geo_features::FeatureCollection>,
+        _crs_epsg_code: u16,
+    ) {
+        egui::ComboBox::from_label("Operation")
+            .selected_text(self.kind.name())
+            .show_ui(ui, |ui| {
+                for kind in OverlayKind::ALL {
+                    ui.selectable_value(&mut self.kind, kind, kind.name());
+                }
+            });
+        if ui.button("Execute").clicked() {
+            self.execute_pressed = true;
+        }
+    }
+
+    fn visit_feature(&mut self, feature: &geo_projected::Unprojected<geo_features::Feature>) {
+        self.pieces.extend(Piece::from_feature(&feature.0));
+    }
+
+    fn finalize(&mut self) -> Result<Outcome, Box<dyn error::Error>> {
+        let pieces = mem::take(&mut self.pieces);
+        let other_pieces = self
+            .other
+            .as_ref()
+            .ok_or("No layer to overlay was picked")?
+            .0
+            .features
+            .iter()
+            .filter_map(Piece::from_feature)
+            .collect::<Vec<_>>();
+
+        let (index, other_index) = (PieceIndex::new(&pieces), PieceIndex::new(&other_pieces));
+
+        let mut features = vec![];
+        if let OverlayKind::Intersection | OverlayKind::Union = self.kind {
+            let renames = duplicate_property_renames(&pieces, &other_pieces);
+            features.extend(intersections(&pieces, &other_index, &renames));
+        }
+        if let OverlayKind::Union | OverlayKind::Difference | OverlayKind::SymmetricDifference =
+            self.kind
+        {
+            features.extend(differences(&pieces, &other_index));
+        }
+        if let OverlayKind::Union | OverlayKind::SymmetricDifference = self.kind {
+            features.extend(differences(&other_pieces, &index));
+        }
+
+        Ok(Outcome::FeatureCollection(geo_projected::Unprojected::new(
+            geo_features::FeatureCollection::from_features(features),
+        )))
+    }
+}
+
+// New names for the properties of the other layer that the layer also has, suffixed with the
+// first number that makes them unique among the properties of both layers.
+fn duplicate_property_renames(
+    pieces: &[Piece],
+    other_pieces: &[Piece],
+) -> collections::HashMap<String, String> {
+    let names = pieces
+        .iter()
+        .flat_map(|piece| piece.properties.keys())
+        .collect::<collections::HashSet<_>>();
+    let other_names = other_pieces
+        .iter()
+        .flat_map(|piece| piece.properties.keys())
+        .collect::<collections::HashSet<_>>();
+    let mut taken = names
+        .union(&other_names)
+        .map(|name| (*name).clone())
+        .collect::<collections::HashSet<_>>();
+    let mut duplicates = names.intersection(&other_names).collect::<Vec<_>>();
+    duplicates.sort();
+    duplicates
+        .into_iter()
+        .map(|name| {
+            let renamed = (2..)
+                .map(|n| format!("{name}_{n}"))
+                .find(|renamed| !taken.contains(renamed))
+                .unwrap_or_default();
+            taken.insert(renamed.clone());
+            ((*name).clone(), renamed)
+        })
+        .collect()
+}
+
+// Every overlapping pair of pieces, with the properties of both.
+fn intersections(
+    pieces: &[Piece],
+    other_index: &PieceIndex,
+    renames: &collections::HashMap<String, String>,
+) -> Vec<geo_features::Feature> {
+    let mut features = vec![];
+    for piece in pieces {
+        for other_piece in other_index.intersecting(piece) {
+            let intersection = piece.polygons.intersection(&other_piece.polygons);
+            if intersection.0.is_empty() {
+                continue;
+            }
+            let mut properties = piece.properties.clone();
+            for (name, value) in &other_piece.properties {
+                let name = renames.get(name).unwrap_or(name);
+                properties.insert(name.clone(), value.clone());
+            }
+            features.push(
+                geo_features::FeatureBuilder::new()
+                    .with_geometry(intersection.into())
+                    .with_properties(properties)
+                    .build(),
+            );
+        }
+    }
+    features
+}
+
+// What's left of each piece outside of the other pieces, with its own properties.
+fn differences(pieces: &[Piece], other_index: &PieceIndex) -> Vec<geo_features::Feature> {
+    pieces
+        .iter()
+        .filter_map(|piece| {
+            let overlapping = other_index
+                .intersecting(piece)
+                .map(|other| other.polygons.clone())
+                .collect::<Vec<_>>();
+            let difference = if overlapping.is_empty() {
+                piece.polygons.clone()
+            } else {
+                piece
+                    .polygons
+                    .difference(&crate::union::union_all(overlapping))
+            };
+            if difference.0.is_empty() {
+                return None;
+            }
+            Some(
+                geo_features::FeatureBuilder::new()
+                    .with_geometry(difference.into())
+                    .with_properties(piece.properties.clone())
+                    .build(),
+            )
+        })
+        .collect()
+}
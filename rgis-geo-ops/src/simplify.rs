@@ -38,6 +38,7 @@ impl Operation for Simplify {
         &mut self,
         ui: &mut bevy_egui::egui::Ui,
         feature_collection: &geo_projected::Unprojected<geo_features::FeatureCollection>,
+        _crs_epsg_code: u16,
     ) {
         ui.label("Epsilon:");
         ui.text_edit_singleline(&mut self.epsilon_text);
@@ -0,0 +1,20 @@
+use geo::BooleanOps;
+
+// Unions the polygons pairwise, level by level, which keeps the intermediate results small compared
+// to folding them one at a time.
+pub(crate) fn union_all(mut multi_polygons: Vec<geo::MultiPolygon>) -> geo::MultiPolygon {
+    while multi_polygons.len() > 1 {
+        let mut iter = multi_polygons.into_iter();
+        let mut unioned = vec![];
+        while let Some(a) = iter.next() {
+            unioned.push(match iter.next() {
+                Some(b) => a.union(&b),
+                None => a,
+            });
+        }
+        multi_polygons = unioned;
+    }
+    multi_polygons
+        .pop()
+        .unwrap_or_else(|| geo::MultiPolygon::new(vec![]))
+}
@@ -0,0 +1,358 @@
+// Methods for binning numeric property values into classes. The algorithms follow the ones used
+// by QGIS so analysts get the same class breaks they would get in desktop GIS tools.
+
+// Natural breaks is O(k·n²), so larger datasets are sampled down to this many values (same limit
+// QGIS uses).
+const MAX_NATURAL_BREAKS_VALUES: usize = 3000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClassificationMethod {
+    EqualInterval,
+    Quantile,
+    NaturalBreaks,
+    StandardDeviation,
+    /// User-entered breaks between classes. The minimum and maximum values are added as the
+    /// outer bounds.
+    Manual(Vec<f64>),
+}
+
+impl ClassificationMethod {
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            ClassificationMethod::EqualInterval => "Equal interval",
+            ClassificationMethod::Quantile => "Quantile",
+            ClassificationMethod::NaturalBreaks => "Natural breaks (Jenks)",
+            ClassificationMethod::StandardDeviation => "Standard deviation",
+            ClassificationMethod::Manual(_) => "Manual",
+        }
+    }
+}
+
+/// Class bounds in ascending order, starting with the minimum value and ending with the maximum
+/// value. Class `i` spans `(bounds[i], bounds[i + 1]]`, except for the first class which also
+/// includes its lower bound.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClassBreaks(Vec<f64>);
+
+impl ClassBreaks {
+    pub fn bounds(&self) -> &[f64] {
+        &self.0
+    }
+
+    pub fn num_classes(&self) -> usize {
+        self.0.len().saturating_sub(1)
+    }
+
+    /// `(lower, upper)` bounds of every class.
+    pub fn ranges(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.0.windows(2).filter_map(|pair| match *pair {
+            [lower, upper] => Some((lower, upper)),
+            _ => None,
+        })
+    }
+
+    /// `None` if the value falls outside of every class.
+    pub fn class_index(&self, value: f64) -> Option<usize> {
+        let (first, last) = (*self.0.first()?, *self.0.last()?);
+        if !(first..=last).contains(&value) {
+            return None;
+        }
+        let index = self.0.partition_point(|bound| *bound < value);
+        Some(
+            index
+                .saturating_sub(1)
+                .min(self.num_classes().saturating_sub(1)),
+        )
+    }
+}
+
+/// Compute class breaks for `values`. Non-finite values are ignored. `num_classes` is ignored by
+/// `ClassificationMethod::Manual`. Fewer classes than requested may be returned when the values
+/// don't have enough distinct breaks.
+pub fn classify(
+    method: &ClassificationMethod,
+    values: &[f64],
+    num_classes: usize,
+) -> Option<ClassBreaks> {
+    let mut sorted = values
+        .iter()
+        .copied()
+        .filter(|n| n.is_finite())
+        .collect::<Vec<_>>();
+    sorted.sort_unstable_by(f64::total_cmp);
+    let (min, max) = (*sorted.first()?, *sorted.last()?);
+    let num_classes = num_classes.max(1);
+
+    let inner_breaks = match method {
+        ClassificationMethod::EqualInterval => equal_interval(min, max, num_classes),
+        ClassificationMethod::Quantile => quantile(&sorted, num_classes),
+        ClassificationMethod::NaturalBreaks => natural_breaks(&sorted, num_classes),
+        ClassificationMethod::StandardDeviation => standard_deviation(&sorted, num_classes),
+        ClassificationMethod::Manual(breaks) => breaks.clone(),
+    };
+
+    let mut bounds = vec![min];
+    bounds.extend(
+        inner_breaks
+            .into_iter()
+            .filter(|n| n.is_finite() && *n > min && *n < max),
+    );
+    bounds.push(max);
+    bounds.sort_unstable_by(f64::total_cmp);
+    bounds.dedup();
+    if bounds.len() == 1 {
+        // Every value is the same, so use a single class that only contains that value.
+        bounds.push(max);
+    }
+    Some(ClassBreaks(bounds))
+}
+
+fn equal_interval(min: f64, max: f64, num_classes: usize) -> Vec<f64> {
+    let interval = (max - min) / num_classes as f64;
+    (1..num_classes)
+        .map(|i| min + interval * i as f64)
+        .collect()
+}
+
+// Linear interpolation between the closest ranks (type 7 in R's `quantile`).
+fn quantile(sorted: &[f64], num_classes: usize) -> Vec<f64> {
+    let n = sorted.len();
+    (1..num_classes)
+        .filter_map(|i| {
+            let a = (i as f64 / num_classes as f64) * (n - 1) as f64;
+            let index = a.floor() as usize;
+            let r = a - a.floor();
+            let lower = *sorted.get(index)?;
+            let upper = sorted.get(index + 1).copied().unwrap_or(lower);
+            Some((1. - r) * lower + r * upper)
+        })
+        .collect()
+}
+
+// Jenks natural breaks optimization (Fisher's exact dynamic programming approach), returning the
+// upper bound of every class but the last.
+#[allow(clippy::indexing_slicing)]
+fn natural_breaks(sorted: &[f64], num_classes: usize) -> Vec<f64> {
+    let sample = sample_evenly(sorted, MAX_NATURAL_BREAKS_VALUES);
+    let n = sample.len();
+    if num_classes >= n {
+        return sample;
+    }
+
+    // 1-indexed matrices: `lower_class_limits[l][j]` is the number of values in the first `j - 1`
+    // classes of an optimal `j`-class split of the first `l` values, and `variances[l][j]` is the
+    // total within-class sum of squared deviations of that split.
+    let mut lower_class_limits = vec![vec![0usize; num_classes + 1]; n + 1];
+    let mut variances = vec![vec![0f64; num_classes + 1]; n + 1];
+    for j in 1..=num_classes {
+        lower_class_limits[1][j] = 1;
+        for row in variances.iter_mut().skip(2) {
+            row[j] = f64::INFINITY;
+        }
+    }
+
+    for l in 2..=n {
+        let (mut sum, mut sum_squares, mut variance) = (0., 0., 0.);
+        for m in 1..=l {
+            let lower_class_limit = l - m + 1;
+            let value = sample[lower_class_limit - 1];
+            sum += value;
+            sum_squares += value * value;
+            variance = sum_squares - (sum * sum) / m as f64;
+            let previous = lower_class_limit - 1;
+            if previous != 0 {
+                for j in 2..=num_classes {
+                    let candidate = variance + variances[previous][j - 1];
+                    if variances[l][j] >= candidate {
+                        lower_class_limits[l][j] = previous;
+                        variances[l][j] = candidate;
+                    }
+                }
+            }
+        }
+        lower_class_limits[l][1] = 1;
+        variances[l][1] = variance;
+    }
+
+    let mut breaks = vec![0.; num_classes - 1];
+    let mut k = n;
+    for j in (2..=num_classes).rev() {
+        let limit = lower_class_limits[k][j];
+        breaks[j - 2] = sample[limit - 1];
+        k = limit;
+    }
+    breaks
+}
+
+// Breaks one standard deviation apart, centered on the mean. With an odd number of classes the
+// middle class straddles the mean.
+fn standard_deviation(sorted: &[f64], num_classes: usize) -> Vec<f64> {
+    let n = sorted.len() as f64;
+    let mean = sorted.iter().sum::<f64>() / n;
+    let std_dev = (sorted.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+    let half = num_classes as f64 / 2.;
+    (1..num_classes)
+        .map(|i| mean + (i as f64 - half) * std_dev)
+        .collect()
+}
+
+// Pick `max` values spread evenly across `sorted`, always including the first and last values.
+fn sample_evenly(sorted: &[f64], max: usize) -> Vec<f64> {
+    if sorted.len() <= max || max < 2 {
+        return sorted.to_vec();
+    }
+    let step = (sorted.len() - 1) as f64 / (max - 1) as f64;
+    (0..max)
+        .filter_map(|i| sorted.get((i as f64 * step).round() as usize).copied())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sample data from the jenkspy documentation, whose 3 natural breaks classes are
+    // [1.2, 2.3], (2.3, 5.0] and (5.0, 7.8].
+    const JENKS_SAMPLE: [f64; 12] = [1.3, 7.1, 7.3, 2.3, 3.9, 4.1, 7.8, 1.2, 4.3, 7.3, 5.0, 4.3];
+
+    fn assert_bounds(class_breaks: Option<ClassBreaks>, expected: &[f64]) {
+        let Some(class_breaks) = class_breaks else {
+            panic!("Expected bounds {expected:?}, got no class breaks");
+        };
+        let bounds = class_breaks.bounds();
+        assert_eq!(bounds.len(), expected.len(), "{bounds:?} != {expected:?}");
+        for (bound, expected_bound) in bounds.iter().zip(expected) {
+            assert!(
+                (bound - expected_bound).abs() < 1e-9,
+                "{bounds:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn equal_interval() {
+        assert_bounds(
+            classify(&ClassificationMethod::EqualInterval, &JENKS_SAMPLE, 3),
+            &[1.2, 3.4, 5.6, 7.8],
+        );
+    }
+
+    #[test]
+    fn quantile() {
+        // Same as R's `quantile(x, c(0.25, 0.5, 0.75))`
+        assert_bounds(
+            classify(&ClassificationMethod::Quantile, &JENKS_SAMPLE, 4),
+            &[1.2, 3.5, 4.3, 7.15, 7.8],
+        );
+    }
+
+    #[test]
+    fn natural_breaks() {
+        assert_bounds(
+            classify(&ClassificationMethod::NaturalBreaks, &JENKS_SAMPLE, 3),
+            &[1.2, 2.3, 5.0, 7.8],
+        );
+    }
+
+    #[test]
+    fn standard_deviation() {
+        // Population mean and standard deviation of the sample
+        let (mean, std_dev) = (4.658333333333333, 2.233255595661982);
+        assert_bounds(
+            classify(&ClassificationMethod::StandardDeviation, &JENKS_SAMPLE, 4),
+            &[1.2, mean - std_dev, mean, mean + std_dev, 7.8],
+        );
+    }
+
+    #[test]
+    fn manual() {
+        // Breaks outside of the values are dropped, and the order doesn't matter
+        assert_bounds(
+            classify(
+                &ClassificationMethod::Manual(vec![5., 100., 2.]),
+                &JENKS_SAMPLE,
+                10,
+            ),
+            &[1.2, 2., 5., 7.8],
+        );
+    }
+
+    #[test]
+    fn empty_values() {
+        for method in [
+            ClassificationMethod::EqualInterval,
+            ClassificationMethod::Quantile,
+            ClassificationMethod::NaturalBreaks,
+            ClassificationMethod::StandardDeviation,
+            ClassificationMethod::Manual(vec![1.]),
+        ] {
+            assert_eq!(classify(&method, &[], 5), None);
+        }
+    }
+
+    #[test]
+    fn identical_values() {
+        for method in [
+            ClassificationMethod::EqualInterval,
+            ClassificationMethod::Quantile,
+            ClassificationMethod::NaturalBreaks,
+            ClassificationMethod::StandardDeviation,
+        ] {
+            let class_breaks = classify(&method, &[3.; 10], 5);
+            assert_bounds(class_breaks.clone(), &[3., 3.]);
+            assert_eq!(class_breaks.and_then(|c| c.class_index(3.)), Some(0));
+        }
+    }
+
+    #[test]
+    fn fewer_values_than_classes() {
+        let values = [1., 2., 3.];
+        assert_bounds(
+            classify(&ClassificationMethod::NaturalBreaks, &values, 5),
+            &[1., 2., 3.],
+        );
+        for method in [
+            ClassificationMethod::EqualInterval,
+            ClassificationMethod::Quantile,
+            ClassificationMethod::NaturalBreaks,
+            ClassificationMethod::StandardDeviation,
+        ] {
+            let Some(class_breaks) = classify(&method, &values, 5) else {
+                panic!("No class breaks for {}", method.display_name());
+            };
+            assert!(class_breaks.num_classes() <= 5);
+            for value in values {
+                assert!(class_breaks.class_index(value).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn nan_values() {
+        let values = [f64::NAN, 0., f64::INFINITY, 10., f64::NEG_INFINITY];
+        assert_bounds(
+            classify(&ClassificationMethod::EqualInterval, &values, 2),
+            &[0., 5., 10.],
+        );
+        assert_bounds(
+            classify(&ClassificationMethod::NaturalBreaks, &values, 2),
+            &[0., 10.],
+        );
+        assert_eq!(
+            classify(&ClassificationMethod::Quantile, &[f64::NAN; 3], 2),
+            None
+        );
+    }
+
+    #[test]
+    fn class_index_on_bounds() {
+        let class_breaks = ClassBreaks(vec![0., 5., 10.]);
+        assert_eq!(class_breaks.class_index(0.), Some(0));
+        assert_eq!(class_breaks.class_index(5.), Some(0));
+        assert_eq!(class_breaks.class_index(5.000001), Some(1));
+        assert_eq!(class_breaks.class_index(10.), Some(1));
+        assert_eq!(class_breaks.class_index(-0.1), None);
+        assert_eq!(class_breaks.class_index(10.1), None);
+        assert_eq!(class_breaks.class_index(f64::NAN), None);
+    }
+}
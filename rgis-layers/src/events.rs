@@ -0,0 +1,5 @@
+use bevy::prelude::Event;
+
+/// Change the `Layer`'s style
+#[derive(Event)]
+pub struct UpdateLayerStyleEvent(pub rgis_layer_id::LayerId, pub crate::LayerStyle);
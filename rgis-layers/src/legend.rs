@@ -0,0 +1,117 @@
+use bevy::prelude::Color;
+
+/// What a layer looks like in a map legend, shared by every place the legend gets drawn.
+#[derive(Clone, Debug)]
+pub struct Legend {
+    pub symbol: LegendSymbol,
+    /// `None` when the layer has a single color, in which case its only entry is labelled with
+    /// the layer name.
+    pub title: Option<String>,
+    pub entries: Vec<LegendEntry>,
+    pub stroke: Color,
+}
+
+#[derive(Clone, Debug)]
+pub struct LegendEntry {
+    pub label: String,
+    pub fill: Option<Color>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LegendSymbol {
+    Point,
+    Line,
+    Polygon,
+}
+
+impl LegendSymbol {
+    pub fn from_geom_type(geom_type: geo_geom_type::GeomType) -> Self {
+        if geom_type
+            .intersects(geo_geom_type::GeomType::POINT | geo_geom_type::GeomType::MULTI_POINT)
+        {
+            LegendSymbol::Point
+        } else if geom_type.has_fill() {
+            LegendSymbol::Polygon
+        } else {
+            LegendSymbol::Line
+        }
+    }
+}
+
+impl crate::Layer {
+    pub fn legend(&self) -> Legend {
+        let (title, entries) = match self.style {
+            crate::LayerStyle::Single => (
+                None,
+                vec![LegendEntry {
+                    label: self.name.clone(),
+                    fill: self.color.fill,
+                }],
+            ),
+            crate::LayerStyle::Graduated(ref graduated) => {
+                let class_colors = graduated.class_colors();
+                let entries = if class_colors.is_empty() {
+                    // Continuous ramp, show its two ends.
+                    graduated
+                        .value_range()
+                        .map(|(min, max)| {
+                            [min, max]
+                                .into_iter()
+                                .map(|value| LegendEntry {
+                                    label: format_number(value),
+                                    fill: graduated.value_color(value),
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default()
+                } else {
+                    class_colors
+                        .into_iter()
+                        .map(|((lower, upper), fill)| LegendEntry {
+                            label: format!("{} – {}", format_number(lower), format_number(upper)),
+                            fill: Some(fill),
+                        })
+                        .collect()
+                };
+                (
+                    Some(format!("{} ({})", self.name, graduated.property)),
+                    entries,
+                )
+            }
+            crate::LayerStyle::Categorized(ref categorized) => {
+                let mut entries = categorized
+                    .categories
+                    .iter()
+                    .map(|category| LegendEntry {
+                        label: category.value.clone(),
+                        fill: Some(category.color),
+                    })
+                    .collect::<Vec<_>>();
+                if categorized.other_count > 0 {
+                    entries.push(LegendEntry {
+                        label: "Other".into(),
+                        fill: Some(categorized.other_color),
+                    });
+                }
+                (
+                    Some(format!("{} ({})", self.name, categorized.property)),
+                    entries,
+                )
+            }
+        };
+        Legend {
+            symbol: LegendSymbol::from_geom_type(self.geom_type),
+            title,
+            entries,
+            stroke: self.color.stroke,
+        }
+    }
+}
+
+fn format_number(n: f64) -> String {
+    if n.fract() == 0. {
+        format!("{n}")
+    } else {
+        format!("{n:.2}")
+    }
+}
@@ -10,8 +10,19 @@ use bevy::prelude::*;
 use geo::contains::Contains;
 use std::sync;
 
+mod classification;
+mod events;
+mod legend;
+mod style;
 mod systems;
 
+pub use classification::{classify, ClassBreaks, ClassificationMethod};
+pub use events::UpdateLayerStyleEvent;
+pub use legend::{Legend, LegendEntry, LegendSymbol};
+pub use style::{
+    CategorizedStyle, Category, Classification, ColorRamp, GraduatedStyle, LayerStyle,
+};
+
 #[derive(Copy, Clone, Debug)]
 pub struct LayerIndex(pub usize);
 
@@ -58,7 +69,7 @@ impl Layers {
     ) -> impl Iterator<Item = &Layer> {
         self.iter_top_to_bottom()
             .filter(move |layer| match layer.projected_feature_collection {
-                Some(ref projected) => projected.as_ref().contains(&coord),
+                Some(ref projected) => (**projected).as_ref().contains(&coord),
                 None => false,
             })
     }
@@ -146,13 +157,15 @@ impl Layers {
         rgis_layer_id::LayerId::new()
     }
 
-    fn add(
+    /// Adds a layer on top of the others. It's only rendered once its projected feature collection
+    /// is set.
+    pub fn add(
         &mut self,
+        layer_id: rgis_layer_id::LayerId,
         unprojected: geo_projected::Unprojected<geo_features::FeatureCollection>,
         name: String,
         source_crs_epsg_code: u16,
-    ) -> rgis_layer_id::LayerId {
-        let layer_id = self.next_layer_id();
+    ) {
         let geom_type = geo_geom_type::determine(unprojected.as_raw().geometry_iter());
         let layer = Layer {
             unprojected_feature_collection: unprojected,
@@ -168,6 +181,8 @@ impl Layers {
                     stroke: colorous_color_to_bevy_color(next_colorous_color()),
                 }
             },
+            style: LayerStyle::default(),
+            style_generation: 0,
             name,
             visible: true,
             id: layer_id,
@@ -175,7 +190,6 @@ impl Layers {
             geom_type,
         };
         self.data.push(layer);
-        layer_id
     }
 
     pub fn clear_projected(&mut self) {
@@ -198,9 +212,13 @@ pub struct LayerColor {
 #[derive(Clone, Debug)]
 pub struct Layer {
     pub unprojected_feature_collection: geo_projected::Unprojected<geo_features::FeatureCollection>,
+    /// Shared with jobs that read the features off the main thread, like the SVG export.
     pub projected_feature_collection:
-        Option<geo_projected::Projected<geo_features::FeatureCollection>>,
+        Option<sync::Arc<geo_projected::Projected<geo_features::FeatureCollection>>>,
     pub color: LayerColor,
+    pub style: LayerStyle,
+    /// Bumped whenever `style` changes, so meshes built for an older style can be dropped.
+    pub style_generation: u64,
     pub id: rgis_layer_id::LayerId,
     pub name: String,
     pub visible: bool,
@@ -213,6 +231,16 @@ impl Layer {
         self.projected_feature_collection.is_some()
     }
 
+    /// Append features to a layer that's still loading. The projected feature collection is left
+    /// as is, until the appended features get reprojected.
+    pub fn append_features(
+        &mut self,
+        unprojected: geo_projected::Unprojected<geo_features::FeatureCollection>,
+    ) {
+        self.geom_type |= geo_geom_type::determine(unprojected.as_raw().geometry_iter());
+        self.unprojected_feature_collection.0.append(unprojected.0);
+    }
+
     #[inline]
     pub fn get_projected_feature_collection_or_log(
         &self,
@@ -239,6 +267,11 @@ impl Layer {
             .features_iter()
             .find(|f| f.id() == feature_id)
     }
+
+    /// Fill color of a single feature under the layer's current style.
+    pub fn feature_fill_color(&self, feature: &geo_features::Feature) -> Option<Color> {
+        self.style.feature_fill_color(feature, self.color.fill)
+    }
 }
 
 fn colorous_color_to_bevy_color(colorous_color: colorous::Color) -> Color {
@@ -261,7 +294,8 @@ pub struct Plugin;
 
 impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Layers::new());
+        app.insert_resource(Layers::new())
+            .add_event::<UpdateLayerStyleEvent>();
         systems::configure(app);
     }
 }
@@ -0,0 +1,301 @@
+use crate::classification::{ClassBreaks, ClassificationMethod};
+use bevy::prelude::Color;
+
+#[derive(Clone, Debug, Default)]
+pub enum LayerStyle {
+    /// Every feature is painted with the layer's `LayerColor`.
+    #[default]
+    Single,
+    /// Each feature's fill is interpolated along a color ramp from a numeric property.
+    Graduated(GraduatedStyle),
+    /// Each distinct value of a string or boolean property gets its own color.
+    Categorized(CategorizedStyle),
+}
+
+impl LayerStyle {
+    pub fn is_single(&self) -> bool {
+        matches!(self, LayerStyle::Single)
+    }
+
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            LayerStyle::Single => "Single color",
+            LayerStyle::Graduated(_) => "Graduated",
+            LayerStyle::Categorized(_) => "Categorized",
+        }
+    }
+
+    /// Fill color of a single feature, falling back to `fill` for features the style has no color
+    /// for.
+    pub fn feature_fill_color(
+        &self,
+        feature: &geo_features::Feature,
+        fill: Option<Color>,
+    ) -> Option<Color> {
+        match self {
+            LayerStyle::Single => fill,
+            LayerStyle::Graduated(graduated) => graduated.color(feature).or(fill),
+            LayerStyle::Categorized(categorized) => categorized.color(feature).or(fill),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GraduatedStyle {
+    pub property: String,
+    pub ramp: ColorRamp,
+    /// When `None`, colors are interpolated continuously between the minimum and maximum values.
+    pub classification: Option<Classification>,
+    // Range of the property values across the layer, used to normalize each value.
+    min: f64,
+    max: f64,
+}
+
+impl GraduatedStyle {
+    pub fn new(
+        property: String,
+        ramp: ColorRamp,
+        classification: Option<(ClassificationMethod, usize)>,
+        feature_collection: &geo_features::FeatureCollection,
+    ) -> Self {
+        let values = feature_collection
+            .features
+            .iter()
+            .filter_map(|feature| feature.properties.get(&property)?.as_number())
+            .filter(|n| n.is_finite())
+            .collect::<Vec<_>>();
+        let (min, max) = values
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), n| {
+                (min.min(*n), max.max(*n))
+            });
+        let classification = classification.and_then(|(method, num_classes)| {
+            let breaks = crate::classification::classify(&method, &values, num_classes)?;
+            Some(Classification {
+                method,
+                num_classes,
+                breaks,
+            })
+        });
+        GraduatedStyle {
+            property,
+            ramp,
+            classification,
+            min,
+            max,
+        }
+    }
+
+    /// `None` if the feature has no numeric value for the property.
+    pub fn color(&self, feature: &geo_features::Feature) -> Option<Color> {
+        self.value_color(feature.properties.get(&self.property)?.as_number()?)
+    }
+
+    /// `None` if the value is not finite or falls outside of every class.
+    pub fn value_color(&self, value: f64) -> Option<Color> {
+        if !value.is_finite() {
+            return None;
+        }
+        let t = match self.classification {
+            Some(ref classification) => {
+                let breaks = &classification.breaks;
+                class_position(breaks.class_index(value)?, breaks.num_classes())
+            }
+            None if self.max > self.min => (value - self.min) / (self.max - self.min),
+            None => 0.,
+        };
+        Some(self.ramp_color(t))
+    }
+
+    /// Minimum and maximum values of the property, `None` if no feature has a numeric value.
+    pub fn value_range(&self) -> Option<(f64, f64)> {
+        (self.min <= self.max).then_some((self.min, self.max))
+    }
+
+    /// `(lower, upper)` bounds of every class along with its color. Empty when unclassified.
+    pub fn class_colors(&self) -> Vec<((f64, f64), Color)> {
+        let Some(ref classification) = self.classification else {
+            return vec![];
+        };
+        let num_classes = classification.breaks.num_classes();
+        classification
+            .breaks
+            .ranges()
+            .enumerate()
+            .map(|(i, range)| (range, self.ramp_color(class_position(i, num_classes))))
+            .collect()
+    }
+
+    fn ramp_color(&self, t: f64) -> Color {
+        crate::colorous_color_to_bevy_color(self.ramp.gradient().eval_continuous(t))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Classification {
+    pub method: ClassificationMethod,
+    /// Requested number of classes, `breaks` may contain fewer.
+    pub num_classes: usize,
+    pub breaks: ClassBreaks,
+}
+
+// Spread class colors across the full ramp.
+fn class_position(class_index: usize, num_classes: usize) -> f64 {
+    if num_classes > 1 {
+        class_index as f64 / (num_classes - 1) as f64
+    } else {
+        0.5
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CategorizedStyle {
+    pub property: String,
+    /// Categories with their own color, most common first.
+    pub categories: Vec<Category>,
+    /// Color of every value that didn't make it into `categories`.
+    pub other_color: Color,
+    /// Number of features lumped into "Other".
+    pub other_count: usize,
+    pub max_categories: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Category {
+    pub value: String,
+    pub count: usize,
+    pub color: Color,
+}
+
+impl CategorizedStyle {
+    pub const DEFAULT_MAX_CATEGORIES: usize = 10;
+
+    pub fn new(
+        property: String,
+        max_categories: usize,
+        feature_collection: &geo_features::FeatureCollection,
+    ) -> Self {
+        let mut counts = std::collections::HashMap::<&str, usize>::new();
+        for feature in &feature_collection.features {
+            if let Some(value) = feature
+                .properties
+                .get(&property)
+                .and_then(|value| value.as_category())
+            {
+                *counts.entry(value).or_default() += 1;
+            }
+        }
+        let mut counts = counts.into_iter().collect::<Vec<_>>();
+        // Ties are broken by value so the order (and colors) are stable.
+        counts.sort_unstable_by(|(a, a_count), (b, b_count)| {
+            b_count.cmp(a_count).then_with(|| a.cmp(b))
+        });
+        let other_count = counts.iter().skip(max_categories).map(|(_, n)| n).sum();
+        let categories = counts
+            .into_iter()
+            .take(max_categories)
+            .zip(crate::COLORS.iter().cycle())
+            .map(|((value, count), color)| Category {
+                value: value.to_owned(),
+                count,
+                color: crate::colorous_color_to_bevy_color(*color),
+            })
+            .collect();
+        CategorizedStyle {
+            property,
+            categories,
+            other_color: OTHER_CATEGORY_COLOR,
+            other_count,
+            max_categories,
+        }
+    }
+
+    /// Regroup the categories, keeping the colors the user already picked.
+    pub fn with_max_categories(
+        &self,
+        max_categories: usize,
+        feature_collection: &geo_features::FeatureCollection,
+    ) -> Self {
+        let mut style =
+            CategorizedStyle::new(self.property.clone(), max_categories, feature_collection);
+        for category in &mut style.categories {
+            if let Some(existing) = self.categories.iter().find(|c| c.value == category.value) {
+                category.color = existing.color;
+            }
+        }
+        style.other_color = self.other_color;
+        style
+    }
+
+    /// `None` if the feature has no string or boolean value for the property.
+    pub fn color(&self, feature: &geo_features::Feature) -> Option<Color> {
+        let value = feature.properties.get(&self.property)?.as_category()?;
+        Some(
+            self.categories
+                .iter()
+                .find(|category| category.value == value)
+                .map_or(self.other_color, |category| category.color),
+        )
+    }
+}
+
+const OTHER_CATEGORY_COLOR: Color = Color::rgb(0.7, 0.7, 0.7);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorRamp {
+    Blues,
+    Greens,
+    Greys,
+    Oranges,
+    Purples,
+    Reds,
+    YellowGreenBlue,
+    YellowOrangeRed,
+    Viridis,
+    Magma,
+}
+
+impl ColorRamp {
+    pub const ALL: [ColorRamp; 10] = [
+        ColorRamp::Blues,
+        ColorRamp::Greens,
+        ColorRamp::Greys,
+        ColorRamp::Oranges,
+        ColorRamp::Purples,
+        ColorRamp::Reds,
+        ColorRamp::YellowGreenBlue,
+        ColorRamp::YellowOrangeRed,
+        ColorRamp::Viridis,
+        ColorRamp::Magma,
+    ];
+
+    pub const fn gradient(self) -> colorous::Gradient {
+        match self {
+            ColorRamp::Blues => colorous::BLUES,
+            ColorRamp::Greens => colorous::GREENS,
+            ColorRamp::Greys => colorous::GREYS,
+            ColorRamp::Oranges => colorous::ORANGES,
+            ColorRamp::Purples => colorous::PURPLES,
+            ColorRamp::Reds => colorous::REDS,
+            ColorRamp::YellowGreenBlue => colorous::YELLOW_GREEN_BLUE,
+            ColorRamp::YellowOrangeRed => colorous::YELLOW_ORANGE_RED,
+            ColorRamp::Viridis => colorous::VIRIDIS,
+            ColorRamp::Magma => colorous::MAGMA,
+        }
+    }
+
+    pub const fn display_name(self) -> &'static str {
+        match self {
+            ColorRamp::Blues => "Blues",
+            ColorRamp::Greens => "Greens",
+            ColorRamp::Greys => "Greys",
+            ColorRamp::Oranges => "Oranges",
+            ColorRamp::Purples => "Purples",
+            ColorRamp::Reds => "Reds",
+            ColorRamp::YellowGreenBlue => "Yellow-Green-Blue",
+            ColorRamp::YellowOrangeRed => "Yellow-Orange-Red",
+            ColorRamp::Viridis => "Viridis",
+            ColorRamp::Magma => "Magma",
+        }
+    }
+}
@@ -48,6 +48,22 @@ fn handle_update_color_events(
     }
 }
 
+fn handle_update_style_events(
+    mut update_events: EventReader<crate::UpdateLayerStyleEvent>,
+    mut updated_events: EventWriter<rgis_events::LayerStyleUpdatedEvent>,
+    mut layers: ResMut<crate::Layers>,
+) {
+    for crate::UpdateLayerStyleEvent(layer_id, style) in update_events.read() {
+        let Some(layer) = layers.get_mut(*layer_id) else {
+            bevy::log::warn!("Could not find layer");
+            continue;
+        };
+        layer.style = style.clone();
+        layer.style_generation += 1;
+        updated_events.send(rgis_events::LayerStyleUpdatedEvent(*layer_id));
+    }
+}
+
 fn handle_delete_layer_events(
     mut delete_layer_event_reader: EventReader<rgis_events::DeleteLayerEvent>,
     mut despawn_meshes_event_writer: EventWriter<rgis_events::DespawnMeshesEvent>,
@@ -127,7 +143,9 @@ fn handle_create_layer_events(
     mut layers: ResMut<crate::Layers>,
 ) {
     for event in create_layer_events.drain() {
-        let layer_id = layers.add(
+        let layer_id = layers.next_layer_id();
+        layers.add(
+            layer_id,
             event.feature_collection,
             event.name,
             event.source_crs_epsg_code,
@@ -136,16 +154,58 @@ fn handle_create_layer_events(
     }
 }
 
+fn handle_streamed_features_events(
+    mut streamed_features_events: ResMut<
+        bevy::ecs::event::Events<rgis_events::StreamedFeaturesEvent>,
+    >,
+    mut layer_created_event_writer: EventWriter<rgis_events::LayerCreatedEvent>,
+    mut layer_features_appended_event_writer: EventWriter<rgis_events::LayerFeaturesAppendedEvent>,
+    mut layers: ResMut<crate::Layers>,
+) {
+    for event in streamed_features_events.drain() {
+        match event {
+            rgis_events::StreamedFeaturesEvent::Create {
+                layer_id,
+                feature_collection,
+                name,
+                source_crs_epsg_code,
+            } => {
+                layers.add(layer_id, feature_collection, name, source_crs_epsg_code);
+                layer_created_event_writer.send(rgis_events::LayerCreatedEvent(layer_id));
+            }
+            rgis_events::StreamedFeaturesEvent::Append {
+                layer_id,
+                feature_collection,
+            } => {
+                // The layer may have been deleted while it was loading
+                let Some(layer) = layers.get_mut(layer_id) else {
+                    continue;
+                };
+                let first_feature_index = layer.unprojected_feature_collection.0.features.len();
+                layer.append_features(feature_collection);
+                layer_features_appended_event_writer.send(
+                    rgis_events::LayerFeaturesAppendedEvent {
+                        layer_id,
+                        first_feature_index,
+                    },
+                );
+            }
+        }
+    }
+}
+
 pub fn configure(app: &mut App) {
     app.add_systems(
         Update,
         (
             handle_toggle_layer_visibility_events,
             handle_update_color_events,
+            handle_update_style_events,
             handle_move_layer_events,
             handle_delete_layer_events,
             handle_map_clicked_events,
             handle_create_layer_events,
+            handle_streamed_features_events,
         ),
     );
 }
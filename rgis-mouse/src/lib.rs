@@ -13,11 +13,17 @@ mod systems;
 #[derive(Clone, Resource)]
 pub struct MousePos(pub geo_projected::Projected<geo::Coord>);
 
+/// Where the mouse was pressed with the `DrawRect` tool, in screen and projected coordinates, until
+/// it's released.
+#[derive(Default, Resource)]
+pub struct RectDragStart(pub Option<(Vec2, geo_projected::Projected<geo::Coord>)>);
+
 pub struct Plugin;
 
 impl bevy::app::Plugin for Plugin {
     fn build(&self, app: &mut bevy::app::App) {
         systems::configure(app);
+        app.init_resource::<RectDragStart>();
         app.insert_resource(MousePos(geo_projected::Projected::new(geo::Coord {
             x: 0.,
             y: 0.,
@@ -96,7 +96,9 @@ fn mouse_motion_system(
     mouse_motion_event_reader.clear();
     let cursor_icon = match rgis_settings.current_tool {
         rgis_settings::Tool::Pan => bevy::window::CursorIcon::Grab,
-        rgis_settings::Tool::Query => bevy::window::CursorIcon::Crosshair,
+        rgis_settings::Tool::Query | rgis_settings::Tool::DrawRect => {
+            bevy::window::CursorIcon::Crosshair
+        }
     };
     set_cursor_icon(&mut window, &mut last_cursor_icon, cursor_icon);
 }
@@ -127,6 +129,41 @@ fn mouse_click_system(
     }
 }
 
+fn draw_rect_system(
+    mut map_rect_drawn_event_writer: bevy::ecs::event::EventWriter<rgis_events::MapRectDrawnEvent>,
+    mouse_button: Res<bevy::input::ButtonInput<bevy::input::mouse::MouseButton>>,
+    mut rgis_settings: ResMut<rgis_settings::RgisSettings>,
+    mouse_position: Res<crate::MousePos>,
+    mut rect_drag_start: ResMut<crate::RectDragStart>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut bevy_egui_ctx: bevy_egui::EguiContexts,
+) {
+    if rgis_settings.current_tool != rgis_settings::Tool::DrawRect {
+        return;
+    }
+    if mouse_button.just_pressed(bevy::input::mouse::MouseButton::Left) {
+        if bevy_egui_ctx.ctx_mut().is_pointer_over_area() {
+            return;
+        }
+        let Some(cursor_position) = windows
+            .get_single()
+            .ok()
+            .and_then(|window| window.cursor_position())
+        else {
+            return;
+        };
+        rect_drag_start.0 = Some((cursor_position, mouse_position.0));
+    } else if mouse_button.just_released(bevy::input::mouse::MouseButton::Left) {
+        let Some((_, start)) = rect_drag_start.0.take() else {
+            return;
+        };
+        map_rect_drawn_event_writer.send(rgis_events::MapRectDrawnEvent(geo_projected::Projected(
+            geo::Rect::new(start.0, mouse_position.0 .0),
+        )));
+        rgis_settings.current_tool = rgis_settings::Tool::Pan;
+    }
+}
+
 fn mouse_scroll_system(
     mut mouse_scroll_event_reader: bevy::ecs::event::EventReader<bevy::input::mouse::MouseWheel>,
     mut zoom_camera_events: bevy::ecs::event::EventWriter<rgis_events::ZoomCameraEvent>,
@@ -160,6 +197,7 @@ pub fn configure(app: &mut App) {
             mouse_scroll_system,
             mouse_click_system,
             mouse_motion_system,
+            draw_rect_system,
         ),
     );
 }
@@ -13,6 +13,10 @@ pub struct FetchedFile {
     pub name: String,
     pub bytes: bytes::Bytes,
     pub crs_epsg_code: u16,
+    /// Last segment of the URL path the file was fetched from, after any redirects.
+    pub file_name: Option<String>,
+    /// The `Content-Type` header of the response.
+    pub content_type: Option<String>,
 }
 
 pub struct NetworkFetchJob {
@@ -62,6 +66,17 @@ async fn build_request_future(
     ctx: bevy_jobs::Context,
 ) -> Result<FetchedFile, Error> {
     let response = reqwest::get(url).await?;
+    let file_name = response
+        .url()
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .filter(|segment| !segment.is_empty())
+        .map(String::from);
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from);
     let total_size = response.content_length().unwrap_or(0);
     let mut bytes_stream = response.bytes_stream();
     let mut bytes = Vec::<u8>::with_capacity(total_size as usize);
@@ -83,6 +98,8 @@ async fn build_request_future(
         bytes: bytes::Bytes::from(bytes),
         crs_epsg_code,
         name,
+        file_name,
+        content_type,
     })
 }
 
@@ -1,13 +1,24 @@
+use bevy::prelude::Color;
+
+pub struct StyledGeometry {
+    pub geometry: geo_projected::Projected<geo::Geometry>,
+    // Fill color for this geometry, falls back to the layer's fill color when `None`
+    pub fill: Option<Color>,
+}
+
 pub struct MeshBuildingJob {
     pub layer_id: rgis_layer_id::LayerId,
-    pub geometry: geo_projected::Projected<geo::Geometry>,
+    pub geometries: Vec<StyledGeometry>,
     pub is_selected: bool,
+    /// Style generation of the layer when the job was spawned
+    pub style_generation: u64,
 }
 
 pub struct MeshBuildingJobOutcome {
-    pub geometry_mesh: geo_bevy::GeometryMesh,
+    pub geometry_meshes: Vec<(geo_bevy::GeometryMesh, Option<Color>)>,
     pub layer_id: rgis_layer_id::LayerId,
     pub is_selected: bool,
+    pub style_generation: u64,
 }
 
 impl bevy_jobs::Job for MeshBuildingJob {
@@ -18,14 +29,22 @@ impl bevy_jobs::Job for MeshBuildingJob {
     }
 
     fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
-        let Some(geometry_mesh) = geo_bevy::geometry_to_mesh(self.geometry.as_raw()) else {
+        let geometry_meshes = self
+            .geometries
+            .iter()
+            .filter_map(|styled| {
+                geo_bevy::geometry_to_mesh(styled.geometry.as_raw()).map(|mesh| (mesh, styled.fill))
+            })
+            .collect::<Vec<_>>();
+        if geometry_meshes.is_empty() {
             return Box::pin(async move { None });
-        };
+        }
         Box::pin(async move {
             Some(MeshBuildingJobOutcome {
-                geometry_mesh,
+                geometry_meshes,
                 layer_id: self.layer_id,
                 is_selected: self.is_selected,
+                style_generation: self.style_generation,
             })
         })
     }
@@ -9,9 +9,11 @@
 use bevy::prelude::*;
 
 mod jobs;
+mod raster;
 mod systems;
 mod z_index;
 
+pub use raster::{render_png, RasterError, RasterOptions};
 use z_index::ZIndex;
 
 #[derive(Clone, Copy, Component, PartialEq, Eq)]
@@ -37,6 +39,7 @@ const SELECTED_COLOR: Color = Color::PINK;
 
 fn spawn_geometry_meshes(
     geometry_mesh: geo_bevy::GeometryMesh,
+    fill: Option<Color>,
     materials: &mut Assets<ColorMaterial>,
     layer: &rgis_layers::Layer,
     commands: &mut Commands,
@@ -78,7 +81,7 @@ fn spawn_geometry_meshes(
                     if is_selected {
                         SELECTED_COLOR
                     } else {
-                        layer.color.fill.unwrap()
+                        fill.unwrap()
                     },
                 );
                 entity_commands.insert(layer.id);
@@ -102,7 +105,7 @@ fn spawn_geometry_meshes(
                 if is_selected {
                     SELECTED_COLOR
                 } else {
-                    layer.color.fill.unwrap()
+                    fill.unwrap()
                 },
                 layer_index,
                 polygon_mesh.mesh,
@@ -0,0 +1,357 @@
+// Software rendering of the map, for when there's no window or GPU. Shapes are painted in the order
+// given by `ZIndex::calculate`, so the result stacks the same way the meshes spawned by this crate
+// do.
+
+use crate::{RenderEntityType, ZIndex};
+use bevy::prelude::Color;
+
+// Diameter of the point sprites, in pixels
+const POINT_SIZE: f32 = 5.;
+// Scale of the point fill relative to its stroke, like the sprites
+const POINT_FILL_SCALE: f32 = 0.7;
+const LINE_WIDTH: f32 = 1.;
+
+#[derive(thiserror::Error, Debug)]
+pub enum RasterError {
+    #[error("Invalid image size: {0}x{1}")]
+    InvalidSize(u32, u32),
+    #[error("{0}")]
+    Png(String),
+}
+
+pub struct RasterOptions {
+    pub view_extent: geo_projected::Projected<geo::Rect>,
+    /// Pixels
+    pub width: u32,
+    /// Pixels
+    pub height: u32,
+    /// Transparent when `None`.
+    pub background: Option<Color>,
+}
+
+struct Shape {
+    z_index: ZIndex,
+    path: tiny_skia::Path,
+    paint: Paint,
+}
+
+enum Paint {
+    Fill(Color),
+    Stroke(Color),
+}
+
+/// Renders the visible layers to a PNG image.
+pub fn render_png(
+    layers: &rgis_layers::Layers,
+    options: &RasterOptions,
+) -> Result<Vec<u8>, RasterError> {
+    let mut pixmap = tiny_skia::Pixmap::new(options.width, options.height)
+        .ok_or(RasterError::InvalidSize(options.width, options.height))?;
+    if let Some(background) = options.background {
+        pixmap.fill(to_skia_color(background));
+    }
+
+    let mut shapes = vec![];
+    for (index, layer) in layers.iter_bottom_to_top().enumerate() {
+        let Some(ref projected) = layer.projected_feature_collection else {
+            continue;
+        };
+        if !layer.visible {
+            continue;
+        }
+        let mut builder = ShapeBuilder {
+            options,
+            layer,
+            layer_index: rgis_layers::LayerIndex(index),
+            shapes: &mut shapes,
+        };
+        for feature in &projected.as_raw().features {
+            if let Some(ref geometry) = feature.geometry {
+                builder.push_geometry(geometry, layer.feature_fill_color(feature));
+            }
+        }
+    }
+    // Stable, so shapes with the same z-index keep the order of their features.
+    shapes.sort_by_key(|shape| shape.z_index.0);
+
+    for shape in &shapes {
+        let mut paint = tiny_skia::Paint {
+            anti_alias: true,
+            ..Default::default()
+        };
+        match shape.paint {
+            Paint::Fill(color) => {
+                paint.set_color(to_skia_color(color));
+                pixmap.fill_path(
+                    &shape.path,
+                    &paint,
+                    tiny_skia::FillRule::EvenOdd,
+                    tiny_skia::Transform::identity(),
+                    None,
+                );
+            }
+            Paint::Stroke(color) => {
+                paint.set_color(to_skia_color(color));
+                let stroke = tiny_skia::Stroke {
+                    width: LINE_WIDTH,
+                    ..Default::default()
+                };
+                pixmap.stroke_path(
+                    &shape.path,
+                    &paint,
+                    &stroke,
+                    tiny_skia::Transform::identity(),
+                    None,
+                );
+            }
+        }
+    }
+
+    pixmap
+        .encode_png()
+        .map_err(|e| RasterError::Png(e.to_string()))
+}
+
+struct ShapeBuilder<'a> {
+    options: &'a RasterOptions,
+    layer: &'a rgis_layers::Layer,
+    layer_index: rgis_layers::LayerIndex,
+    shapes: &'a mut Vec<Shape>,
+}
+
+impl<'a> ShapeBuilder<'a> {
+    fn push_geometry(&mut self, geometry: &geo::Geometry, fill: Option<Color>) {
+        match geometry {
+            geo::Geometry::Point(point) => self.push_point(point.0, fill),
+            geo::Geometry::MultiPoint(multi_point) => {
+                for point in multi_point {
+                    self.push_point(point.0, fill);
+                }
+            }
+            geo::Geometry::Line(line) => {
+                self.push_line_string(&geo::LineString::new(vec![line.start, line.end]))
+            }
+            geo::Geometry::LineString(line_string) => self.push_line_string(line_string),
+            geo::Geometry::MultiLineString(multi_line_string) => {
+                for line_string in multi_line_string {
+                    self.push_line_string(line_string);
+                }
+            }
+            geo::Geometry::Polygon(polygon) => self.push_polygon(polygon, fill),
+            geo::Geometry::MultiPolygon(multi_polygon) => {
+                for polygon in multi_polygon {
+                    self.push_polygon(polygon, fill);
+                }
+            }
+            geo::Geometry::Rect(rect) => self.push_polygon(&rect.to_polygon(), fill),
+            geo::Geometry::Triangle(triangle) => self.push_polygon(&triangle.to_polygon(), fill),
+            geo::Geometry::GeometryCollection(geometry_collection) => {
+                for geometry in geometry_collection {
+                    self.push_geometry(geometry, fill);
+                }
+            }
+        }
+    }
+
+    fn push_point(&mut self, coord: geo::Coord, fill: Option<Color>) {
+        let (x, y) = self.to_pixel(coord);
+        let stroke = tiny_skia::PathBuilder::from_circle(x, y, POINT_SIZE / 2.);
+        self.push(
+            stroke,
+            RenderEntityType::PointStroke,
+            Paint::Fill(self.layer.color.stroke),
+        );
+        if let Some(fill) = fill {
+            let fill_path =
+                tiny_skia::PathBuilder::from_circle(x, y, POINT_SIZE * POINT_FILL_SCALE / 2.);
+            self.push(fill_path, RenderEntityType::PointFill, Paint::Fill(fill));
+        }
+    }
+
+    fn push_line_string(&mut self, line_string: &geo::LineString) {
+        let mut path_builder = tiny_skia::PathBuilder::new();
+        self.add_line_string(&mut path_builder, line_string, false);
+        self.push(
+            path_builder.finish(),
+            RenderEntityType::LineString,
+            Paint::Stroke(self.layer.color.stroke),
+        );
+    }
+
+    // The fill and the borders are separate shapes, like the polygon meshes.
+    fn push_polygon(&mut self, polygon: &geo::Polygon, fill: Option<Color>) {
+        let mut path_builder = tiny_skia::PathBuilder::new();
+        for ring in std::iter::once(polygon.exterior()).chain(polygon.interiors()) {
+            self.add_line_string(&mut path_builder, ring, true);
+        }
+        let Some(path) = path_builder.finish() else {
+            return;
+        };
+        if let Some(fill) = fill {
+            self.push(
+                Some(path.clone()),
+                RenderEntityType::Polygon,
+                Paint::Fill(fill),
+            );
+        }
+        self.push(
+            Some(path),
+            RenderEntityType::LineString,
+            Paint::Stroke(self.layer.color.stroke),
+        );
+    }
+
+    fn add_line_string(
+        &self,
+        path_builder: &mut tiny_skia::PathBuilder,
+        line_string: &geo::LineString,
+        close: bool,
+    ) {
+        for (i, coord) in line_string.coords().enumerate() {
+            let (x, y) = self.to_pixel(*coord);
+            if i == 0 {
+                path_builder.move_to(x, y);
+            } else {
+                path_builder.line_to(x, y);
+            }
+        }
+        if close && !line_string.0.is_empty() {
+            path_builder.close();
+        }
+    }
+
+    fn push(&mut self, path: Option<tiny_skia::Path>, entity_type: RenderEntityType, paint: Paint) {
+        // `None` for empty geometries
+        let Some(path) = path else {
+            return;
+        };
+        self.shapes.push(Shape {
+            z_index: ZIndex::calculate(self.layer_index, entity_type),
+            path,
+            paint,
+        });
+    }
+
+    fn to_pixel(&self, coord: geo::Coord) -> (f32, f32) {
+        let view_extent = self.options.view_extent.0;
+        (
+            ((coord.x - view_extent.min().x) / view_extent.width() * f64::from(self.options.width))
+                as f32,
+            ((view_extent.max().y - coord.y) / view_extent.height()
+                * f64::from(self.options.height)) as f32,
+        )
+    }
+}
+
+fn to_skia_color(color: Color) -> tiny_skia::Color {
+    let [r, g, b, a] = color.as_rgba_u8();
+    tiny_skia::Color::from_rgba8(r, g, b, a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error;
+
+    // A polygon layer under a layer holding a point and a smaller polygon, 1 map unit per pixel.
+    fn layers() -> rgis_layers::Layers {
+        let square = |min: f64, max: f64| {
+            geo::Geometry::Polygon(geo::Rect::new((min, min), (max, max)).to_polygon())
+        };
+        let kind = |kind: &str| {
+            geo_features::Properties::from([(
+                "kind".to_owned(),
+                geo_features::Value::String(kind.into()),
+            )])
+        };
+        let bottom = geo_features::FeatureCollection::from_features(vec![
+            geo_features::FeatureBuilder::new()
+                .with_geometry(square(10., 90.))
+                .build(),
+        ]);
+        // The point comes first, so it's only drawn above the polygon if the shapes get sorted
+        let top = geo_features::FeatureCollection::from_features(vec![
+            geo_features::FeatureBuilder::new()
+                .with_geometry(geo::Geometry::Point(geo::point!(x: 50.5, y: 49.5)))
+                .with_properties(kind("point"))
+                .build(),
+            geo_features::FeatureBuilder::new()
+                .with_geometry(square(40., 60.))
+                .with_properties(kind("square"))
+                .build(),
+        ]);
+
+        let mut layers = rgis_layers::Layers::new();
+        add_layer(&mut layers, bottom, Color::RED);
+        let top_layer_id = add_layer(&mut layers, top, Color::BLUE);
+        if let Some(layer) = layers.get_mut(top_layer_id) {
+            let mut style = rgis_layers::CategorizedStyle::new(
+                "kind".into(),
+                rgis_layers::CategorizedStyle::DEFAULT_MAX_CATEGORIES,
+                layer.unprojected_feature_collection.as_raw(),
+            );
+            for category in &mut style.categories {
+                category.color = match category.value.as_str() {
+                    "point" => Color::BLUE,
+                    _ => Color::GREEN,
+                };
+            }
+            layer.style = rgis_layers::LayerStyle::Categorized(style);
+        }
+        layers
+    }
+
+    fn add_layer(
+        layers: &mut rgis_layers::Layers,
+        feature_collection: geo_features::FeatureCollection,
+        color: Color,
+    ) -> rgis_layer_id::LayerId {
+        let layer_id = rgis_layer_id::LayerId::new();
+        layers.add(
+            layer_id,
+            geo_projected::Unprojected::new(feature_collection.clone()),
+            String::new(),
+            3857,
+        );
+        if let Some(layer) = layers.get_mut(layer_id) {
+            // Already in the target CRS
+            layer.projected_feature_collection = Some(std::sync::Arc::new(
+                geo_projected::Projected::new(feature_collection),
+            ));
+            layer.color = rgis_layers::LayerColor {
+                fill: Some(color),
+                stroke: color,
+            };
+        }
+        layer_id
+    }
+
+    #[test]
+    fn overlapping_layers() -> Result<(), Box<dyn error::Error>> {
+        let png = render_png(
+            &layers(),
+            &RasterOptions {
+                view_extent: geo_projected::Projected::new(geo::Rect::new((0., 0.), (100., 100.))),
+                width: 100,
+                height: 100,
+                background: Some(Color::BLACK),
+            },
+        )?;
+        let pixmap = tiny_skia::Pixmap::decode_png(&png)?;
+        let pixel = |x, y| {
+            pixmap
+                .pixel(x, y)
+                .map(|pixel| [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()])
+        };
+
+        // Background
+        assert_eq!(pixel(5, 5), Some([0, 0, 0, 255]));
+        // Bottom layer
+        assert_eq!(pixel(20, 20), Some([255, 0, 0, 255]));
+        // Top layer polygon, colored by its category
+        assert_eq!(pixel(45, 45), Some([0, 255, 0, 255]));
+        // Top layer point, above the polygon of its own layer
+        assert_eq!(pixel(50, 50), Some([0, 0, 255, 255]));
+        Ok(())
+    }
+}
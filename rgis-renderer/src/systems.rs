@@ -1,6 +1,41 @@
 use bevy::prelude::*;
 
-use crate::{jobs::MeshBuildingJob, RenderEntityType};
+use crate::{
+    jobs::{MeshBuildingJob, StyledGeometry},
+    RenderEntityType,
+};
+
+fn build_mesh_building_job<'a>(
+    layer: &rgis_layers::Layer,
+    features: impl Iterator<Item = geo_projected::Projected<&'a geo_features::Feature>>,
+) -> MeshBuildingJob {
+    let geometries = if layer.style.is_single() {
+        // Every feature shares the same color, so build one mesh for the whole layer.
+        vec![StyledGeometry {
+            geometry: geo_projected::Projected(geo::Geometry::GeometryCollection(
+                features
+                    .filter_map(|feature| feature.0.geometry.clone())
+                    .collect(),
+            )),
+            fill: None,
+        }]
+    } else {
+        features
+            .filter_map(|feature| {
+                Some(StyledGeometry {
+                    geometry: feature.geometry()?.cloned(),
+                    fill: layer.feature_fill_color(feature.0),
+                })
+            })
+            .collect()
+    };
+    MeshBuildingJob {
+        layer_id: layer.id,
+        geometries,
+        is_selected: false,
+        style_generation: layer.style_generation,
+    }
+}
 
 fn layer_loaded(
     layers: Res<rgis_layers::Layers>,
@@ -12,11 +47,73 @@ fn layer_loaded(
             continue;
         };
 
-        job_spawner.spawn(MeshBuildingJob {
-            layer_id: layer.id,
-            geometry: feature_collection.to_geometry_collection_geometry(),
-            is_selected: false,
-        })
+        job_spawner.spawn(build_mesh_building_job(
+            layer,
+            feature_collection.features_iter(),
+        ))
+    }
+}
+
+// Only build meshes for the appended features, the layer's other meshes are already spawned.
+fn handle_appended_features_reprojected_events(
+    layers: Res<rgis_layers::Layers>,
+    mut event_reader: EventReader<rgis_events::AppendedFeaturesReprojectedEvent>,
+    mut job_spawner: bevy_jobs::JobSpawner,
+) {
+    for event in event_reader.read() {
+        let Some(layer) = layers.get(event.layer_id) else {
+            continue;
+        };
+        let Some(feature_collection) = layer.projected_feature_collection.as_ref() else {
+            continue;
+        };
+
+        job_spawner.spawn(build_mesh_building_job(
+            layer,
+            feature_collection
+                .features_iter()
+                .skip(event.first_feature_index),
+        ))
+    }
+}
+
+type LayerEntitiesWithTypeQuery<'world, 'state, 'a> = Query<
+    'world,
+    'state,
+    (&'a rgis_layer_id::LayerId, Entity, &'a RenderEntityType),
+    Or<(With<Handle<ColorMaterial>>, With<Handle<Image>>)>,
+>;
+
+fn handle_layer_style_updated_event(
+    mut event_reader: EventReader<rgis_events::LayerStyleUpdatedEvent>,
+    layers: Res<rgis_layers::Layers>,
+    query: LayerEntitiesWithTypeQuery,
+    mut commands: Commands,
+    mut job_spawner: bevy_jobs::JobSpawner,
+) {
+    for layer in event_reader.read().flat_map(|event| layers.get(event.0)) {
+        let Some(feature_collection) = layer.projected_feature_collection.as_ref() else {
+            continue;
+        };
+
+        // Rebuild the layer's meshes with the new colors, but leave any selection highlight alone.
+        for (_, entity, _) in query.iter().filter(|(i, _, entity_type)| {
+            **i == layer.id
+                && matches!(
+                    entity_type,
+                    RenderEntityType::Polygon
+                        | RenderEntityType::LineString
+                        | RenderEntityType::PointFill
+                        | RenderEntityType::PointStroke
+                )
+        }) {
+            commands.entity(entity).despawn();
+        }
+
+        job_spawner.spawn(build_mesh_building_job(
+            layer,
+            feature_collection.features_iter(),
+        ))
     }
 }
 
@@ -31,9 +128,10 @@ fn handle_mesh_building_job_outcome(
 ) {
     while let Some(outcome) = finished_jobs.take_next::<MeshBuildingJob>() {
         let Some(crate::jobs::MeshBuildingJobOutcome {
-            geometry_mesh,
+            geometry_meshes,
             layer_id,
             is_selected,
+            style_generation,
         }) = outcome
         else {
             continue;
@@ -41,17 +139,25 @@ fn handle_mesh_building_job_outcome(
         let Some((layer, layer_index)) = layers.get_with_index(layer_id) else {
             continue;
         };
+        // The layer was restyled while the meshes were being built, and a job for the new style is
+        // on its way. Selection highlights don't depend on the style.
+        if !is_selected && style_generation != layer.style_generation {
+            continue;
+        }
 
-        crate::spawn_geometry_meshes(
-            geometry_mesh,
-            &mut materials,
-            layer,
-            &mut commands,
-            &mut assets_meshes,
-            layer_index,
-            &asset_server,
-            is_selected,
-        );
+        for (geometry_mesh, fill) in geometry_meshes {
+            crate::spawn_geometry_meshes(
+                geometry_mesh,
+                fill.or(layer.color.fill),
+                &mut materials,
+                layer,
+                &mut commands,
+                &mut assets_meshes,
+                layer_index,
+                &asset_server,
+                is_selected,
+            );
+        }
 
         meshes_spawned_event_writer.send(layer_id.into());
     }
@@ -138,6 +244,11 @@ fn handle_layer_color_updated_event(
             continue;
         };
 
+        if is_fill && !layer.style.is_single() {
+            // Fills are driven by the layer's style rather than its single fill color.
+            continue;
+        }
+
         if layer.geom_type == geo_geom_type::GeomType::POINT {
             let render_entity_type = if is_fill {
                 RenderEntityType::PointFill
@@ -252,8 +363,12 @@ fn handle_feature_selected_event_spawn(
         };
         job_spawner.spawn(MeshBuildingJob {
             layer_id: event.0,
-            geometry: geometry.cloned(),
+            geometries: vec![StyledGeometry {
+                geometry: geometry.cloned(),
+                fill: None,
+            }],
             is_selected: true,
+            style_generation: layer.style_generation,
         });
     }
 }
@@ -263,9 +378,11 @@ pub fn configure(app: &mut App) {
         Update,
         (
             layer_loaded,
+            handle_appended_features_reprojected_events,
             handle_layer_became_hidden_event,
             handle_layer_became_visible_event,
             handle_layer_color_updated_event,
+            handle_layer_style_updated_event,
             handle_layer_z_index_updated_event,
             handle_despawn_meshes_event,
             handle_mesh_building_job_outcome,
@@ -16,6 +16,8 @@ static DEFAULT_TARGET_CRS: u16 = 3857;
 pub enum Tool {
     Pan,
     Query,
+    /// Drag a rectangle on the map, after which the tool goes back to `Pan`
+    DrawRect,
 }
 
 #[derive(Resource)]
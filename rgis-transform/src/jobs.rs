@@ -3,12 +3,16 @@ pub struct ReprojectGeometryJob {
     pub layer_id: rgis_layer_id::LayerId,
     pub source_epsg_code: u16,
     pub target_epsg_code: u16,
+    /// When `None`, the whole layer is reprojected. Otherwise only the features appended to the
+    /// layer starting at this index are.
+    pub append_at: Option<usize>,
 }
 
 pub struct ReprojectGeometryJobOutcome {
     pub feature_collection: geo_projected::Projected<geo_features::FeatureCollection>,
     pub layer_id: rgis_layer_id::LayerId,
     pub target_crs_epsg_code: u16,
+    pub append_at: Option<usize>,
 }
 
 impl bevy_jobs::Job for ReprojectGeometryJob {
@@ -44,6 +48,7 @@ impl bevy_jobs::Job for ReprojectGeometryJob {
                 feature_collection: self.feature_collection.into_projected(),
                 layer_id: self.layer_id,
                 target_crs_epsg_code: self.target_epsg_code,
+                append_at: self.append_at,
             })
         })
     }
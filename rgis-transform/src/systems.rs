@@ -1,4 +1,5 @@
 use bevy::prelude::{App, Update};
+use std::sync;
 
 fn handle_layer_created_events(
     mut layer_created_event_reader: bevy::ecs::event::EventReader<rgis_events::LayerCreatedEvent>,
@@ -16,17 +17,69 @@ fn handle_layer_created_events(
             layer_id: event.0,
             source_epsg_code: layer.crs_epsg_code,
             target_epsg_code: rgis_settings.target_crs_epsg_code,
+            append_at: None,
         })
     }
 }
 
+fn handle_layer_features_appended_events(
+    mut layer_features_appended_event_reader: bevy::ecs::event::EventReader<
+        rgis_events::LayerFeaturesAppendedEvent,
+    >,
+    layers: bevy::ecs::system::Res<rgis_layers::Layers>,
+    rgis_settings: bevy::ecs::system::Res<rgis_settings::RgisSettings>,
+    mut job_spawner: bevy_jobs::JobSpawner,
+) {
+    for event in layer_features_appended_event_reader.read() {
+        let Some(layer) = layers.get(event.layer_id) else {
+            continue;
+        };
+        spawn_append_job(
+            &mut job_spawner,
+            layer,
+            event.first_feature_index,
+            rgis_settings.target_crs_epsg_code,
+        );
+    }
+}
+
+fn spawn_append_job(
+    job_spawner: &mut bevy_jobs::JobSpawner,
+    layer: &rgis_layers::Layer,
+    first_feature_index: usize,
+    target_epsg_code: u16,
+) {
+    let features = layer
+        .unprojected_feature_collection
+        .0
+        .features
+        .get(first_feature_index..)
+        .unwrap_or_default()
+        .to_vec();
+    job_spawner.spawn(crate::jobs::ReprojectGeometryJob {
+        feature_collection: geo_projected::Unprojected::new(
+            geo_features::FeatureCollection::from_features(features),
+        ),
+        layer_id: layer.id,
+        source_epsg_code: layer.crs_epsg_code,
+        target_epsg_code,
+        append_at: Some(first_feature_index),
+    })
+}
+
+#[derive(bevy::ecs::system::SystemParam)]
+struct ReprojectedEventWriters<'w> {
+    layer_reprojected: bevy::ecs::event::EventWriter<'w, rgis_events::LayerReprojectedEvent>,
+    appended_features_reprojected:
+        bevy::ecs::event::EventWriter<'w, rgis_events::AppendedFeaturesReprojectedEvent>,
+}
+
 fn handle_reproject_geometry_job_completion_events(
     mut finished_jobs: bevy_jobs::FinishedJobs,
     mut layers: bevy::ecs::system::ResMut<rgis_layers::Layers>,
-    mut layer_reprojected_event_writer: bevy::ecs::event::EventWriter<
-        rgis_events::LayerReprojectedEvent,
-    >,
+    mut event_writers: ReprojectedEventWriters,
     rgis_settings: bevy::ecs::system::Res<rgis_settings::RgisSettings>,
+    mut job_spawner: bevy_jobs::JobSpawner,
 ) {
     while let Some(outcome) = finished_jobs.take_next::<crate::jobs::ReprojectGeometryJob>() {
         let outcome = match outcome {
@@ -46,9 +99,48 @@ fn handle_reproject_geometry_job_completion_events(
             continue;
         };
 
-        layer.projected_feature_collection = Some(outcome.feature_collection);
-
-        layer_reprojected_event_writer.send(rgis_events::LayerReprojectedEvent(outcome.layer_id));
+        match outcome.append_at {
+            None => {
+                layer.projected_feature_collection =
+                    Some(sync::Arc::new(outcome.feature_collection));
+                event_writers
+                    .layer_reprojected
+                    .send(rgis_events::LayerReprojectedEvent(outcome.layer_id));
+                // Features may have been appended while the layer was being reprojected
+                let projected_count = layer
+                    .projected_feature_collection
+                    .as_ref()
+                    .map_or(0, |projected| projected.0.features.len());
+                if projected_count < layer.unprojected_feature_collection.0.features.len() {
+                    spawn_append_job(
+                        &mut job_spawner,
+                        layer,
+                        projected_count,
+                        outcome.target_crs_epsg_code,
+                    );
+                }
+            }
+            Some(first_feature_index) => {
+                // Skip the features if the whole layer is being reprojected, or if another job
+                // already covered them
+                let Some(projected) = layer.projected_feature_collection.as_mut() else {
+                    continue;
+                };
+                if projected.0.features.len() != first_feature_index {
+                    continue;
+                }
+                // Copied if a job is still reading the features
+                sync::Arc::make_mut(projected)
+                    .0
+                    .append(outcome.feature_collection.0);
+                event_writers.appended_features_reprojected.send(
+                    rgis_events::AppendedFeaturesReprojectedEvent {
+                        layer_id: outcome.layer_id,
+                        first_feature_index,
+                    },
+                );
+            }
+        }
     }
 }
 
@@ -67,6 +159,7 @@ fn handle_crs_changed_events(
                 layer_id: layer.id,
                 source_epsg_code: layer.crs_epsg_code,
                 target_epsg_code: rgis_settings.target_crs_epsg_code,
+                append_at: None,
             })
         }
     }
@@ -77,6 +170,7 @@ pub fn configure(app: &mut App) {
         Update,
         (
             handle_layer_created_events,
+            handle_layer_features_appended_events,
             handle_reproject_geometry_job_completion_events,
             handle_crs_changed_events,
         ),
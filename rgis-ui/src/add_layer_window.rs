@@ -13,7 +13,10 @@ pub struct Events<'w, 's> {
         bevy::ecs::system::ResMut<'w, bevy::ecs::event::Events<rgis_events::HideAddLayerWindow>>,
 }
 
-pub struct OpenFileJob;
+pub struct OpenFileJob {
+    /// The format chosen by the user, when `None` it's detected from the file.
+    pub file_format: Option<FileFormat>,
+}
 
 impl bevy_jobs::Job for OpenFileJob {
     type Outcome = Option<OpenedFile>;
@@ -25,15 +28,101 @@ impl bevy_jobs::Job for OpenFileJob {
 
     fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
         Box::pin(async move {
+            if self.file_format == Some(FileFormat::Shapefile) {
+                return open_shapefile().await;
+            }
             let task = rfd::AsyncFileDialog::new().pick_file();
             let file_handle = task.await?;
             let file_name = file_handle.file_name();
+            // FlatGeobuf files can be several GB, so they're left on disk and only the features
+            // that get loaded are read
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                let detected_format =
+                    geo_file_loader::detect_file_format(Some(&file_name), None, &[]);
+                if self.file_format.or(detected_format) == Some(FileFormat::FlatGeobuf) {
+                    let mut opened_file = OpenedFile::new(file_name, vec![]);
+                    opened_file.detected_format = detected_format;
+                    opened_file.path = Some(file_handle.path().to_owned());
+                    return Some(opened_file);
+                }
+            }
             let bytes = file_handle.read().await;
-            Some(OpenedFile { file_name, bytes })
+            let mut opened_file = OpenedFile::new(file_name, bytes);
+            opened_file.detected_format = geo_file_loader::detect_file_format(
+                Some(&opened_file.file_name),
+                None,
+                &opened_file.bytes,
+            );
+            let file_format = self.file_format.or(opened_file.detected_format);
+            let layers = match file_format {
+                Some(FileFormat::GeoPackage) => {
+                    geo_file_loader::geopackage_feature_tables(&opened_file.bytes)
+                }
+                Some(FileFormat::TopoJson) => geo_file_loader::topojson_objects(&opened_file.bytes),
+                _ => Ok(vec![]),
+            };
+            match layers {
+                // Select the first layer by default
+                Ok(layers) => {
+                    opened_file.layers = layers
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, layer)| (layer, i == 0))
+                        .collect()
+                }
+                Err(e) => {
+                    bevy::log::error!("Could not read the layers of the file: {:?}", e);
+                    return None;
+                }
+            }
+            if file_format == Some(FileFormat::GeoParquet) {
+                match geo_file_loader::geoparquet_columns(&opened_file.bytes) {
+                    // Select every column by default
+                    Ok(columns) => {
+                        opened_file.columns =
+                            columns.into_iter().map(|column| (column, true)).collect()
+                    }
+                    Err(e) => {
+                        bevy::log::error!("Could not read the columns of the file: {:?}", e);
+                        return None;
+                    }
+                }
+            }
+            Some(opened_file)
         })
     }
 }
 
+// Shapefiles are spread across several files (.shp, .dbf, .prj, …), so let the user select all of
+// them, or a single .zip bundle.
+async fn open_shapefile() -> Option<OpenedFile> {
+    let file_handles = rfd::AsyncFileDialog::new()
+        .add_filter("Shapefile", &["shp", "dbf", "prj", "cpg", "shx", "zip"])
+        .pick_files()
+        .await?;
+    let mut files = Vec::with_capacity(file_handles.len());
+    for file_handle in file_handles {
+        files.push((file_handle.file_name(), file_handle.read().await));
+    }
+    if let [(file_name, bytes)] = files.as_slice() {
+        return Some(OpenedFile::new(file_name.clone(), bytes.clone()));
+    }
+    let file_name = files
+        .iter()
+        .map(|(file_name, _)| file_name)
+        .find(|file_name| file_name.to_lowercase().ends_with(".shp"))
+        .or_else(|| files.first().map(|(file_name, _)| file_name))?
+        .clone();
+    match geo_file_loader::bundle_shapefile_files(files) {
+        Ok(bytes) => Some(OpenedFile::new(file_name, bytes)),
+        Err(e) => {
+            bevy::log::error!("Could not bundle shapefile files: {:?}", e);
+            None
+        }
+    }
+}
+
 pub(crate) struct AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
     pub state: &'a mut State,
     pub is_visible: &'a mut bool,
@@ -41,6 +130,8 @@ pub(crate) struct AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
     pub bevy_egui_ctx: &'a mut bevy_egui::EguiContext,
     pub job_spawner: &'a mut bevy_jobs::JobSpawner<'w1, 's1>,
     pub events: &'a mut Events<'w2, 's2>,
+    pub view_extent: Option<geo_projected::Projected<geo::Rect>>,
+    pub target_crs_epsg_code: u16,
 }
 
 #[derive(PartialEq, Eq)]
@@ -55,8 +146,14 @@ pub struct State {
     pub text_edit_contents: String,
     crs_input: String,
     selected_source: Source,
+    /// The format chosen by the user, when `None` it's detected from the file or text.
     selected_format: Option<FileFormat>,
     crs_input_outcome: Option<crate::widgets::crs_input::Outcome>,
+    csv_geometry_columns: Option<geo_file_loader::CsvGeometryColumns>,
+    osm_tag_filter: String,
+    only_load_view_extent: bool,
+    /// SRID of the EWKB input last filled into `crs_input`.
+    applied_wkb_srid: Option<u16>,
 }
 
 const DEFAULT_CRS_INPUT: &str = "4326";
@@ -69,6 +166,10 @@ impl Default for State {
             crs_input_outcome: None,
             selected_format: None,
             selected_source: Source::Unselected,
+            csv_geometry_columns: None,
+            osm_tag_filter: String::new(),
+            only_load_view_extent: false,
+            applied_wkb_srid: None,
         }
     }
 }
@@ -82,12 +183,60 @@ impl State {
         self.crs_input = DEFAULT_CRS_INPUT.into();
         self.selected_source = Source::Unselected;
         self.selected_format = None;
+        self.csv_geometry_columns = None;
+        self.osm_tag_filter = String::new();
+        self.only_load_view_extent = false;
+        self.applied_wkb_srid = None;
+    }
+
+    fn load_options(&self, file_format: FileFormat) -> geo_file_loader::LoadOptions {
+        geo_file_loader::LoadOptions {
+            csv_geometry_columns: match file_format {
+                FileFormat::Csv => self.csv_geometry_columns.clone(),
+                _ => None,
+            },
+            bbox: None,
+            layer: None,
+            columns: None,
+            osm_tag_filter: match file_format {
+                FileFormat::Osm => geo_file_loader::OsmTagFilter::parse(&self.osm_tag_filter),
+                _ => None,
+            },
+        }
     }
 }
 
 pub struct OpenedFile {
     bytes: Vec<u8>,
+    /// Set instead of `bytes` for files that are read from disk while loading.
+    path: Option<std::path::PathBuf>,
     file_name: String,
+    /// Format detected from the file name and contents.
+    detected_format: Option<FileFormat>,
+    /// Layers of a GeoPackage or TopoJSON file, and whether the user selected them for loading.
+    layers: Vec<(String, bool)>,
+    /// Attribute columns of a GeoParquet file, and whether the user selected them for loading.
+    columns: Vec<(String, bool)>,
+}
+
+impl OpenedFile {
+    fn new(file_name: String, bytes: Vec<u8>) -> Self {
+        OpenedFile {
+            bytes,
+            path: None,
+            file_name,
+            detected_format: None,
+            layers: vec![],
+            columns: vec![],
+        }
+    }
+
+    fn reader(&self) -> std::io::Result<Box<dyn std::io::Read + '_>> {
+        Ok(match self.path {
+            Some(ref path) => Box::new(std::io::BufReader::new(std::fs::File::open(path)?)),
+            None => Box::new(self.bytes.as_slice()),
+        })
+    }
 }
 
 impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
@@ -130,12 +279,65 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
                     return;
                 }
 
-                ui.label("Source CRS:");
-                let crs_input_widget = crate::widgets::CrsInput::new(
-                    &mut self.state.crs_input,
-                    &mut self.state.crs_input_outcome,
-                );
-                ui.add(crs_input_widget);
+                let detected_format = match self.state.selected_source {
+                    Source::File => self
+                        .selected_file
+                        .0
+                        .as_ref()
+                        .and_then(|file| file.detected_format),
+                    _ => geo_file_loader::detect_file_format(
+                        None,
+                        None,
+                        self.state.text_edit_contents.as_bytes(),
+                    )
+                    .filter(|format| format.is_plaintext()),
+                };
+                // The format chosen by the user overrides the detected one
+                let file_format = self.state.selected_format.or(detected_format);
+
+                // EWKB carries the SRID of its geometries
+                if file_format == Some(FileFormat::Wkb) {
+                    let bytes = match self.state.selected_source {
+                        Source::Text => Some(self.state.text_edit_contents.as_bytes()),
+                        _ => self.selected_file.0.as_ref().map(|file| file.bytes.as_slice()),
+                    };
+                    // Only once per SRID, so the user can still correct it
+                    if let Some(srid) = bytes.and_then(geo_file_loader::wkb_srid) {
+                        if self.state.applied_wkb_srid != Some(srid) {
+                            self.state.applied_wkb_srid = Some(srid);
+                            self.state.crs_input = srid.to_string();
+                            self.state.crs_input_outcome = None;
+                        }
+                    }
+                }
+
+                // OSM data is always WGS 84
+                if file_format != Some(FileFormat::Osm) {
+                    ui.label("Source CRS:");
+                    let crs_input_widget = crate::widgets::CrsInput::new(
+                        &mut self.state.crs_input,
+                        &mut self.state.crs_input_outcome,
+                    );
+                    ui.add(crs_input_widget);
+                }
+                match file_format {
+                    Some(FileFormat::Shapefile) => {
+                        ui.label("Used only if the CRS can't be determined from the .prj file.");
+                    }
+                    Some(FileFormat::FlatGeobuf) => {
+                        ui.label("Used only if the file header doesn't declare a CRS.");
+                    }
+                    Some(FileFormat::GeoPackage) => {
+                        ui.label("Used only if the CRS of the feature table can't be determined.");
+                    }
+                    Some(FileFormat::GeoParquet) => {
+                        ui.label("Used only if the CRS in the file metadata can't be determined.");
+                    }
+                    Some(FileFormat::Wkb) => {
+                        ui.label("Filled in from the SRID of EWKB geometries.");
+                    }
+                    _ => (),
+                }
 
                 ui.separator();
 
@@ -148,17 +350,43 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
                 if self.state.selected_source == Source::File
                     || self.state.selected_source == Source::Text
                 {
+                    ui.radio_value(&mut self.state.selected_format, None, "Auto-detect");
+
                     ui.radio_value(
                         &mut self.state.selected_format,
                         Some(FileFormat::GeoJson),
                         "GeoJSON",
                     );
 
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::GeoJsonSeq),
+                        "GeoJSONSeq",
+                    );
+
                     ui.radio_value(
                         &mut self.state.selected_format,
                         Some(FileFormat::Gpx),
                         "GPX",
                     );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::Kml),
+                        "KML/KMZ",
+                    );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::Osm),
+                        "OSM XML/PBF",
+                    );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::TopoJson),
+                        "TopoJSON",
+                    );
                 }
 
                 if self.state.selected_source == Source::File {
@@ -167,6 +395,26 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
                         Some(FileFormat::Shapefile),
                         "Shapefile",
                     );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::FlatGeobuf),
+                        "FlatGeobuf",
+                    );
+
+                    if cfg!(not(target_arch = "wasm32")) {
+                        ui.radio_value(
+                            &mut self.state.selected_format,
+                            Some(FileFormat::GeoPackage),
+                            "GeoPackage",
+                        );
+                    }
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::GeoParquet),
+                        "GeoParquet",
+                    );
                 }
 
                 if self.state.selected_source == Source::File
@@ -177,25 +425,102 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
                         Some(FileFormat::Wkt),
                         "WKT",
                     );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::Wkb),
+                        "WKB",
+                    );
+
+                    ui.radio_value(
+                        &mut self.state.selected_format,
+                        Some(FileFormat::Csv),
+                        "CSV",
+                    );
                 }
 
-                let Some(selected_format) = self.state.selected_format else {
-                    return;
+                let has_input = match self.state.selected_source {
+                    Source::File => self.selected_file.0.is_some(),
+                    _ => !self.state.text_edit_contents.is_empty(),
                 };
+                if self.state.selected_format.is_none() && has_input {
+                    match detected_format {
+                        Some(format) => {
+                            ui.label(format!("Detected format: {}", format.display_name()));
+                        }
+                        None => {
+                            ui.label("Could not detect the format, select it above.");
+                        }
+                    }
+                }
 
                 ui.separator();
 
+                if file_format == Some(FileFormat::Osm) {
+                    ui.label("Tag filter:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.state.osm_tag_filter)
+                            .hint_text("building=*, highway=primary"),
+                    );
+                    ui.label("Leave empty to load every tagged element.");
+                    ui.separator();
+                }
+
                 if self.state.selected_source == Source::File {
                     ui.label("Select file:");
 
                     if ui.button("📄 Select file").clicked() {
-                        self.job_spawner.spawn(OpenFileJob);
+                        self.job_spawner.spawn(OpenFileJob {
+                            file_format: self.state.selected_format,
+                        });
                     }
 
-                    let submittable = self.selected_file.0.is_some();
+                    let submittable = file_format.is_some_and(|file_format| {
+                        self.selected_file.0.as_ref().is_some_and(|file| {
+                            !has_layers(file_format)
+                                || file.layers.iter().any(|(_, selected)| *selected)
+                        })
+                    });
 
-                    if let Some(loaded_file) = &self.selected_file.0 {
+                    if let Some(loaded_file) = &mut self.selected_file.0 {
                         ui.label(format!("Selected file: {}", loaded_file.file_name));
+                        if file_format.is_some_and(has_layers) {
+                            if loaded_file.layers.is_empty() {
+                                ui.label("No layers found.");
+                            } else {
+                                ui.label("Layers:");
+                            }
+                            for (layer, selected) in &mut loaded_file.layers {
+                                ui.checkbox(selected, layer.as_str());
+                            }
+                        }
+                        if file_format == Some(FileFormat::Csv) {
+                            ui.add(CsvColumnsWidget {
+                                bytes: &loaded_file.bytes,
+                                geometry_columns: &mut self.state.csv_geometry_columns,
+                            });
+                        }
+                        if file_format == Some(FileFormat::GeoParquet) {
+                            ui.label("Columns:");
+                            egui::ScrollArea::vertical()
+                                .id_source("geoparquet_columns")
+                                .max_height(200.)
+                                .show(ui, |ui| {
+                                    for (column, selected) in &mut loaded_file.columns {
+                                        ui.checkbox(selected, column.as_str());
+                                    }
+                                });
+                        }
+                    }
+
+                    if file_format == Some(FileFormat::FlatGeobuf) {
+                        ui.add_enabled(
+                            self.view_extent.is_some(),
+                            egui::Checkbox::new(
+                                &mut self.state.only_load_view_extent,
+                                "Only load features in the current view",
+                            ),
+                        );
                     }
 
                     ui.separator();
@@ -204,21 +529,84 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
                         .add_enabled(submittable, egui::Button::new("Add layer"))
                         .clicked()
                     {
-                        let crs_epsg_code = match selected_format {
-                            FileFormat::GeoJson => 4326,
+                        let Some(file_format) = file_format else {
+                            return;
+                        };
+                        let crs_epsg_code = match file_format {
+                            FileFormat::GeoJson | FileFormat::Osm => 4326,
                             // TODO: don't allow the user to add a layer if the CRS isn't valid
                             _ => u16::from_str(&self.state.crs_input).unwrap(),
                         };
                         match self.selected_file.0.take() {
                             Some(loaded_file) => {
-                                self.events.load_file_event_writer.send(
-                                    rgis_events::LoadFileEvent::FromBytes {
-                                        file_name: loaded_file.file_name,
-                                        file_format: selected_format,
-                                        bytes: loaded_file.bytes.into(),
+                                let mut options = self.state.load_options(file_format);
+                                if file_format == FileFormat::GeoParquet {
+                                    options.columns = Some(
+                                        loaded_file
+                                            .columns
+                                            .iter()
+                                            .filter(|(_, selected)| *selected)
+                                            .map(|(column, _)| column.clone())
+                                            .collect(),
+                                    );
+                                }
+                                if let (FileFormat::FlatGeobuf, true, Some(view_extent)) = (
+                                    file_format,
+                                    self.state.only_load_view_extent,
+                                    self.view_extent,
+                                ) {
+                                    match flatgeobuf_view_bbox(
+                                        &loaded_file,
+                                        view_extent,
+                                        self.target_crs_epsg_code,
                                         crs_epsg_code,
-                                    },
-                                );
+                                    ) {
+                                        Ok(bbox) => options.bbox = Some(bbox),
+                                        Err(e) => {
+                                            bevy::log::error!(
+                                                "Could not determine the view extent in the CRS of the file: {:?}",
+                                                e
+                                            );
+                                            self.selected_file.0 = Some(loaded_file);
+                                            return;
+                                        }
+                                    }
+                                }
+                                if has_layers(file_format) {
+                                    let layers = loaded_file
+                                        .layers
+                                        .into_iter()
+                                        .filter(|(_, selected)| *selected)
+                                        .map(|(layer, _)| layer);
+                                    send_layer_load_events(
+                                        self.events,
+                                        file_format,
+                                        loaded_file.bytes.into(),
+                                        layers,
+                                        crs_epsg_code,
+                                        options,
+                                    );
+                                } else if let Some(path) = loaded_file.path {
+                                    self.events.load_file_event_writer.send(
+                                        rgis_events::LoadFileEvent::FromPath {
+                                            file_name: loaded_file.file_name,
+                                            file_format,
+                                            path,
+                                            crs_epsg_code,
+                                            options,
+                                        },
+                                    );
+                                } else {
+                                    self.events.load_file_event_writer.send(
+                                        rgis_events::LoadFileEvent::FromBytes {
+                                            file_name: loaded_file.file_name,
+                                            file_format,
+                                            bytes: loaded_file.bytes.into(),
+                                            crs_epsg_code,
+                                            options,
+                                        },
+                                    );
+                                }
                             }
                             None => {
                                 bevy::log::error!(
@@ -237,11 +625,19 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
                         .show(ui, |ui| {
                             egui::widgets::TextEdit::multiline(&mut self.state.text_edit_contents)
                                 .code_editor()
-                                .hint_text(hint_text(selected_format))
+                                .hint_text(file_format.map_or(AUTO_DETECT_HINT_TEXT, hint_text))
                                 .show(ui);
                         });
 
-                    let submittable = !self.state.text_edit_contents.is_empty();
+                    let submittable =
+                        !self.state.text_edit_contents.is_empty() && file_format.is_some();
+
+                    if submittable && file_format == Some(FileFormat::Csv) {
+                        ui.add(CsvColumnsWidget {
+                            bytes: self.state.text_edit_contents.as_bytes(),
+                            geometry_columns: &mut self.state.csv_geometry_columns,
+                        });
+                    }
 
                     ui.separator();
 
@@ -249,14 +645,45 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
                         .add_enabled(submittable, egui::Button::new("Add layer"))
                         .clicked()
                     {
+                        let Some(file_format) = file_format else {
+                            return;
+                        };
                         let new = mem::take(&mut self.state.text_edit_contents);
-                        match selected_format {
-                            FileFormat::Shapefile => {
+                        match file_format {
+                            FileFormat::Shapefile
+                            | FileFormat::FlatGeobuf
+                            | FileFormat::GeoPackage
+                            | FileFormat::GeoParquet => {
                                 unreachable!()
                             }
+                            // Every object in the topology becomes its own layer
+                            FileFormat::TopoJson => {
+                                match geo_file_loader::topojson_objects(new.as_bytes()) {
+                                    Ok(objects) => send_layer_load_events(
+                                        self.events,
+                                        FileFormat::TopoJson,
+                                        new.into(),
+                                        objects.into_iter(),
+                                        // TODO: don't allow the user to add a layer if the CRS isn't valid
+                                        u16::from_str(&self.state.crs_input).unwrap(),
+                                        self.state.load_options(file_format),
+                                    ),
+                                    Err(e) => {
+                                        bevy::log::error!(
+                                            "Could not read the objects of the TopoJSON: {:?}",
+                                            e
+                                        );
+                                    }
+                                }
+                            }
                             file_format @ (FileFormat::Wkt
+                            | FileFormat::Wkb
                             | FileFormat::GeoJson
-                            | FileFormat::Gpx) => {
+                            | FileFormat::GeoJsonSeq
+                            | FileFormat::Gpx
+                            | FileFormat::Kml
+                            | FileFormat::Osm
+                            | FileFormat::Csv) => {
                                 self.events.load_file_event_writer.send(
                                     rgis_events::LoadFileEvent::FromBytes {
                                         file_name: "Inputted file".into(),
@@ -265,6 +692,7 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
                                         // TODO: don't allow the user to add a layer if the CRS isn't valid
                                         crs_epsg_code: u16::from_str(&self.state.crs_input)
                                             .unwrap(),
+                                        options: self.state.load_options(file_format),
                                     },
                                 );
                             }
@@ -282,15 +710,170 @@ impl<'a, 'w1, 's1, 'w2, 's2> AddLayerWindow<'a, 'w1, 's1, 'w2, 's2> {
     }
 }
 
+// GeoPackage and TopoJSON files hold several layers, the user picks which ones to load.
+fn has_layers(file_format: FileFormat) -> bool {
+    matches!(file_format, FileFormat::GeoPackage | FileFormat::TopoJson)
+}
+
+// Load each of the layers in the file as its own layer, named after it.
+fn send_layer_load_events(
+    events: &mut Events,
+    file_format: FileFormat,
+    bytes: bytes::Bytes,
+    layers: impl Iterator<Item = String>,
+    crs_epsg_code: u16,
+    options: geo_file_loader::LoadOptions,
+) {
+    for layer in layers {
+        events
+            .load_file_event_writer
+            .send(rgis_events::LoadFileEvent::FromBytes {
+                file_name: layer.clone(),
+                file_format,
+                bytes: bytes.clone(),
+                crs_epsg_code,
+                options: geo_file_loader::LoadOptions {
+                    layer: Some(layer),
+                    ..options.clone()
+                },
+            });
+    }
+}
+
+const AUTO_DETECT_HINT_TEXT: &str = "Paste GeoJSON, TopoJSON, GPX, KML, WKT, WKB, or CSV";
+
 const fn hint_text(format: FileFormat) -> &'static str {
     match format {
         FileFormat::GeoJson => "{\n  \"type\": \"FeatureCollection\",\n  \"features\": []\n}",
+        FileFormat::GeoJsonSeq => "{\"type\": \"Feature\", \"geometry\": {\"type\": \"Point\", \"coordinates\": [2.35, 48.86]}, \"properties\": {}}\n{\"type\": \"Feature\", \"geometry\": {\"type\": \"Point\", \"coordinates\": [-0.13, 51.51]}, \"properties\": {}}",
         FileFormat::Shapefile => panic!("Shapefiles are not textual"),
+        FileFormat::FlatGeobuf => panic!("FlatGeobuf files are not textual"),
+        FileFormat::GeoPackage => panic!("GeoPackage files are not textual"),
+        FileFormat::GeoParquet => panic!("GeoParquet files are not textual"),
         FileFormat::Wkt => "LINESTRING (30 10, 10 30, 40 40)",
+        FileFormat::Wkb => "0101000020E6100000000000000000F03F0000000000000040",
         FileFormat::Gpx => "", // TODO: add example GPX
+        FileFormat::Kml => "<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n  <Placemark>\n    <name>Paris</name>\n    <Point><coordinates>2.35,48.86</coordinates></Point>\n  </Placemark>\n</kml>",
+        FileFormat::Osm => "<osm version=\"0.6\">\n  <node id=\"1\" lat=\"48.86\" lon=\"2.35\">\n    <tag k=\"name\" v=\"Paris\"/>\n  </node>\n</osm>",
+        FileFormat::TopoJson => "{\n  \"type\": \"Topology\",\n  \"objects\": {},\n  \"arcs\": []\n}",
+        FileFormat::Csv => "name,longitude,latitude\nParis,2.35,48.86",
+    }
+}
+
+// The view extent, transformed from the target CRS into the CRS of the FlatGeobuf file, so only the
+// features in view get loaded. The file header's CRS takes precedence over the one the user entered,
+// same as when loading.
+fn flatgeobuf_view_bbox(
+    file: &OpenedFile,
+    view_extent: geo_projected::Projected<geo::Rect>,
+    target_crs_epsg_code: u16,
+    source_crs_epsg_code: u16,
+) -> Result<geo::Rect, Box<dyn std::error::Error + Send + Sync>> {
+    use geo::{BoundingRect, Densify};
+
+    let source_crs_epsg_code =
+        geo_file_loader::flatgeobuf_crs_epsg_code(file.reader()?)?.unwrap_or(source_crs_epsg_code);
+    let view_extent = view_extent.0;
+    // Reprojecting only the corners would miss the bulges of the curved edges
+    let max_segment_length = view_extent.width().max(view_extent.height()) / 16.;
+    let mut geometry = geo::Geometry::Polygon(if max_segment_length > 0. {
+        view_extent.to_polygon().densify(max_segment_length)
+    } else {
+        view_extent.to_polygon()
+    });
+    transform::Transformer::setup(target_crs_epsg_code, source_crs_epsg_code)?
+        .transform(&mut geometry)?;
+    Ok(geometry
+        .bounding_rect()
+        .ok_or("View extent has no bounding rectangle")?)
+}
+
+// Lets the user pick which columns of a CSV file hold the geometry.
+struct CsvColumnsWidget<'a> {
+    bytes: &'a [u8],
+    geometry_columns: &'a mut Option<geo_file_loader::CsvGeometryColumns>,
+}
+
+impl<'a> egui::Widget for CsvColumnsWidget<'a> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        use geo_file_loader::CsvGeometryColumns;
+
+        let headers = match geo_file_loader::csv_headers(self.bytes) {
+            Ok(headers) => headers,
+            Err(e) => return ui.label(format!("Could not read CSV header: {e}")),
+        };
+        let has_column = |name: &String| headers.contains(name);
+        let is_valid = match self.geometry_columns {
+            Some(CsvGeometryColumns::Xy { ref x, ref y }) => has_column(x) && has_column(y),
+            Some(CsvGeometryColumns::Wkt(ref wkt)) => has_column(wkt),
+            None => false,
+        };
+        if !is_valid {
+            *self.geometry_columns =
+                CsvGeometryColumns::detect(&headers).or_else(|| default_xy_columns(&headers));
+        }
+
+        ui.vertical(|ui| {
+            ui.label("Geometry columns:");
+            let is_xy = matches!(self.geometry_columns, Some(CsvGeometryColumns::Xy { .. }));
+            ui.horizontal(|ui| {
+                if ui.radio(is_xy, "X/Y columns").clicked() && !is_xy {
+                    *self.geometry_columns = match CsvGeometryColumns::detect(&headers) {
+                        Some(xy @ CsvGeometryColumns::Xy { .. }) => Some(xy),
+                        _ => default_xy_columns(&headers),
+                    };
+                }
+                if ui.radio(!is_xy, "WKT column").clicked() && is_xy {
+                    *self.geometry_columns = match CsvGeometryColumns::detect(&headers) {
+                        Some(wkt @ CsvGeometryColumns::Wkt(_)) => Some(wkt),
+                        _ => headers.first().cloned().map(CsvGeometryColumns::Wkt),
+                    };
+                }
+            });
+            match self.geometry_columns {
+                Some(CsvGeometryColumns::Xy {
+                    ref mut x,
+                    ref mut y,
+                }) => {
+                    column_combo_box(ui, "csv_x_column", "X (longitude)", x, &headers);
+                    column_combo_box(ui, "csv_y_column", "Y (latitude)", y, &headers);
+                }
+                Some(CsvGeometryColumns::Wkt(ref mut wkt)) => {
+                    column_combo_box(ui, "csv_wkt_column", "WKT", wkt, &headers);
+                }
+                None => (),
+            }
+        })
+        .response
+    }
+}
+
+fn default_xy_columns(headers: &[String]) -> Option<geo_file_loader::CsvGeometryColumns> {
+    match headers {
+        [x, y, ..] => Some(geo_file_loader::CsvGeometryColumns::Xy {
+            x: x.clone(),
+            y: y.clone(),
+        }),
+        _ => None,
     }
 }
 
+fn column_combo_box(
+    ui: &mut egui::Ui,
+    id: &str,
+    label: &str,
+    selected: &mut String,
+    headers: &[String],
+) {
+    egui::ComboBox::from_id_source(id)
+        .selected_text(format!("{label}: {selected}"))
+        .show_ui(ui, |ui| {
+            for header in headers {
+                ui.selectable_value(selected, header.clone(), header);
+            }
+        });
+}
+
 struct LibraryWidget<'a, 'w, 's> {
     events: &'a mut Events<'w, 's>,
 }
@@ -4,4 +4,6 @@ use bevy::prelude::Event;
 pub struct OpenOperationWindowEvent {
     pub operation: Box<dyn Send + Sync + rgis_geo_ops::Operation>,
     pub feature_collection: geo_projected::Unprojected<geo_features::FeatureCollection>,
+    pub name: String,
+    pub crs_epsg_code: u16,
 }
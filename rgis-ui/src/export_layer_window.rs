@@ -0,0 +1,83 @@
+use bevy_egui::egui;
+
+pub(crate) struct ExportLayerWindow<'a, 'w> {
+    pub state: &'a mut crate::ExportLayerWindowState,
+    pub layers: &'a rgis_layers::Layers,
+    pub bevy_egui_ctx: &'a mut bevy_egui::EguiContext,
+    pub export_layer_event_writer:
+        &'a mut bevy::ecs::event::EventWriter<'w, rgis_events::ExportLayerEvent>,
+    pub target_crs_epsg_code: u16,
+}
+
+impl<'a, 'w> ExportLayerWindow<'a, 'w> {
+    pub(crate) fn render(&mut self) {
+        let (true, Some(layer_id)) = (self.state.is_visible, self.state.layer_id) else {
+            return;
+        };
+        let Some(layer) = self.layers.get(layer_id) else {
+            bevy::log::warn!(
+                "Could not find layer with ID {:?}, closing export layer window",
+                layer_id
+            );
+            self.state.is_visible = false;
+            return;
+        };
+        let mut should_close = false;
+        egui::Window::new("Save Layer As")
+            .open(&mut self.state.is_visible)
+            .show(self.bevy_egui_ctx.get_mut(), |ui| {
+                ui.label(format!("Layer: {}", layer.name));
+
+                ui.separator();
+
+                ui.label("Format:");
+                for file_format in geo_file_exporter::FileFormat::ALL {
+                    if cfg!(target_arch = "wasm32")
+                        && file_format == geo_file_exporter::FileFormat::GeoPackage
+                    {
+                        continue;
+                    }
+                    ui.radio_value(
+                        &mut self.state.file_format,
+                        file_format,
+                        file_format.display_name(),
+                    );
+                }
+
+                ui.separator();
+
+                ui.label("Coordinates:");
+                ui.radio_value(
+                    &mut self.state.projected,
+                    false,
+                    format!("Layer CRS (EPSG {})", layer.crs_epsg_code),
+                );
+                // Disabled while the layer is being reprojected
+                let map_crs_radio = ui.add_enabled(
+                    layer.projected_feature_collection.is_some(),
+                    egui::RadioButton::new(
+                        self.state.projected,
+                        format!("Map CRS (EPSG {})", self.target_crs_epsg_code),
+                    ),
+                );
+                if map_crs_radio.clicked() {
+                    self.state.projected = true;
+                }
+
+                ui.separator();
+
+                if ui.button("💾 Save").clicked() {
+                    self.export_layer_event_writer
+                        .send(rgis_events::ExportLayerEvent {
+                            layer_id,
+                            file_format: self.state.file_format,
+                            projected: self.state.projected,
+                        });
+                    should_close = true;
+                }
+            });
+        if should_close {
+            self.state.is_visible = false;
+        }
+    }
+}
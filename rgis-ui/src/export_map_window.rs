@@ -0,0 +1,48 @@
+use bevy_egui::egui;
+
+pub(crate) struct ExportMapWindow<'a, 'w> {
+    pub state: &'a mut crate::ExportMapWindowState,
+    pub bevy_egui_ctx: &'a mut bevy_egui::EguiContext,
+    pub export_map_svg_event_writer:
+        &'a mut bevy::ecs::event::EventWriter<'w, rgis_events::ExportMapSvgEvent>,
+    pub view_extent: Option<geo_projected::Projected<geo::Rect>>,
+    pub map_size: Option<rgis_units::ScreenSize>,
+}
+
+impl<'a, 'w> ExportMapWindow<'a, 'w> {
+    pub(crate) fn render(&mut self) {
+        let mut should_close = false;
+        egui::Window::new("Export Map as SVG")
+            .open(&mut self.state.is_visible)
+            .resizable(false)
+            .show(self.bevy_egui_ctx.get_mut(), |ui| {
+                ui.label("Exports the visible layers within the current map view.");
+
+                ui.separator();
+
+                ui.checkbox(&mut self.state.legend, "Legend");
+                ui.checkbox(&mut self.state.scale_bar, "Scale bar");
+
+                ui.separator();
+
+                let (Some(view_extent), Some(map_size)) = (self.view_extent, &self.map_size) else {
+                    ui.add_enabled(false, egui::Button::new("💾 Save"));
+                    return;
+                };
+                if ui.button("💾 Save").clicked() {
+                    self.export_map_svg_event_writer
+                        .send(rgis_events::ExportMapSvgEvent {
+                            view_extent,
+                            width: map_size.width,
+                            height: map_size.height,
+                            legend: self.state.legend,
+                            scale_bar: self.state.scale_bar,
+                        });
+                    should_close = true;
+                }
+            });
+        if should_close {
+            self.state.is_visible = false;
+        }
+    }
+}
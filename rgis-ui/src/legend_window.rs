@@ -0,0 +1,97 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_egui::egui;
+
+const SWATCH_SIZE: f32 = 14.;
+
+#[derive(SystemParam)]
+pub struct LegendWindow<'w, 's> {
+    layers: Res<'w, rgis_layers::Layers>,
+    #[system_param(ignore)]
+    marker: std::marker::PhantomData<&'s usize>,
+}
+
+impl<'w, 's> egui::Widget for LegendWindow<'w, 's> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        ui.vertical(|ui| {
+            let mut visible_layers = self
+                .layers
+                .iter_top_to_bottom()
+                .filter(|layer| layer.visible)
+                .peekable();
+            if visible_layers.peek().is_none() {
+                ui.label("No visible layers");
+            }
+            for layer in visible_layers {
+                ui.add(LayerLegend { layer });
+            }
+        })
+        .response
+    }
+}
+
+impl crate::Window for LegendWindow<'_, '_> {
+    type Item<'w, 's> = LegendWindow<'w, 's>;
+
+    fn title(&self) -> &str {
+        "Legend"
+    }
+
+    fn default_width(&self) -> f32 {
+        200.
+    }
+}
+
+struct LayerLegend<'a> {
+    layer: &'a rgis_layers::Layer,
+}
+
+impl<'a> egui::Widget for LayerLegend<'a> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let legend = self.layer.legend();
+
+        ui.vertical(|ui| {
+            if let Some(ref title) = legend.title {
+                ui.strong(title);
+            }
+            for entry in &legend.entries {
+                ui.horizontal(|ui| {
+                    paint_symbol(ui, legend.symbol, entry.fill, legend.stroke);
+                    ui.label(&entry.label);
+                });
+            }
+        })
+        .response
+    }
+}
+
+fn paint_symbol(
+    ui: &mut egui::Ui,
+    symbol: rgis_layers::LegendSymbol,
+    fill: Option<Color>,
+    stroke: Color,
+) {
+    let (rect, _) =
+        ui.allocate_exact_size(egui::vec2(SWATCH_SIZE, SWATCH_SIZE), egui::Sense::hover());
+    let painter = ui.painter();
+    let fill = fill.map_or(egui::Color32::TRANSPARENT, bevy_color_to_egui_color);
+    let stroke = egui::Stroke::new(1., bevy_color_to_egui_color(stroke));
+    match symbol {
+        rgis_layers::LegendSymbol::Point => {
+            painter.circle(rect.center(), SWATCH_SIZE / 3., fill, stroke);
+        }
+        rgis_layers::LegendSymbol::Line => {
+            painter.line_segment(
+                [rect.left_bottom(), rect.right_top()],
+                egui::Stroke::new(2., stroke.color),
+            );
+        }
+        rgis_layers::LegendSymbol::Polygon => {
+            painter.rect(rect.shrink(1.), 0., fill, stroke);
+        }
+    }
+}
+
+fn bevy_color_to_egui_color(color: Color) -> egui::Color32 {
+    let [r, g, b, a] = color.as_rgba_u8();
+    egui::Color32::from_rgba_unmultiplied(r, g, b, a)
+}
@@ -8,15 +8,19 @@
 
 use bevy::{ecs::system::SystemParam, prelude::*};
 use bevy_egui::egui;
-use std::marker;
+use std::{marker, sync};
 
 mod add_layer_window;
 mod bottom_panel;
 mod change_crs_window;
 mod debug_window;
 mod events;
+mod export_layer_window;
+mod export_map_window;
 mod feature_properties_window;
+mod legend_window;
 mod manage_layer_window;
+mod map_view;
 mod message_window;
 mod operation_window;
 mod side_panel;
@@ -61,6 +65,48 @@ pub struct MessageWindowState {
 pub struct ManageLayerWindowState {
     layer_id: Option<rgis_layer_id::LayerId>,
     is_visible: bool,
+    manual_breaks_text: String,
+    // Scanning every feature for them each frame is slow for big layers
+    property_names: Option<manage_layer_window::PropertyNames>,
+    // Value of the slider being dragged, applied once it's let go
+    dragged_slider_value: Option<usize>,
+    // Color being picked for a category, applied once the pointer is released
+    picked_category_color: Option<(manage_layer_window::CategoryColor, bevy::prelude::Color)>,
+}
+
+pub struct ExportLayerWindowState {
+    layer_id: Option<rgis_layer_id::LayerId>,
+    is_visible: bool,
+    file_format: geo_file_exporter::FileFormat,
+    projected: bool,
+}
+
+impl Default for ExportLayerWindowState {
+    fn default() -> Self {
+        ExportLayerWindowState {
+            layer_id: None,
+            is_visible: false,
+            file_format: geo_file_exporter::FileFormat::GeoJson,
+            projected: false,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct ExportMapWindowState {
+    is_visible: bool,
+    legend: bool,
+    scale_bar: bool,
+}
+
+impl Default for ExportMapWindowState {
+    fn default() -> Self {
+        ExportMapWindowState {
+            is_visible: false,
+            legend: true,
+            scale_bar: true,
+        }
+    }
 }
 
 #[derive(Default)]
@@ -73,7 +119,17 @@ pub struct FeaturePropertiesWindowState {
 struct OperationWindowState {
     is_visible: bool,
     operation: Option<Box<dyn Send + Sync + rgis_geo_ops::Operation>>,
-    feature_collection: geo_projected::Unprojected<geo_features::FeatureCollection>,
+    // Shared with preview jobs
+    feature_collection: sync::Arc<geo_projected::Unprojected<geo_features::FeatureCollection>>,
+    name: String,
+    crs_epsg_code: u16,
+    other_layer_id: Option<rgis_layer_id::LayerId>,
+    // Only the outcome of the last preview job is passed to the operation
+    preview_id: u64,
+    map_rect_source: operation_window::MapRectSource,
+    drawn_rect: Option<geo_projected::Projected<geo::Rect>>,
+    // Last one passed to the operation
+    map_rect: Option<geo_projected::Projected<geo::Rect>>,
 }
 
 impl bevy::app::Plugin for Plugin {
@@ -83,6 +139,7 @@ impl bevy::app::Plugin for Plugin {
             .insert_resource(TopPanelHeight(0.))
             .insert_resource(BottomPanelHeight(0.))
             .insert_resource(SidePanelWidth(0.))
+            .init_resource::<ExportMapWindowState>()
             .add_event::<events::OpenOperationWindowEvent>();
 
         systems::configure(app);
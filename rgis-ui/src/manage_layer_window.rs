@@ -5,6 +5,7 @@ pub(crate) struct ManageLayerWindow<'a> {
     pub layers: &'a rgis_layers::Layers,
     pub bevy_egui_ctx: &'a mut bevy_egui::EguiContext,
     pub color_events: &'a mut bevy::ecs::event::Events<rgis_events::UpdateLayerColorEvent>,
+    pub style_events: &'a mut bevy::ecs::event::Events<rgis_layers::UpdateLayerStyleEvent>,
 }
 
 impl<'a> ManageLayerWindow<'a> {
@@ -20,6 +21,14 @@ impl<'a> ManageLayerWindow<'a> {
             self.state.is_visible = false;
             return;
         };
+        let num_features = layer.unprojected_feature_collection.0.features.len();
+        if !matches!(
+            self.state.property_names,
+            Some(ref property_names)
+                if property_names.layer_id == layer_id && property_names.num_features == num_features
+        ) {
+            self.state.property_names = Some(PropertyNames::new(layer));
+        }
         egui::Window::new("Manage Layer")
             .open(&mut self.state.is_visible)
             .show(self.bevy_egui_ctx.get_mut(), |ui| {
@@ -33,7 +42,21 @@ impl<'a> ManageLayerWindow<'a> {
                         ui.label("CRS");
                         ui.label(&format!("EPSG {}", layer.crs_epsg_code));
                         ui.end_row();
-                        if layer.geom_type.has_fill() {
+                        if let (true, Some(property_names)) =
+                            (layer.geom_type.has_fill(), &self.state.property_names)
+                        {
+                            ui.label("Style");
+                            ui.add(StyleWidget {
+                                layer,
+                                property_names,
+                                manual_breaks_text: &mut self.state.manual_breaks_text,
+                                dragged_slider_value: &mut self.state.dragged_slider_value,
+                                picked_category_color: &mut self.state.picked_category_color,
+                                style_events: self.style_events,
+                            });
+                            ui.end_row();
+                        }
+                        if layer.geom_type.has_fill() && layer.style.is_single() {
                             if let Some(fill) = layer.color.fill {
                                 ui.label("Fill color");
                                 ui.add(FillColorWidget {
@@ -109,3 +132,396 @@ impl<'a> egui::Widget for FillColorWidget<'a> {
         response
     }
 }
+
+type ClassificationSpec = Option<(rgis_layers::ClassificationMethod, usize)>;
+
+const DEFAULT_NUM_CLASSES: usize = 5;
+
+const CLASSIFICATION_METHODS: [rgis_layers::ClassificationMethod; 4] = [
+    rgis_layers::ClassificationMethod::EqualInterval,
+    rgis_layers::ClassificationMethod::Quantile,
+    rgis_layers::ClassificationMethod::NaturalBreaks,
+    rgis_layers::ClassificationMethod::StandardDeviation,
+];
+
+/// Names of the properties of a layer that it can be styled by.
+pub(crate) struct PropertyNames {
+    layer_id: rgis_layer_id::LayerId,
+    // Features get appended while a layer loads, which can bring new properties
+    num_features: usize,
+    numeric: Vec<String>,
+    categorical: Vec<String>,
+}
+
+impl PropertyNames {
+    fn new(layer: &rgis_layers::Layer) -> Self {
+        let feature_collection = layer.unprojected_feature_collection.as_raw();
+        PropertyNames {
+            layer_id: layer.id,
+            num_features: feature_collection.features.len(),
+            numeric: feature_collection
+                .numeric_property_names()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            categorical: feature_collection
+                .categorical_property_names()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum CategoryColor {
+    Category(usize),
+    Other,
+}
+
+struct StyleWidget<'a> {
+    layer: &'a rgis_layers::Layer,
+    property_names: &'a PropertyNames,
+    manual_breaks_text: &'a mut String,
+    dragged_slider_value: &'a mut Option<usize>,
+    picked_category_color: &'a mut Option<(CategoryColor, bevy::prelude::Color)>,
+    style_events: &'a mut bevy::ecs::event::Events<rgis_layers::UpdateLayerStyleEvent>,
+}
+
+impl<'a> StyleWidget<'a> {
+    fn send(&mut self, style: rgis_layers::LayerStyle) {
+        self.style_events
+            .send(rgis_layers::UpdateLayerStyleEvent(self.layer.id, style));
+    }
+
+    fn send_graduated(
+        &mut self,
+        property: &str,
+        ramp: rgis_layers::ColorRamp,
+        classification: ClassificationSpec,
+    ) {
+        let style = rgis_layers::LayerStyle::Graduated(rgis_layers::GraduatedStyle::new(
+            property.to_owned(),
+            ramp,
+            classification,
+            self.layer.unprojected_feature_collection.as_raw(),
+        ));
+        self.send(style);
+    }
+
+    fn graduated_ui(&mut self, ui: &mut egui::Ui, graduated: &rgis_layers::GraduatedStyle) {
+        let (property, ramp) = (graduated.property.as_str(), graduated.ramp);
+        let classification = graduated
+            .classification
+            .as_ref()
+            .map(|classification| (classification.method.clone(), classification.num_classes));
+
+        egui::ComboBox::from_id_source(("layer_style_property", self.layer.id))
+            .selected_text(format!("Property: {property}"))
+            .show_ui(ui, |ui| {
+                for name in &self.property_names.numeric {
+                    if ui.selectable_label(name == property, name).clicked() && name != property {
+                        self.send_graduated(name, ramp, classification.clone());
+                    }
+                }
+            });
+
+        egui::ComboBox::from_id_source(("layer_style_ramp", self.layer.id))
+            .selected_text(format!("Ramp: {}", ramp.display_name()))
+            .show_ui(ui, |ui| {
+                for other in rgis_layers::ColorRamp::ALL {
+                    if ui
+                        .selectable_label(other == ramp, other.display_name())
+                        .clicked()
+                        && other != ramp
+                    {
+                        self.send_graduated(property, other, classification.clone());
+                    }
+                }
+            });
+
+        let method = classification.as_ref().map(|(method, _)| method);
+        egui::ComboBox::from_id_source(("layer_style_classification", self.layer.id))
+            .selected_text(format!(
+                "Classes: {}",
+                method.map_or("Continuous", |method| method.display_name())
+            ))
+            .show_ui(ui, |ui| {
+                if ui
+                    .selectable_label(method.is_none(), "Continuous")
+                    .clicked()
+                    && method.is_some()
+                {
+                    self.send_graduated(property, ramp, None);
+                }
+                let num_classes = classification
+                    .as_ref()
+                    .map_or(DEFAULT_NUM_CLASSES, |(_, num_classes)| *num_classes);
+                for other in CLASSIFICATION_METHODS {
+                    let is_selected = method == Some(&other);
+                    if ui
+                        .selectable_label(is_selected, other.display_name())
+                        .clicked()
+                        && !is_selected
+                    {
+                        self.send_graduated(property, ramp, Some((other, num_classes)));
+                    }
+                }
+                let is_manual =
+                    matches!(method, Some(rgis_layers::ClassificationMethod::Manual(_)));
+                if ui.selectable_label(is_manual, "Manual").clicked() && !is_manual {
+                    // Start from the current breaks so the user can tweak them.
+                    let breaks = graduated
+                        .classification
+                        .as_ref()
+                        .map(|classification| inner_bounds(&classification.breaks))
+                        .unwrap_or_default();
+                    *self.manual_breaks_text = format_breaks(&breaks);
+                    self.send_graduated(
+                        property,
+                        ramp,
+                        Some((
+                            rgis_layers::ClassificationMethod::Manual(breaks),
+                            num_classes,
+                        )),
+                    );
+                }
+            });
+
+        let Some((method, num_classes)) = classification else {
+            return;
+        };
+
+        if let rgis_layers::ClassificationMethod::Manual(_) = method {
+            ui.horizontal(|ui| {
+                ui.label("Breaks:");
+                ui.text_edit_singleline(self.manual_breaks_text);
+                let parsed = parse_breaks(self.manual_breaks_text);
+                if ui
+                    .add_enabled(parsed.is_some(), egui::Button::new("Apply"))
+                    .clicked()
+                {
+                    if let Some(breaks) = parsed {
+                        self.send_graduated(
+                            property,
+                            ramp,
+                            Some((
+                                rgis_layers::ClassificationMethod::Manual(breaks),
+                                num_classes,
+                            )),
+                        );
+                    }
+                }
+            });
+        } else {
+            let new_num_classes = released_slider(
+                ui,
+                self.dragged_slider_value,
+                num_classes,
+                2..=12,
+                "classes",
+            );
+            if let Some(new_num_classes) = new_num_classes.filter(|n| *n != num_classes) {
+                self.send_graduated(property, ramp, Some((method, new_num_classes)));
+            }
+        }
+    }
+}
+
+impl<'a> StyleWidget<'a> {
+    fn send_categorized(&mut self, property: &str) {
+        let style = rgis_layers::LayerStyle::Categorized(rgis_layers::CategorizedStyle::new(
+            property.to_owned(),
+            rgis_layers::CategorizedStyle::DEFAULT_MAX_CATEGORIES,
+            self.layer.unprojected_feature_collection.as_raw(),
+        ));
+        self.send(style);
+    }
+
+    fn categorized_ui(&mut self, ui: &mut egui::Ui, categorized: &rgis_layers::CategorizedStyle) {
+        let property = categorized.property.as_str();
+
+        egui::ComboBox::from_id_source(("layer_style_category_property", self.layer.id))
+            .selected_text(format!("Property: {property}"))
+            .show_ui(ui, |ui| {
+                for name in &self.property_names.categorical {
+                    if ui.selectable_label(name == property, name).clicked() && name != property {
+                        self.send_categorized(name);
+                    }
+                }
+            });
+
+        let max_categories = released_slider(
+            ui,
+            self.dragged_slider_value,
+            categorized.max_categories,
+            1..=30,
+            "categories",
+        );
+        if let Some(max_categories) = max_categories.filter(|n| *n != categorized.max_categories) {
+            let style = categorized.with_max_categories(
+                max_categories,
+                self.layer.unprojected_feature_collection.as_raw(),
+            );
+            self.send(rgis_layers::LayerStyle::Categorized(style));
+        }
+
+        egui::ScrollArea::vertical()
+            .max_height(200.)
+            .show(ui, |ui| {
+                for (i, category) in categorized.categories.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if let Some(color) = edit_color(
+                            ui,
+                            self.picked_category_color,
+                            CategoryColor::Category(i),
+                            category.color,
+                        ) {
+                            let mut style = categorized.clone();
+                            if let Some(category) = style.categories.get_mut(i) {
+                                category.color = color;
+                            }
+                            self.send(rgis_layers::LayerStyle::Categorized(style));
+                        }
+                        ui.label(format!("{} ({})", category.value, category.count));
+                    });
+                }
+                if categorized.other_count > 0 {
+                    ui.horizontal(|ui| {
+                        if let Some(color) = edit_color(
+                            ui,
+                            self.picked_category_color,
+                            CategoryColor::Other,
+                            categorized.other_color,
+                        ) {
+                            let mut style = categorized.clone();
+                            style.other_color = color;
+                            self.send(rgis_layers::LayerStyle::Categorized(style));
+                        }
+                        ui.label(format!("Other ({})", categorized.other_count));
+                    });
+                }
+            });
+    }
+}
+
+impl<'a> egui::Widget for StyleWidget<'a> {
+    fn ui(mut self, ui: &mut egui::Ui) -> egui::Response {
+        let property_names = self.property_names;
+        ui.vertical(|ui| {
+            egui::ComboBox::from_id_source(("layer_style", self.layer.id))
+                .selected_text(self.layer.style.display_name())
+                .show_ui(ui, |ui| {
+                    if ui
+                        .selectable_label(self.layer.style.is_single(), "Single color")
+                        .clicked()
+                        && !self.layer.style.is_single()
+                    {
+                        self.send(rgis_layers::LayerStyle::Single);
+                    }
+                    let is_graduated =
+                        matches!(self.layer.style, rgis_layers::LayerStyle::Graduated(_));
+                    if let Some(first_property) = property_names.numeric.first() {
+                        if ui.selectable_label(is_graduated, "Graduated").clicked() && !is_graduated
+                        {
+                            self.send_graduated(
+                                first_property,
+                                rgis_layers::ColorRamp::Blues,
+                                None,
+                            );
+                        }
+                    }
+                    let is_categorized =
+                        matches!(self.layer.style, rgis_layers::LayerStyle::Categorized(_));
+                    if let Some(first_property) = property_names.categorical.first() {
+                        if ui.selectable_label(is_categorized, "Categorized").clicked()
+                            && !is_categorized
+                        {
+                            self.send_categorized(first_property);
+                        }
+                    }
+                });
+
+            match self.layer.style {
+                rgis_layers::LayerStyle::Single => (),
+                rgis_layers::LayerStyle::Graduated(ref graduated) => {
+                    self.graduated_ui(ui, graduated);
+                }
+                rgis_layers::LayerStyle::Categorized(ref categorized) => {
+                    self.categorized_ui(ui, categorized);
+                }
+            }
+        })
+        .response
+    }
+}
+
+// Slider that returns the new value once the user lets go of it, rather than on every frame of the
+// drag. The value being dragged is kept in `dragged_value` in the meantime.
+fn released_slider(
+    ui: &mut egui::Ui,
+    dragged_value: &mut Option<usize>,
+    value: usize,
+    range: std::ops::RangeInclusive<usize>,
+    text: &str,
+) -> Option<usize> {
+    let mut new_value = dragged_value.unwrap_or(value);
+    let response = ui.add(egui::Slider::new(&mut new_value, range).text(text));
+    *dragged_value = response.dragged().then_some(new_value);
+    (response.drag_stopped() || (response.changed() && !response.dragged())).then_some(new_value)
+}
+
+// Color button that returns the new color once the user is done changing it. While they drag in
+// the color picker, the color is kept in `picked` instead.
+fn edit_color(
+    ui: &mut egui::Ui,
+    picked: &mut Option<(CategoryColor, bevy::prelude::Color)>,
+    target: CategoryColor,
+    color: bevy::prelude::Color,
+) -> Option<bevy::prelude::Color> {
+    let color = match *picked {
+        Some((picked_target, picked_color)) if picked_target == target => picked_color,
+        _ => color,
+    };
+    let mut rgba = color.as_linear_rgba_f32();
+    if ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed() {
+        let [r, g, b, a] = rgba;
+        *picked = Some((target, bevy::prelude::Color::rgba_linear(r, g, b, a)));
+    }
+    match *picked {
+        Some((picked_target, picked_color))
+            if picked_target == target && !ui.input(|input| input.pointer.any_down()) =>
+        {
+            *picked = None;
+            Some(picked_color)
+        }
+        _ => None,
+    }
+}
+
+// Breaks between classes, without the minimum and maximum values.
+fn inner_bounds(breaks: &rgis_layers::ClassBreaks) -> Vec<f64> {
+    let bounds = breaks.bounds();
+    bounds
+        .iter()
+        .skip(1)
+        .take(bounds.len().saturating_sub(2))
+        .copied()
+        .collect()
+}
+
+fn format_breaks(breaks: &[f64]) -> String {
+    breaks
+        .iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn parse_breaks(text: &str) -> Option<Vec<f64>> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse::<f64>().ok())
+        .collect()
+}
@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+
+/// The part of the map currently in view, and the CRS it's in.
+#[derive(bevy::ecs::system::SystemParam)]
+pub struct MapView<'w, 's> {
+    pub rgis_settings: Res<'w, rgis_settings::RgisSettings>,
+    camera_query: Query<'w, 's, &'static Transform, With<Camera>>,
+    windows: Query<'w, 's, &'static bevy::window::Window, With<bevy::window::PrimaryWindow>>,
+    ui_margins: crate::UiMargins<'w, 's>,
+}
+
+impl<'w, 's> MapView<'w, 's> {
+    pub fn projected_geo_rect(&self) -> Option<geo_projected::Projected<geo::Rect>> {
+        let transform = self.camera_query.get_single().ok()?;
+        let window = self.windows.get_single().ok()?;
+        Some(self.map_area(window).projected_geo_rect(transform, window))
+    }
+
+    pub fn size(&self) -> Option<rgis_units::ScreenSize> {
+        let window = self.windows.get_single().ok()?;
+        Some(self.map_area(window).size())
+    }
+
+    fn map_area<'a>(&self, window: &'a bevy::window::Window) -> rgis_units::MapArea<'a> {
+        rgis_units::MapArea {
+            window,
+            left_offset_px: self.ui_margins.left.0,
+            right_offset_px: 0.,
+            top_offset_px: self.ui_margins.top.0,
+            bottom_offset_px: self.ui_margins.bottom.0,
+        }
+    }
+}
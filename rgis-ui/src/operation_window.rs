@@ -1,14 +1,16 @@
 use bevy_egui::egui;
+use std::{error, mem, sync};
 
-pub(crate) struct OperationWindow<'w> {
-    pub bevy_egui_ctx: &'w mut bevy_egui::EguiContext,
-    pub state: &'w mut crate::OperationWindowState,
-    pub create_layer_event_writer: bevy::ecs::event::EventWriter<'w, rgis_events::CreateLayerEvent>,
-    pub render_message_event_writer:
-        bevy::ecs::event::EventWriter<'w, rgis_events::RenderMessageEvent>,
+pub(crate) struct OperationWindow<'a, 'w, 's> {
+    pub bevy_egui_ctx: &'a mut bevy_egui::EguiContext,
+    pub state: &'a mut crate::OperationWindowState,
+    pub job_spawner: &'a mut bevy_jobs::JobSpawner<'w, 's>,
+    pub layers: &'a rgis_layers::Layers,
+    pub view_rect: Option<geo_projected::Projected<geo::Rect>>,
+    pub rgis_settings: &'a mut rgis_settings::RgisSettings,
 }
 
-impl<'w> OperationWindow<'w> {
+impl<'a, 'w, 's> OperationWindow<'a, 'w, 's> {
     pub(crate) fn render(&mut self) {
         if !self.state.is_visible {
             self.state.operation = None;
@@ -19,24 +21,15 @@ impl<'w> OperationWindow<'w> {
         };
         match operation.next_action() {
             rgis_geo_ops::Action::Perform => {
-                // TODO: perform in background job
-                let outcome = operation.perform(self.state.feature_collection.clone());
-                match outcome {
-                    Ok(rgis_geo_ops::Outcome::FeatureCollection(feature_collection)) => {
-                        self.create_layer_event_writer
-                            .send(rgis_events::CreateLayerEvent {
-                                feature_collection,
-                                name: "FOOOOO".into(),      // FIXME
-                                source_crs_epsg_code: 4326, // FIXME
-                            });
-                    }
-                    Ok(rgis_geo_ops::Outcome::Text(text)) => {
-                        self.render_message_event_writer
-                            .send(rgis_events::RenderMessageEvent(text));
-                    }
-                    Err(e) => {
-                        bevy::log::error!("Encountered an error during the operation: {}", e);
-                    }
+                let other_layer = other_layer(&**operation, self.layers, self.state.other_layer_id);
+                if let Some(operation) = self.state.operation.take() {
+                    self.job_spawner.spawn(PerformOperationJob {
+                        operation,
+                        feature_collection: mem::take(&mut self.state.feature_collection),
+                        other_layer,
+                        name: self.state.name.clone(),
+                        crs_epsg_code: self.state.crs_epsg_code,
+                    });
                 }
                 self.state.is_visible = false;
             }
@@ -45,9 +38,292 @@ impl<'w> OperationWindow<'w> {
                     .open(&mut self.state.is_visible)
                     .anchor(egui::Align2::LEFT_TOP, [5., 5.])
                     .show(self.bevy_egui_ctx.get_mut(), |ui| {
-                        operation.ui(ui, &self.state.feature_collection);
+                        let needs_other_layer = operation.needs_other_layer();
+                        if needs_other_layer {
+                            ui.add(OtherLayerPicker {
+                                layers: self.layers,
+                                other_layer_id: &mut self.state.other_layer_id,
+                            });
+                            if self.state.other_layer_id.is_none() {
+                                ui.label("Pick the other layer");
+                            }
+                            ui.separator();
+                        }
+                        if operation.needs_map_rect() {
+                            ui.add(MapRectPicker {
+                                source: &mut self.state.map_rect_source,
+                                has_drawn_rect: self.state.drawn_rect.is_some(),
+                                current_tool: &mut self.rgis_settings.current_tool,
+                            });
+                            let map_rect = match self.state.map_rect_source {
+                                MapRectSource::View => self.view_rect,
+                                MapRectSource::Drawn => self.state.drawn_rect,
+                            };
+                            if map_rect.map(|rect| rect.0) != self.state.map_rect.map(|rect| rect.0)
+                            {
+                                self.state.map_rect = map_rect;
+                                operation.set_map_rect(map_rect.and_then(|rect| {
+                                    unproject_rect(
+                                        rect,
+                                        self.rgis_settings.target_crs_epsg_code,
+                                        self.state.crs_epsg_code,
+                                    )
+                                    .map_err(|e| {
+                                        bevy::log::error!(
+                                            "Could not reproject the rectangle: {}",
+                                            e
+                                        )
+                                    })
+                                    .ok()
+                                }));
+                            }
+                            ui.separator();
+                        }
+                        let has_inputs = !needs_other_layer || self.state.other_layer_id.is_some();
+                        ui.add_enabled_ui(has_inputs, |ui| {
+                            operation.ui(
+                                ui,
+                                &self.state.feature_collection,
+                                self.state.crs_epsg_code,
+                            );
+                        });
                     });
+                if let Some(preview) = operation.preview() {
+                    self.state.preview_id = self.state.preview_id.wrapping_add(1);
+                    self.job_spawner.spawn(PreviewOperationJob {
+                        operation: preview,
+                        feature_collection: self.state.feature_collection.clone(),
+                        other_layer: other_layer(
+                            &**operation,
+                            self.layers,
+                            self.state.other_layer_id,
+                        ),
+                        name: self.state.name.clone(),
+                        crs_epsg_code: self.state.crs_epsg_code,
+                        id: self.state.preview_id,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn other_layer(
+    operation: &dyn rgis_geo_ops::Operation,
+    layers: &rgis_layers::Layers,
+    other_layer_id: Option<rgis_layer_id::LayerId>,
+) -> Option<OtherLayer> {
+    if !operation.needs_other_layer() {
+        return None;
+    }
+    let layer = layers.get(other_layer_id?)?;
+    Some(OtherLayer {
+        feature_collection: layer.unprojected_feature_collection.clone(),
+        crs_epsg_code: layer.crs_epsg_code,
+    })
+}
+
+pub(crate) struct PerformOperationJob {
+    pub operation: Box<dyn Send + Sync + rgis_geo_ops::Operation>,
+    pub feature_collection: sync::Arc<geo_projected::Unprojected<geo_features::FeatureCollection>>,
+    pub other_layer: Option<OtherLayer>,
+    pub name: String,
+    pub crs_epsg_code: u16,
+}
+
+pub(crate) struct OtherLayer {
+    pub feature_collection: geo_projected::Unprojected<geo_features::FeatureCollection>,
+    pub crs_epsg_code: u16,
+}
+
+pub(crate) struct PerformOperationOutcome {
+    pub outcome: Result<rgis_geo_ops::Outcome, String>,
+    pub name: String,
+    pub crs_epsg_code: u16,
+}
+
+impl bevy_jobs::Job for PerformOperationJob {
+    type Outcome = PerformOperationOutcome;
+    const JOB_TYPE: bevy_jobs::JobType = bevy_jobs::JobType::Compute;
+
+    fn name(&self) -> String {
+        format!("Performing '{}'", self.name)
+    }
+
+    fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move {
+            let outcome = perform_operation(
+                self.operation,
+                self.feature_collection,
+                self.other_layer,
+                self.crs_epsg_code,
+            )
+            .map_err(|e| e.to_string());
+            PerformOperationOutcome {
+                outcome,
+                name: self.name,
+                crs_epsg_code: self.crs_epsg_code,
+            }
+        })
+    }
+}
+
+pub(crate) struct PreviewOperationJob {
+    pub operation: Box<dyn Send + Sync + rgis_geo_ops::Operation>,
+    pub feature_collection: sync::Arc<geo_projected::Unprojected<geo_features::FeatureCollection>>,
+    pub other_layer: Option<OtherLayer>,
+    pub name: String,
+    pub crs_epsg_code: u16,
+    pub id: u64,
+}
+
+pub(crate) struct PreviewOperationOutcome {
+    pub outcome: Result<rgis_geo_ops::Outcome, String>,
+    pub id: u64,
+}
+
+impl bevy_jobs::Job for PreviewOperationJob {
+    type Outcome = PreviewOperationOutcome;
+    const JOB_TYPE: bevy_jobs::JobType = bevy_jobs::JobType::Compute;
+
+    fn name(&self) -> String {
+        format!("Previewing '{}'", self.name)
+    }
+
+    fn perform(self, _: bevy_jobs::Context) -> bevy_jobs::AsyncReturn<Self::Outcome> {
+        Box::pin(async move {
+            let outcome = perform_operation(
+                self.operation,
+                self.feature_collection,
+                self.other_layer,
+                self.crs_epsg_code,
+            )
+            .map_err(|e| e.to_string());
+            PreviewOperationOutcome {
+                outcome,
+                id: self.id,
             }
+        })
+    }
+}
+
+fn perform_operation(
+    mut operation: Box<dyn Send + Sync + rgis_geo_ops::Operation>,
+    feature_collection: sync::Arc<geo_projected::Unprojected<geo_features::FeatureCollection>>,
+    other_layer: Option<OtherLayer>,
+    crs_epsg_code: u16,
+) -> Result<rgis_geo_ops::Outcome, Box<dyn error::Error>> {
+    if let Some(other_layer) = other_layer {
+        operation.set_other_layer(Some(reproject_layer(other_layer, crs_epsg_code)?));
+    }
+    // Copied if a preview job is still reading the features
+    let feature_collection =
+        sync::Arc::try_unwrap(feature_collection).unwrap_or_else(|shared| (*shared).clone());
+    operation.perform(feature_collection)
+}
+
+struct OtherLayerPicker<'a> {
+    layers: &'a rgis_layers::Layers,
+    other_layer_id: &'a mut Option<rgis_layer_id::LayerId>,
+}
+
+impl<'a> egui::Widget for OtherLayerPicker<'a> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let selected_text = self
+            .other_layer_id
+            .and_then(|layer_id| self.layers.get(layer_id))
+            .map_or("", |layer| layer.name.as_str());
+        egui::ComboBox::from_label("Other layer")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                for layer in self.layers.iter_top_to_bottom() {
+                    ui.selectable_value(self.other_layer_id, Some(layer.id), &layer.name);
+                }
+            })
+            .response
+    }
+}
+
+// Operations on two layers need both of them in the same CRS.
+fn reproject_layer(
+    layer: OtherLayer,
+    target_crs_epsg_code: u16,
+) -> Result<geo_projected::Unprojected<geo_features::FeatureCollection>, transform::Error> {
+    let mut feature_collection = layer.feature_collection;
+    if layer.crs_epsg_code == target_crs_epsg_code {
+        return Ok(feature_collection);
+    }
+    let transformer = transform::Transformer::setup(layer.crs_epsg_code, target_crs_epsg_code)?;
+    for feature in feature_collection.features_iter_mut() {
+        if let Some(ref mut geometry) = feature.0.geometry {
+            transformer.transform(geometry)?;
         }
+        feature.0.recalculate_bounding_rect();
+    }
+    feature_collection.0.recalculate_bounding_rect();
+    Ok(feature_collection)
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub(crate) enum MapRectSource {
+    #[default]
+    View,
+    Drawn,
+}
+
+struct MapRectPicker<'a> {
+    source: &'a mut MapRectSource,
+    has_drawn_rect: bool,
+    current_tool: &'a mut rgis_settings::Tool,
+}
+
+impl<'a> egui::Widget for MapRectPicker<'a> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        ui.vertical(|ui| {
+            ui.radio_value(self.source, MapRectSource::View, "Current view");
+            ui.horizontal(|ui| {
+                ui.radio_value(self.source, MapRectSource::Drawn, "Drawn rectangle");
+                if ui
+                    .add_enabled(
+                        *self.source == MapRectSource::Drawn,
+                        egui::Button::new("✏ Draw"),
+                    )
+                    .clicked()
+                {
+                    *self.current_tool = rgis_settings::Tool::DrawRect;
+                }
+            });
+            if *self.current_tool == rgis_settings::Tool::DrawRect {
+                ui.label("Drag a rectangle on the map");
+            } else if *self.source == MapRectSource::Drawn && !self.has_drawn_rect {
+                ui.label("No rectangle was drawn yet");
+            }
+        })
+        .response
+    }
+}
+
+// The edges of the rectangle curve in the CRS of the layer, so they're densified before being
+// reprojected.
+fn unproject_rect(
+    rect: geo_projected::Projected<geo::Rect>,
+    target_crs_epsg_code: u16,
+    layer_crs_epsg_code: u16,
+) -> Result<geo_projected::Unprojected<geo::Polygon>, Box<dyn std::error::Error>> {
+    use geo::Densify;
+
+    let rect = rect.0;
+    let max_segment_length = rect.width().max(rect.height()) / 16.;
+    let mut geometry = geo::Geometry::Polygon(if max_segment_length > 0. {
+        rect.to_polygon().densify(max_segment_length)
+    } else {
+        rect.to_polygon()
+    });
+    if target_crs_epsg_code != layer_crs_epsg_code {
+        transform::Transformer::setup(target_crs_epsg_code, layer_crs_epsg_code)?
+            .transform(&mut geometry)?;
     }
+    Ok(geo_projected::Unprojected::new(geo::Polygon::try_from(
+        geometry,
+    )?))
 }
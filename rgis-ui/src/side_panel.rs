@@ -13,11 +13,12 @@ pub struct Events<'w> {
     create_layer_event_writer: bevy::ecs::event::EventWriter<'w, rgis_events::CreateLayerEvent>,
     show_add_layer_window_event_writer:
         bevy::ecs::event::EventWriter<'w, rgis_events::ShowAddLayerWindow>,
-    render_message_event_writer: bevy::ecs::event::EventWriter<'w, rgis_events::RenderMessageEvent>,
     open_operation_window_event_writer:
         bevy::ecs::event::EventWriter<'w, crate::events::OpenOperationWindowEvent>,
     show_manage_layer_window_event_writer:
         bevy::ecs::event::EventWriter<'w, rgis_events::ShowManageLayerWindowEvent>,
+    show_export_layer_window_event_writer:
+        bevy::ecs::event::EventWriter<'w, rgis_events::ShowExportLayerWindowEvent>,
 }
 
 pub(crate) struct SidePanel<'a, 'w> {
@@ -90,42 +91,15 @@ impl<'a, 'w, Op: rgis_geo_ops::OperationEntry> egui::Widget for OperationButton<
             egui::Button::new(Op::NAME),
         );
         if button.clicked() {
-            let mut operation = Op::build();
-            match operation.next_action() {
-                rgis_geo_ops::Action::RenderUi => {
-                    self.events.open_operation_window_event_writer.send(
-                        crate::events::OpenOperationWindowEvent {
-                            operation,
-                            feature_collection: self.layer.unprojected_feature_collection.clone(), // TODO: clone?
-                        },
-                    );
-                }
-                rgis_geo_ops::Action::Perform => {
-                    // TODO: perform in background job
-                    let outcome =
-                        operation.perform(self.layer.unprojected_feature_collection.clone()); // TODO: clone?
-
-                    match outcome {
-                        Ok(rgis_geo_ops::Outcome::FeatureCollection(feature_collection)) => {
-                            self.events.create_layer_event_writer.send(
-                                rgis_events::CreateLayerEvent {
-                                    feature_collection,
-                                    name: Op::NAME.into(),
-                                    source_crs_epsg_code: self.layer.crs_epsg_code,
-                                },
-                            );
-                        }
-                        Ok(rgis_geo_ops::Outcome::Text(text)) => {
-                            self.events
-                                .render_message_event_writer
-                                .send(rgis_events::RenderMessageEvent(text));
-                        }
-                        Err(e) => {
-                            bevy::log::error!("Encountered an error during the operation: {}", e);
-                        }
-                    }
-                }
-            }
+            // Operations without a UI are performed by the operation window right away
+            self.events.open_operation_window_event_writer.send(
+                crate::events::OpenOperationWindowEvent {
+                    operation: Op::build(),
+                    feature_collection: self.layer.unprojected_feature_collection.clone(), // TODO: clone?
+                    name: Op::NAME.into(),
+                    crs_epsg_code: self.layer.crs_epsg_code,
+                },
+            );
         }
         button
     }
@@ -207,6 +181,12 @@ impl<'a, 'w> Widget for Layer<'a, 'w> {
                             .send(rgis_events::CenterCameraEvent(layer.id));
                     }
 
+                    if ui.button("💾 Save layer as…").clicked() {
+                        self.events
+                            .show_export_layer_window_event_writer
+                            .send(rgis_events::ShowExportLayerWindowEvent(layer.id));
+                    }
+
                     if ui.button("❌ Remove").clicked() {
                         self.delete_layer(layer);
                     }
@@ -314,14 +294,30 @@ impl<'a, 'w> egui::Widget for OperationsWidget<'a, 'w> {
                 }
             }
 
+            ui.add(OperationButton::<rgis_geo_ops::Buffer>::new(
+                self.events,
+                self.layer,
+            ));
+            ui.add(OperationButton::<rgis_geo_ops::Clip>::new(
+                self.events,
+                self.layer,
+            ));
             ui.add(OperationButton::<rgis_geo_ops::ConvexHull>::new(
                 self.events,
                 self.layer,
             ));
+            ui.add(OperationButton::<rgis_geo_ops::Dissolve>::new(
+                self.events,
+                self.layer,
+            ));
             ui.add(OperationButton::<rgis_geo_ops::Outliers>::new(
                 self.events,
                 self.layer,
             ));
+            ui.add(OperationButton::<rgis_geo_ops::Overlay>::new(
+                self.events,
+                self.layer,
+            ));
             ui.add(OperationButton::<rgis_geo_ops::Rotate>::new(
                 self.events,
                 self.layer,
@@ -4,6 +4,7 @@ use bevy_egui::{
     egui::{self, Widget},
     EguiContext,
 };
+use std::sync;
 
 fn render_bottom_panel(
     mut egui_ctx_query: Query<&mut EguiContext, With<PrimaryWindow>>,
@@ -59,11 +60,38 @@ fn handle_open_file_job(
     }
 }
 
+fn handle_perform_operation_job(
+    mut finished_jobs: bevy_jobs::FinishedJobs,
+    mut create_layer_event_writer: EventWriter<rgis_events::CreateLayerEvent>,
+    mut render_message_event_writer: EventWriter<rgis_events::RenderMessageEvent>,
+) {
+    while let Some(outcome) =
+        finished_jobs.take_next::<crate::operation_window::PerformOperationJob>()
+    {
+        match outcome.outcome {
+            Ok(rgis_geo_ops::Outcome::FeatureCollection(feature_collection)) => {
+                create_layer_event_writer.send(rgis_events::CreateLayerEvent {
+                    feature_collection,
+                    name: outcome.name,
+                    source_crs_epsg_code: outcome.crs_epsg_code,
+                });
+            }
+            Ok(rgis_geo_ops::Outcome::Text(text)) => {
+                render_message_event_writer.send(rgis_events::RenderMessageEvent(text));
+            }
+            Err(e) => {
+                bevy::log::error!("Encountered an error during the operation: {}", e);
+            }
+        }
+    }
+}
+
 fn render_manage_layer_window(
     mut state: Local<crate::ManageLayerWindowState>,
     mut egui_ctx_query: Query<&mut EguiContext, With<PrimaryWindow>>,
     layers: Res<rgis_layers::Layers>,
     mut color_events: ResMut<bevy::ecs::event::Events<rgis_events::UpdateLayerColorEvent>>,
+    mut style_events: ResMut<bevy::ecs::event::Events<rgis_layers::UpdateLayerStyleEvent>>,
     mut show_manage_layer_window_event_reader: bevy::ecs::event::EventReader<
         rgis_events::ShowManageLayerWindowEvent,
     >,
@@ -82,6 +110,56 @@ fn render_manage_layer_window(
         layers: &layers,
         bevy_egui_ctx: &mut egui_ctx,
         color_events: &mut color_events,
+        style_events: &mut style_events,
+    }
+    .render();
+}
+
+fn render_export_layer_window(
+    mut state: Local<crate::ExportLayerWindowState>,
+    mut egui_ctx_query: Query<&mut EguiContext, With<PrimaryWindow>>,
+    layers: Res<rgis_layers::Layers>,
+    rgis_settings: Res<rgis_settings::RgisSettings>,
+    mut export_layer_event_writer: EventWriter<rgis_events::ExportLayerEvent>,
+    mut show_export_layer_window_event_reader: bevy::ecs::event::EventReader<
+        rgis_events::ShowExportLayerWindowEvent,
+    >,
+) {
+    let Ok(mut egui_ctx) = egui_ctx_query.get_single_mut() else {
+        return;
+    };
+
+    if let Some(event) = show_export_layer_window_event_reader.read().last() {
+        state.is_visible = true;
+        state.layer_id = Some(event.0);
+    }
+
+    crate::export_layer_window::ExportLayerWindow {
+        state: &mut state,
+        layers: &layers,
+        bevy_egui_ctx: &mut egui_ctx,
+        export_layer_event_writer: &mut export_layer_event_writer,
+        target_crs_epsg_code: rgis_settings.target_crs_epsg_code,
+    }
+    .render();
+}
+
+fn render_export_map_window(
+    mut state: ResMut<crate::ExportMapWindowState>,
+    mut egui_ctx_query: Query<&mut EguiContext, With<PrimaryWindow>>,
+    mut export_map_svg_event_writer: EventWriter<rgis_events::ExportMapSvgEvent>,
+    map_view: crate::map_view::MapView,
+) {
+    let Ok(mut egui_ctx) = egui_ctx_query.get_single_mut() else {
+        return;
+    };
+
+    crate::export_map_window::ExportMapWindow {
+        state: &mut state,
+        bevy_egui_ctx: &mut egui_ctx,
+        export_map_svg_event_writer: &mut export_map_svg_event_writer,
+        view_extent: map_view.projected_geo_rect(),
+        map_size: map_view.size(),
     }
     .render();
 }
@@ -101,6 +179,7 @@ fn render_add_layer_window(
     mut job_spawner: bevy_jobs::JobSpawner,
     mut state: Local<crate::add_layer_window::State>,
     mut events: crate::add_layer_window::Events,
+    map_view: crate::map_view::MapView,
 ) {
     let Ok(mut egui_ctx) = egui_ctx_query.get_single_mut() else {
         return;
@@ -122,6 +201,8 @@ fn render_add_layer_window(
         bevy_egui_ctx: &mut egui_ctx,
         job_spawner: &mut job_spawner,
         events: &mut events,
+        view_extent: map_view.projected_geo_rect(),
+        target_crs_epsg_code: map_view.rgis_settings.target_crs_epsg_code,
     }
     .render();
 }
@@ -200,17 +281,52 @@ fn render_message_window(
     .render();
 }
 
+/// The rectangles on the map that operations can take as input.
+#[derive(bevy::ecs::system::SystemParam)]
+struct MapRects<'w, 's> {
+    map_view_and_settings: ParamSet<
+        'w,
+        's,
+        (
+            crate::map_view::MapView<'w, 's>,
+            ResMut<'w, rgis_settings::RgisSettings>,
+        ),
+    >,
+    map_rect_drawn_event_reader: EventReader<'w, 's, rgis_events::MapRectDrawnEvent>,
+}
+
 fn render_operation_window(
     mut state: Local<crate::OperationWindowState>,
     mut events: ResMut<Events<crate::events::OpenOperationWindowEvent>>,
     mut egui_ctx_query: Query<&mut EguiContext, With<PrimaryWindow>>,
-    create_layer_event_writer: EventWriter<rgis_events::CreateLayerEvent>,
-    render_message_event_writer: EventWriter<rgis_events::RenderMessageEvent>,
+    mut job_spawner: bevy_jobs::JobSpawner,
+    mut finished_jobs: bevy_jobs::FinishedJobs,
+    layers: Res<rgis_layers::Layers>,
+    mut map_rects: MapRects,
 ) {
+    if let Some(event) = map_rects.map_rect_drawn_event_reader.read().last() {
+        state.drawn_rect = Some(event.0);
+    }
     if let Some(event) = events.drain().last() {
         state.is_visible = true;
         state.operation = Some(event.operation);
-        state.feature_collection = event.feature_collection; // Should this be `Some()`? Otherwise we'll always have something stored
+        state.feature_collection = sync::Arc::new(event.feature_collection); // Should this be `Some()`? Otherwise we'll always have something stored
+        state.name = event.name;
+        state.crs_epsg_code = event.crs_epsg_code;
+        state.other_layer_id = None;
+        state.map_rect = None;
+        // Previews of the previous operation are left out
+        state.preview_id = state.preview_id.wrapping_add(1);
+    }
+    while let Some(outcome) =
+        finished_jobs.take_next::<crate::operation_window::PreviewOperationJob>()
+    {
+        if outcome.id != state.preview_id {
+            continue;
+        }
+        if let Some(ref mut operation) = state.operation {
+            operation.set_preview(outcome.outcome);
+        }
     }
 
     let Ok(mut egui_ctx) = egui_ctx_query.get_single_mut() else {
@@ -220,12 +336,49 @@ fn render_operation_window(
     crate::operation_window::OperationWindow {
         bevy_egui_ctx: &mut egui_ctx,
         state: &mut state,
-        create_layer_event_writer,
-        render_message_event_writer,
+        job_spawner: &mut job_spawner,
+        layers: &layers,
+        view_rect: map_rects.map_view_and_settings.p0().projected_geo_rect(),
+        rgis_settings: &mut map_rects.map_view_and_settings.p1(),
     }
     .render();
 }
 
+// Outline of the rectangle being drawn with the `DrawRect` tool
+fn render_rect_drag(
+    rect_drag_start: Res<rgis_mouse::RectDragStart>,
+    windows: Query<&bevy::window::Window, With<PrimaryWindow>>,
+    mut egui_ctx_query: Query<&mut EguiContext, With<PrimaryWindow>>,
+) {
+    let Some((start, _)) = rect_drag_start.0 else {
+        return;
+    };
+    let Some(cursor_position) = windows
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position())
+    else {
+        return;
+    };
+    let Ok(mut egui_ctx) = egui_ctx_query.get_single_mut() else {
+        return;
+    };
+    let ctx = egui_ctx.get_mut();
+    let stroke = ctx.style().visuals.selection.stroke;
+    ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("rect_drag"),
+    ))
+    .rect_stroke(
+        egui::Rect::from_two_pos(
+            egui::pos2(start.x, start.y),
+            egui::pos2(cursor_position.x, cursor_position.y),
+        ),
+        0.,
+        stroke,
+    );
+}
+
 fn render_in_progress(
     query: Query<&bevy_jobs::InProgressJob>,
     mut egui_ctx_query: Query<&mut EguiContext, With<PrimaryWindow>>,
@@ -278,15 +431,23 @@ impl<'a> Widget for InProgressJobWidget<'a> {
     }
 }
 
+/// Windows which are opened from the menus of the top panel.
+#[derive(bevy::ecs::system::SystemParam)]
+struct MenuWindows<'w> {
+    is_debug_window_open:
+        ResMut<'w, crate::IsWindowOpen<crate::debug_window::DebugWindow<'static, 'static>>>,
+    is_legend_window_open:
+        ResMut<'w, crate::IsWindowOpen<crate::legend_window::LegendWindow<'static, 'static>>>,
+    export_map_window_state: ResMut<'w, crate::ExportMapWindowState>,
+}
+
 fn render_top_panel(
     mut egui_ctx_query: Query<&mut EguiContext, With<PrimaryWindow>>,
     mut app_exit_events: ResMut<bevy::ecs::event::Events<bevy::app::AppExit>>,
     mut windows: Query<&mut bevy::window::Window, With<PrimaryWindow>>,
     mut app_settings: ResMut<rgis_settings::RgisSettings>,
     mut top_panel_height: ResMut<crate::TopPanelHeight>,
-    mut is_debug_window_open: ResMut<
-        crate::IsWindowOpen<crate::debug_window::DebugWindow<'static, 'static>>,
-    >,
+    mut menu_windows: MenuWindows,
 ) {
     let Ok(mut window) = windows.get_single_mut() else {
         return;
@@ -302,7 +463,9 @@ fn render_top_panel(
         window: &mut window,
         app_settings: &mut app_settings,
         top_panel_height: &mut top_panel_height,
-        is_debug_window_open: &mut is_debug_window_open,
+        is_debug_window_open: &mut menu_windows.is_debug_window_open,
+        is_legend_window_open: &mut menu_windows.is_legend_window_open,
+        export_map_window_state: &mut menu_windows.export_map_window_state,
     }
     .render();
 }
@@ -360,16 +523,23 @@ pub fn configure(app: &mut App) {
             render_side_panel.in_set(RenderSystemSet::SideBarProgressBar),
             render_in_progress.in_set(RenderSystemSet::SideBarProgressBar),
             handle_open_file_job,
+            handle_perform_operation_job,
             render_manage_layer_window.in_set(RenderSystemSet::Windows),
+            render_export_layer_window.in_set(RenderSystemSet::Windows),
+            render_export_map_window.in_set(RenderSystemSet::Windows),
             render_add_layer_window.in_set(RenderSystemSet::Windows),
             render_change_crs_window.in_set(RenderSystemSet::Windows),
             render_feature_properties_window.in_set(RenderSystemSet::Windows),
             render_operation_window.in_set(RenderSystemSet::Windows),
+            render_rect_drag.in_set(RenderSystemSet::Windows),
         ),
     );
 
     app.insert_resource(crate::IsWindowOpen::<crate::debug_window::DebugWindow>::closed());
     app.add_systems(Update, render_window::<crate::debug_window::DebugWindow>);
+
+    app.insert_resource(crate::IsWindowOpen::<crate::legend_window::LegendWindow>::closed());
+    app.add_systems(Update, render_window::<crate::legend_window::LegendWindow>);
 }
 
 fn render_window<W: Window + 'static>(
@@ -8,6 +8,9 @@ pub(crate) struct TopPanel<'a, 'w, 's> {
     pub app_settings: &'a mut rgis_settings::RgisSettings,
     pub top_panel_height: &'a mut crate::TopPanelHeight,
     pub is_debug_window_open: &'a mut crate::IsWindowOpen<crate::debug_window::DebugWindow<'w, 's>>,
+    pub is_legend_window_open:
+        &'a mut crate::IsWindowOpen<crate::legend_window::LegendWindow<'w, 's>>,
+    pub export_map_window_state: &'a mut crate::ExportMapWindowState,
 }
 
 impl<'a, 'w, 's> TopPanel<'a, 'w, 's> {
@@ -19,6 +22,9 @@ impl<'a, 'w, 's> TopPanel<'a, 'w, 's> {
 
                     ui.label("rgis");
                     ui.menu_button("File", |ui| {
+                        if ui.button("Export map as SVG…").clicked() {
+                            self.export_map_window_state.is_visible = true;
+                        }
                         ui.add(ExitButton {
                             app_exit_events: self.app_exit_events,
                         });
@@ -27,6 +33,7 @@ impl<'a, 'w, 's> TopPanel<'a, 'w, 's> {
                         ui.add(FullScreenButton {
                             window: self.window,
                         });
+                        ui.checkbox(&mut self.is_legend_window_open.0, "Legend");
                     });
                     ui.menu_button("Help", |ui| {
                         if ui.button("Debug stats").clicked() {
@@ -12,6 +12,20 @@ use wasm_bindgen::prelude::*;
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
 pub fn run() {
+    // Parsed before building the app, as rendering from the command line doesn't open a window
+    #[cfg(not(target_arch = "wasm32"))]
+    let cli_values = match rgis_cli::run() {
+        Ok(rgis_cli::Mode::Viewer(values)) => values,
+        Ok(rgis_cli::Mode::Render(args)) => {
+            if let Err(e) = rgis_cli::render(args) {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Err(_) => return,
+    };
+
     let mut app = App::new();
 
     app.add_plugins(MinimalPlugins);
@@ -36,6 +50,7 @@ pub fn run() {
     app.add_plugins(rgis_ui::Plugin);
     app.add_plugins(rgis_layers::Plugin);
     app.add_plugins(rgis_file_loader::Plugin);
+    app.add_plugins(rgis_file_exporter::Plugin);
     app.add_plugins(rgis_renderer::Plugin);
     app.add_plugins(rgis_mouse::Plugin);
     app.add_plugins(rgis_keyboard::Plugin);
@@ -50,11 +65,6 @@ pub fn run() {
 
     #[cfg(not(target_arch = "wasm32"))]
     {
-        let cli_values = if let Ok(c) = rgis_cli::run() {
-            c
-        } else {
-            return;
-        };
         let msaa = match cli_values.msaa_sample_count {
             1 => Msaa::Off,
             2 => Msaa::Sample2,
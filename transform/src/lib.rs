@@ -71,6 +71,50 @@ pub fn lookup_epsg_code(epsg_code: u16) -> Result<(geodesy::Minimal, geodesy::Op
     Ok((ctx, op_handle))
 }
 
+/// Units of the coordinates of a CRS.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CrsUnits {
+    Degrees,
+    Linear { meters_per_unit: f64 },
+}
+
+/// Units of the coordinates of the CRS, as listed in its PROJ definition. `None` when the CRS or its
+/// units are unknown.
+pub fn crs_units(epsg_code: u16) -> Option<CrsUnits> {
+    let proj4 = crs_definitions::from_code(epsg_code)?.proj4;
+    let mut params = proj4.split_whitespace();
+    if params.clone().any(|param| param == "+proj=longlat") {
+        return Some(CrsUnits::Degrees);
+    }
+    params.find_map(|param| {
+        let meters_per_unit = match param.split_once('=')? {
+            ("+units", "m") => 1.,
+            ("+units", "km") => 1000.,
+            ("+units", "ft") => 0.3048,
+            ("+units", "us-ft") => 1200. / 3937.,
+            ("+to_meter", to_meter) => to_meter.parse().ok()?,
+            _ => return None,
+        };
+        Some(CrsUnits::Linear { meters_per_unit })
+    })
+}
+
+/// Whether distances measured in the CRS are true to the ground up to a small scale factor, as in
+/// the conformal projections of local grids like UTM zones or state planes. Mercator is conformal,
+/// but its scale grows without bound towards the poles.
+pub fn has_local_scale(epsg_code: u16) -> bool {
+    const LOCAL_CONFORMAL_PROJECTIONS: [&str; 8] = [
+        "tmerc", "etmerc", "utm", "lcc", "stere", "sterea", "omerc", "somerc",
+    ];
+    crs_definitions::from_code(epsg_code).is_some_and(|def| {
+        def.proj4.split_whitespace().any(|param| {
+            param
+                .strip_prefix("+proj=")
+                .is_some_and(|proj| LOCAL_CONFORMAL_PROJECTIONS.contains(&proj))
+        })
+    })
+}
+
 fn geodesy_ctx() -> geodesy::Minimal {
     geodesy::Minimal::new()
 }